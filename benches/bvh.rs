@@ -0,0 +1,86 @@
+// BVH与线性遍历的求交性能对比，运行方式：cargo bench --bench bvh
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use render::bvh::BvhSplit;
+use render::color::Color;
+use render::hittable::Hittable;
+use render::lambertian::Lambertian;
+use render::ray::Ray;
+use render::sphere::Sphere;
+use render::utils::{random_f64_range, Sampler};
+use render::vector::{Point3, Vector3};
+use render::world::World;
+
+const RAYS: usize = 10_000;
+
+fn main() {
+    for &count in &[500, 5_000, 50_000] {
+        let mut sampler = Sampler::new(count as u64);
+        let spheres = create_spheres(count, &mut sampler);
+        let rays = create_rays(RAYS, &mut sampler);
+
+        let linear = create_world(&spheres);
+        let linear_time = trace(&linear, &rays);
+
+        println!("{} spheres, {} rays", count, RAYS);
+        println!("  linear       : {:>10.2?}", linear_time);
+
+        for split in [BvhSplit::Median, BvhSplit::Sah] {
+            let mut world = create_world(&spheres);
+
+            let start = Instant::now();
+            world.build_bvh(0.0, 1.0, split);
+            let build_time = start.elapsed();
+
+            let trace_time = trace(&world, &rays);
+            println!("  bvh {:<9}: {:>10.2?} (build {:.2?}, {:.1}x faster)",
+                format!("{:?}", split), trace_time, build_time,
+                linear_time.as_secs_f64() / trace_time.as_secs_f64());
+        }
+    }
+}
+
+// 在[-50, 50]^3的立方体内随机放置count个小球，返回球心和半径
+fn create_spheres(count: usize, sampler: &mut Sampler) -> Vec<(Point3, f64)> {
+    (0..count)
+        .map(|_| (Vector3::random(-50.0, 50.0, sampler), random_f64_range(0.1, 1.0, sampler)))
+        .collect()
+}
+
+// 每种求交方式都用同一组小球建立场景，保证比较公平
+fn create_world(spheres: &[(Point3, f64)]) -> World {
+    let mut world = World::new();
+    let material = Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)));
+
+    for &(center, radius) in spheres {
+        world.add(Box::new(Sphere::new(center, radius, material.clone())));
+    }
+
+    world
+}
+
+// 从立方体外的一点射向立方体内随机位置的光线
+fn create_rays(count: usize, sampler: &mut Sampler) -> Vec<Ray> {
+    let origin = Point3::new(0.0, 0.0, 120.0);
+
+    (0..count)
+        .map(|_| Ray::new(origin, Vector3::random(-50.0, 50.0, sampler) - origin, random_f64_range(0.0, 1.0, sampler)))
+        .collect()
+}
+
+fn trace(world: &World, rays: &[Ray]) -> Duration {
+    let start = Instant::now();
+    let mut hits = 0;
+    let mut sampler = Sampler::new(0);
+
+    for ray in rays {
+        if world.hit(ray, 0.001, f64::INFINITY, &mut sampler).is_some() {
+            hits += 1;
+        }
+    }
+
+    std::hint::black_box(hits);
+    start.elapsed()
+}
@@ -0,0 +1,87 @@
+use crate::ray::Ray;
+use crate::vector::Point3;
+
+//包围盒的最小厚度，平行于坐标平面的矩形、三角形的包围盒在该方向上厚度为0，在BVH的slab测试中可能被漏掉
+const THICKNESS: f64 = 0.0001;
+
+//轴对齐包围盒（Axis-Aligned Bounding Box），用于快速排除不可能与光线相交的物体
+#[derive(Debug, Copy, Clone)]
+pub struct Aabb {
+    pub minimum: Point3,
+    pub maximum: Point3,
+}
+
+impl Aabb {
+    pub fn new(minimum: Point3, maximum: Point3) -> Self {
+        Aabb { minimum, maximum }
+    }
+
+    //slab算法：分别求光线在x、y、z三个方向上进入和离开包围盒的t区间，三个区间有交集则相交
+    pub fn hit(&self, r: &Ray, t_min: f64, t_max: f64) -> bool {
+        self.intersect(r, t_min, t_max).is_some()
+    }
+
+    //光线在[t_min, t_max]内位于包围盒中的t区间，不相交时返回None
+    pub fn intersect(&self, r: &Ray, t_min: f64, t_max: f64) -> Option<(f64, f64)> {
+        let mut t_min = t_min;
+        let mut t_max = t_max;
+
+        for axis in 0..3 {
+            let inv_d = 1.0 / r.dir[axis];
+            let mut t0 = (self.minimum[axis] - r.orig[axis]) * inv_d;
+            let mut t1 = (self.maximum[axis] - r.orig[axis]) * inv_d;
+            if inv_d < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+            if t_max <= t_min {
+                return None;
+            }
+        }
+
+        Some((t_min, t_max))
+    }
+
+    //同时包含两个包围盒的最小包围盒
+    pub fn surrounding(&self, other: &Aabb) -> Aabb {
+        Aabb::new(
+            Point3::new(
+                self.minimum.x.min(other.minimum.x),
+                self.minimum.y.min(other.minimum.y),
+                self.minimum.z.min(other.minimum.z),
+            ),
+            Point3::new(
+                self.maximum.x.max(other.maximum.x),
+                self.maximum.y.max(other.maximum.y),
+                self.maximum.z.max(other.maximum.z),
+            ),
+        )
+    }
+
+    //把厚度不足THICKNESS的方向向两侧撑开到THICKNESS
+    pub fn pad(&self) -> Aabb {
+        let mut minimum = self.minimum;
+        let mut maximum = self.maximum;
+        let pad = |min: &mut f64, max: &mut f64| {
+            if *max - *min < THICKNESS {
+                *min -= 0.5 * THICKNESS;
+                *max += 0.5 * THICKNESS;
+            }
+        };
+        pad(&mut minimum.x, &mut maximum.x);
+        pad(&mut minimum.y, &mut maximum.y);
+        pad(&mut minimum.z, &mut maximum.z);
+        Aabb::new(minimum, maximum)
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        (self.minimum + self.maximum) * 0.5
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let d = self.maximum - self.minimum;
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+}
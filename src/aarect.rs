@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::hittable::{area_pdf_value, HitInfo, Hittable};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::utils::{random_f64_range, Sampler};
+use crate::vector::{Point3, Vector3};
+
+//平行于XY平面的矩形：z = k，x在[x0, x1]、y在[y0, y1]之间，外法线指向+z
+pub struct XyRect {
+    pub x0: f64,
+    pub x1: f64,
+    pub y0: f64,
+    pub y1: f64,
+    pub k: f64,
+    pub material: Arc<dyn Material>,
+}
+
+//平行于XZ平面的矩形：y = k，x在[x0, x1]、z在[z0, z1]之间，外法线指向+y
+pub struct XzRect {
+    pub x0: f64,
+    pub x1: f64,
+    pub z0: f64,
+    pub z1: f64,
+    pub k: f64,
+    pub material: Arc<dyn Material>,
+}
+
+//平行于YZ平面的矩形：x = k，y在[y0, y1]、z在[z0, z1]之间，外法线指向+x
+pub struct YzRect {
+    pub y0: f64,
+    pub y1: f64,
+    pub z0: f64,
+    pub z1: f64,
+    pub k: f64,
+    pub material: Arc<dyn Material>,
+}
+
+impl XyRect {
+    pub fn new(x0: f64, x1: f64, y0: f64, y1: f64, k: f64, material: Arc<dyn Material>) -> Self {
+        XyRect { x0, x1, y0, y1, k, material }
+    }
+}
+
+impl XzRect {
+    pub fn new(x0: f64, x1: f64, z0: f64, z1: f64, k: f64, material: Arc<dyn Material>) -> Self {
+        XzRect { x0, x1, z0, z1, k, material }
+    }
+}
+
+impl YzRect {
+    pub fn new(y0: f64, y1: f64, z0: f64, z1: f64, k: f64, material: Arc<dyn Material>) -> Self {
+        YzRect { y0, y1, z0, z1, k, material }
+    }
+}
+
+//三种矩形共用的求交：矩形位于第axis个坐标等于k的平面上，另外两个坐标a、b分别在[a0, a1]、[b0, b1]之间
+//u、v分别是a、b方向上归一化到[0, 1]的坐标
+fn hit_rect<'a>(r: &Ray, t_min: f64, t_max: f64, (a, b, axis): (usize, usize, usize),
+    (a0, a1, b0, b1, k): (f64, f64, f64, f64, f64), material: &'a dyn Material) -> Option<HitInfo<'a>> {
+    //光线与平面平行时不相交
+    if r.dir[axis] == 0.0 {
+        return None;
+    }
+
+    let t = (k - r.orig[axis]) / r.dir[axis];
+    if t < t_min || t > t_max {
+        return None;
+    }
+
+    let pos = r.at(t);
+    if pos[a] < a0 || pos[a] > a1 || pos[b] < b0 || pos[b] > b1 {
+        return None;
+    }
+
+    let mut outward_normal = Vector3::new(0.0, 0.0, 0.0);
+    match axis {
+        0 => outward_normal.x = 1.0,
+        1 => outward_normal.y = 1.0,
+        _ => outward_normal.z = 1.0,
+    }
+
+    let u = (pos[a] - a0) / (a1 - a0);
+    let v = (pos[b] - b0) / (b1 - b0);
+    Some(HitInfo::new(r, pos, &outward_normal, t, u, v, material))
+}
+
+impl Hittable for XyRect {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, _sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        hit_rect(r, t_min, t_max, (0, 1, 2), (self.x0, self.x1, self.y0, self.y1, self.k), &*self.material)
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(Aabb::new(Point3::new(self.x0, self.y0, self.k), Point3::new(self.x1, self.y1, self.k)).pad())
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3, sampler: &mut Sampler) -> f64 {
+        let area = (self.x1 - self.x0) * (self.y1 - self.y0);
+        area_pdf_value(self, area, origin, direction, sampler)
+    }
+
+    fn random(&self, origin: &Point3, sampler: &mut Sampler) -> Vector3 {
+        let a = random_f64_range(self.x0, self.x1, sampler);
+        let b = random_f64_range(self.y0, self.y1, sampler);
+        Point3::new(a, b, self.k) - *origin
+    }
+}
+
+impl Hittable for XzRect {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, _sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        hit_rect(r, t_min, t_max, (0, 2, 1), (self.x0, self.x1, self.z0, self.z1, self.k), &*self.material)
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(Aabb::new(Point3::new(self.x0, self.k, self.z0), Point3::new(self.x1, self.k, self.z1)).pad())
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3, sampler: &mut Sampler) -> f64 {
+        let area = (self.x1 - self.x0) * (self.z1 - self.z0);
+        area_pdf_value(self, area, origin, direction, sampler)
+    }
+
+    fn random(&self, origin: &Point3, sampler: &mut Sampler) -> Vector3 {
+        let a = random_f64_range(self.x0, self.x1, sampler);
+        let b = random_f64_range(self.z0, self.z1, sampler);
+        Point3::new(a, self.k, b) - *origin
+    }
+}
+
+impl Hittable for YzRect {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, _sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        hit_rect(r, t_min, t_max, (1, 2, 0), (self.y0, self.y1, self.z0, self.z1, self.k), &*self.material)
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(Aabb::new(Point3::new(self.k, self.y0, self.z0), Point3::new(self.k, self.y1, self.z1)).pad())
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3, sampler: &mut Sampler) -> f64 {
+        let area = (self.y1 - self.y0) * (self.z1 - self.z0);
+        area_pdf_value(self, area, origin, direction, sampler)
+    }
+
+    fn random(&self, origin: &Point3, sampler: &mut Sampler) -> Vector3 {
+        let a = random_f64_range(self.y0, self.y1, sampler);
+        let b = random_f64_range(self.z0, self.z1, sampler);
+        Point3::new(self.k, a, b) - *origin
+    }
+}
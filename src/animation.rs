@@ -0,0 +1,139 @@
+use crate::aabb::Aabb;
+use crate::matrix::Transform;
+use crate::quaternion::Quaternion;
+use crate::vector::{Point3, Vector3};
+
+//计算运动包围盒时，相邻两个采样时刻之间最多转过的角度（弧度）
+const MAX_STEP_ANGLE: f64 = 0.035;
+
+//某一时刻的平移、旋转和缩放，作用顺序为先缩放、再旋转、最后平移
+#[derive(Debug, Copy, Clone)]
+pub struct Keyframe {
+    pub time: f64,
+    pub translation: Vector3,
+    pub rotation: Quaternion,
+    pub scale: Vector3,
+}
+
+impl Keyframe {
+    pub fn new(time: f64, translation: Vector3, rotation: Quaternion, scale: Vector3) -> Self {
+        Keyframe { time, translation, rotation, scale }
+    }
+
+    fn transform(&self) -> Transform {
+        Transform::scale(&self.scale)
+            .then(&self.rotation.to_transform())
+            .then(&Transform::translate(&self.translation))
+    }
+}
+
+//随时间变化的变换：在关键帧之间对平移和缩放做线性插值，对旋转做球面线性插值
+//第一个关键帧之前和最后一个关键帧之后保持不动
+//旋转插值走最短路径，相邻两个关键帧之间转过超过180度时需要插入中间的关键帧
+#[derive(Debug, Clone)]
+pub struct AnimatedTransform {
+    keyframes: Vec<Keyframe>,
+}
+
+impl AnimatedTransform {
+    //关键帧按时间排序；至少要有一个关键帧，时间不能重复，缩放系数必须为正数（插值过程中不能经过0）
+    pub fn new(mut keyframes: Vec<Keyframe>) -> Result<Self, String> {
+        if keyframes.is_empty() {
+            return Err("an animation needs at least one keyframe".to_string());
+        }
+
+        for key in keyframes.iter_mut() {
+            if key.scale.x <= 0.0 || key.scale.y <= 0.0 || key.scale.z <= 0.0 {
+                return Err(format!("keyframe at time {}: scale factors must be positive", key.time));
+            }
+            key.rotation = key.rotation.normalize();
+        }
+
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        if let Some(pair) = keyframes.windows(2).find(|pair| pair[0].time == pair[1].time) {
+            return Err(format!("more than one keyframe at time {}", pair[0].time));
+        }
+
+        Ok(AnimatedTransform { keyframes })
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    //time时刻的关键帧（插值结果）
+    fn interpolate(&self, time: f64) -> Keyframe {
+        let first = &self.keyframes[0];
+        let last = &self.keyframes[self.keyframes.len() - 1];
+        if time <= first.time {
+            return *first;
+        }
+        if time >= last.time {
+            return *last;
+        }
+
+        //第一个时间大于time的关键帧
+        let next = self.keyframes.partition_point(|key| key.time <= time);
+        let (a, b) = (&self.keyframes[next - 1], &self.keyframes[next]);
+        let s = (time - a.time) / (b.time - a.time);
+
+        Keyframe::new(
+            time,
+            a.translation * (1.0 - s) + b.translation * s,
+            a.rotation.slerp(&b.rotation, s),
+            a.scale * (1.0 - s) + b.scale * s,
+        )
+    }
+
+    //time时刻从物体空间到世界空间的变换
+    pub fn at(&self, time: f64) -> Transform {
+        self.interpolate(time).transform()
+    }
+
+    //物体空间的包围盒在[time0, time1]内扫过的范围
+    //平移和缩放是线性插值，只有旋转会让顶点沿弧线运动，所以按转过的角度细分时间段，
+    //变换每个采样时刻包围盒的8个顶点，再按弧线偏离弦的最大距离把结果向外扩大
+    pub fn bounding_box(&self, bbox: &Aabb, time0: f64, time1: f64) -> Aabb {
+        let mut times = vec![time0];
+        times.extend(self.keyframes.iter().map(|key| key.time).filter(|&t| t > time0 && t < time1));
+        if time1 > time0 {
+            times.push(time1);
+        }
+
+        let mut minimum = Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut maximum = Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+        let mut padding: f64 = 0.0;
+        let mut add_sample = |key: &Keyframe, step_angle: f64| {
+            let transform = key.transform();
+            for i in 0..8 {
+                let corner = Point3::new(
+                    if i & 1 == 0 { bbox.minimum.x } else { bbox.maximum.x },
+                    if i & 2 == 0 { bbox.minimum.y } else { bbox.maximum.y },
+                    if i & 4 == 0 { bbox.minimum.z } else { bbox.maximum.z },
+                );
+                let p = transform.point(&corner);
+                minimum = Point3::new(minimum.x.min(p.x), minimum.y.min(p.y), minimum.z.min(p.z));
+                maximum = Point3::new(maximum.x.max(p.x), maximum.y.max(p.y), maximum.z.max(p.z));
+
+                //半径为r的圆上转过θ的弧，偏离弦的距离不超过r·θ/2
+                let radius = Vector3::new(corner.x * key.scale.x, corner.y * key.scale.y, corner.z * key.scale.z).length();
+                padding = padding.max(radius * step_angle / 2.0);
+            }
+        };
+
+        add_sample(&self.interpolate(time0), 0.0);
+        for pair in times.windows(2) {
+            let (a, b) = (self.interpolate(pair[0]), self.interpolate(pair[1]));
+            let angle = a.rotation.angle_to(&b.rotation);
+            let steps = (angle / MAX_STEP_ANGLE).ceil().max(1.0) as usize;
+            let step_angle = angle / steps as f64;
+            for step in 1..=steps {
+                let t = pair[0] + (pair[1] - pair[0]) * step as f64 / steps as f64;
+                add_sample(&self.interpolate(t), step_angle);
+            }
+        }
+
+        let pad = Vector3::new(padding, padding, padding);
+        Aabb::new(minimum - pad, maximum + pad)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::color::Color;
+use crate::ray::Ray;
+use crate::utils::Sampler;
+use crate::vector::Vector3;
+
+//光线没有击中任何物体时看到的背景（环境光），它同时也是照亮场景的光源
+pub trait Background: Send + Sync {
+    fn color(&self, ray: &Ray) -> Color;
+
+    //按背景的亮度分布随机采样一个方向，用于对背景做重要性采样；不支持采样的背景返回None
+    fn sample(&self, _sampler: &mut Sampler) -> Option<Vector3> {
+        None
+    }
+
+    //sample采样到direction方向的概率密度（立体角）
+    fn pdf(&self, _direction: &Vector3) -> f64 {
+        0.0
+    }
+}
+
+//纯色背景，纯黑背景时场景中只有光源提供光照
+pub struct SolidBackground {
+    pub color: Color,
+}
+
+impl SolidBackground {
+    pub fn new(color: &Color) -> Self {
+        SolidBackground { color: *color }
+    }
+}
+
+impl Background for SolidBackground {
+    fn color(&self, _ray: &Ray) -> Color {
+        self.color
+    }
+}
+
+//天空渐变色：下方为白色，上方为淡蓝色
+#[derive(Default)]
+pub struct GradientBackground;
+
+impl Background for GradientBackground {
+    fn color(&self, ray: &Ray) -> Color {
+        let unit_dir = ray.dir.unit();
+
+        let t = 0.5*(unit_dir.y + 1.0);
+
+        Color::new(1.0 - 0.5 * t, 1.0 - 0.3 * t, 1.0)
+    }
+}
@@ -0,0 +1,195 @@
+// 内置场景
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::aarect::{XyRect, XzRect, YzRect};
+use crate::background::SolidBackground;
+use crate::camera::{Camera, MisHeuristic, RenderSettings};
+use crate::color::Color;
+use crate::cuboid::Cuboid;
+use crate::dielectric::Dielectric;
+use crate::diffuse_light::DiffuseLight;
+use crate::instance::Instance;
+use crate::lambertian::Lambertian;
+use crate::matrix::Transform;
+use crate::metal::Metal;
+use crate::scene::Scene;
+use crate::sphere::Sphere;
+use crate::utils::*;
+use crate::vector::{Point3, Vector3};
+use crate::world::World;
+
+//所有内置场景的名称
+pub const BUILTIN_SCENES: &[&str] = &["random-spheres", "simple-light", "cornell-box"];
+
+// 内置场景的默认渲染参数
+const ASPECT_RATIO: f64  = 16.0 / 9.0;
+const WIDTH:usize = 400;
+const HEIGHT:usize = (WIDTH as f64 / ASPECT_RATIO) as usize; 
+const SAMPLES_PER_PIXEL:i32 = 100;
+const MAX_DEPTH:i32 = 50;
+const THREADS:usize = 0; // 0表示使用全部CPU核心
+
+static IMAGE_FILE: &str = "1.ppm";
+
+//按名称创建内置场景，名称不存在时返回None
+pub fn builtin_scene(name: &str, seed: u64) -> Option<Scene> {
+    match name {
+        "random-spheres" => Some(create_random_spheres_scene(seed)),
+        "simple-light" => Some(create_simple_light_scene(seed)),
+        "cornell-box" => Some(create_cornell_box_scene(seed)),
+        _ => None,
+    }
+}
+
+//随机小球场景，seed决定小球的位置和材质
+pub fn create_random_spheres_scene(seed: u64) -> Scene {
+    // 创建一个包含若干不同材质球体的3D世界
+    let world = create_3d_world(&mut Sampler::new(seed));
+
+    // 创建相机
+    let camera = create_camera();
+
+    let settings = RenderSettings {
+        image_width: WIDTH,
+        image_height: HEIGHT,
+        samples_per_pixel: SAMPLES_PER_PIXEL,
+        max_depth: MAX_DEPTH,
+        threads: THREADS,
+        seed,
+        mis: MisHeuristic::Power,
+    };
+
+    Scene { world, camera, settings, output: PathBuf::from(IMAGE_FILE) }
+}
+
+//黑色背景下由两个发光球体照亮的场景
+pub fn create_simple_light_scene(seed: u64) -> Scene {
+    let mut world = World::new();
+
+    let ground_material = Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)));
+    world.add(Box::new(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, ground_material)));
+
+    let sphere_material = Arc::new(Lambertian::new(&Color::new(0.8, 0.3, 0.2)));
+    world.add(Box::new(Sphere::new(Point3::new(0.0, 2.0, 0.0), 2.0, sphere_material)));
+
+    let light = Arc::new(DiffuseLight::new(&Color::new(4.0, 4.0, 4.0)));
+    world.add_light(Arc::new(Sphere::new(Point3::new(0.0, 7.0, 0.0), 2.0, light.clone())));
+    world.add_light(Arc::new(Sphere::new(Point3::new(4.0, 1.0, 3.0), 0.5, light)));
+
+    let mut camera = Camera::new(Point3::new(26.0, 3.0, 6.0), Point3::new(0.0, 2.0, 0.0), Vector3::new(0.0, 1.0, 0.0),
+        20.0, ASPECT_RATIO, 0.0, 10.0, 0.0, 1.0);
+    camera.set_background(Arc::new(SolidBackground::new(&Color::black())));
+
+    let settings = RenderSettings {
+        image_width: WIDTH,
+        image_height: HEIGHT,
+        samples_per_pixel: SAMPLES_PER_PIXEL,
+        max_depth: MAX_DEPTH,
+        threads: THREADS,
+        seed,
+        mis: MisHeuristic::Power,
+    };
+
+    Scene { world, camera, settings, output: PathBuf::from(IMAGE_FILE) }
+}
+
+//康奈尔盒子：红绿两面侧墙、顶部一块面光源，盒子里放两个长方体
+pub fn create_cornell_box_scene(seed: u64) -> Scene {
+    let mut world = World::new();
+
+    let red = Arc::new(Lambertian::new(&Color::new(0.65, 0.05, 0.05)));
+    let white = Arc::new(Lambertian::new(&Color::new(0.73, 0.73, 0.73)));
+    let green = Arc::new(Lambertian::new(&Color::new(0.12, 0.45, 0.15)));
+    let light = Arc::new(DiffuseLight::new(&Color::new(15.0, 15.0, 15.0)));
+
+    world.add(Box::new(YzRect::new(0.0, 555.0, 0.0, 555.0, 555.0, green)));
+    world.add(Box::new(YzRect::new(0.0, 555.0, 0.0, 555.0, 0.0, red)));
+    world.add_light(Arc::new(XzRect::new(213.0, 343.0, 227.0, 332.0, 554.0, light)));
+    world.add(Box::new(XzRect::new(0.0, 555.0, 0.0, 555.0, 0.0, white.clone())));
+    world.add(Box::new(XzRect::new(0.0, 555.0, 0.0, 555.0, 555.0, white.clone())));
+    world.add(Box::new(XyRect::new(0.0, 555.0, 0.0, 555.0, 555.0, white.clone())));
+
+    //两个长方体先绕y轴旋转再平移到位
+    let tall = Cuboid::new(&Point3::new(0.0, 0.0, 0.0), &Point3::new(165.0, 330.0, 165.0), white.clone());
+    let tall_transform = Transform::rotate_y(15.0).then(&Transform::translate(&Vector3::new(265.0, 0.0, 295.0)));
+    world.add(Box::new(Instance::new(Arc::new(tall), tall_transform)));
+
+    let short = Cuboid::new(&Point3::new(0.0, 0.0, 0.0), &Point3::new(165.0, 165.0, 165.0), white);
+    let short_transform = Transform::rotate_y(-18.0).then(&Transform::translate(&Vector3::new(130.0, 0.0, 65.0)));
+    world.add(Box::new(Instance::new(Arc::new(short), short_transform)));
+
+    let mut camera = Camera::new(Point3::new(278.0, 278.0, -800.0), Point3::new(278.0, 278.0, 0.0), Vector3::new(0.0, 1.0, 0.0),
+        40.0, 1.0, 0.0, 10.0, 0.0, 1.0);
+    camera.set_background(Arc::new(SolidBackground::new(&Color::black())));
+
+    let settings = RenderSettings {
+        image_width: 600,
+        image_height: 600,
+        samples_per_pixel: 200,
+        max_depth: MAX_DEPTH,
+        threads: THREADS,
+        seed,
+        mis: MisHeuristic::Power,
+    };
+
+    Scene { world, camera, settings, output: PathBuf::from(IMAGE_FILE) }
+}
+
+fn create_3d_world(sampler: &mut Sampler) -> World {
+    let mut world = World::new();
+
+    // 创建用于大地的材质
+    let ground_material = Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)));
+    world.add(Box::new(Sphere::new(Point3::new(0.0,-1000.0,0.0), 1000.0, ground_material.clone())));
+
+    for a in -11..11  {
+        for b in -11..11 {
+            let choose_mat = random_f64(sampler);
+            let center = Point3::new(a as f64 + 0.9*random_f64(sampler), 0.2, b as f64 + 0.9*random_f64(sampler));
+
+            if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
+                if choose_mat < 0.8 {
+                    // 漫反射材质球
+                    let albedo = Color::random_color(sampler).mul_color(&Color::random_color(sampler));
+                    let sphere_material = Arc::new(Lambertian::new(&albedo));
+                    let mut sphere = Box::new(Sphere::new(center, 0.2, sphere_material.clone()));
+                    sphere.move_to(&(center + Vector3::new(0.0, random_f64_range(0.0, 0.5, sampler), 0.0)), 0.0, 1.0);
+                    world.add(sphere);
+                } else if choose_mat < 0.95 {
+                    // 金属球
+                    let albedo = Color::random_color_range(0.5, 1.0, sampler);
+                    let fuzz = random_f64_range(0.0, 0.5, sampler);
+                    let sphere_material = Arc::new(Metal::new(&albedo, fuzz));
+                    world.add(Box::new(Sphere::new(center, 0.2, sphere_material.clone())));
+                } else {
+                    // 玻璃类材质球
+                    let sphere_material = Arc::new(Dielectric::new(1.5));
+                    world.add(Box::new(Sphere::new(center, 0.2, sphere_material.clone())));
+                }
+            }
+        }
+    }
+
+    let material1 = Arc::new(Dielectric::new(1.5));
+    world.add(Box::new(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, material1.clone())));
+
+    let material2 = Arc::new(Lambertian::new(&Color::new(0.4, 0.2, 0.1)));
+    world.add(Box::new(Sphere::new(Point3::new(-4.0, 1.0, 0.0), 1.0, material2.clone())));
+
+    let material3 = Arc::new(Metal::new(&Color::new(0.7, 0.6, 0.5), 0.0));
+    world.add(Box::new(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.0, material3.clone())));
+
+    world
+}
+
+fn create_camera() -> Camera {
+    let  lookfrom = Point3::new(13.0,2.0,3.0);
+    let lookat = Point3::new(0.0,0.0,0.0);
+    let vup = Vector3::new(0.0,1.0,0.0);
+    let dist_to_focus = 10.0;
+    let aperture = 0.1;
+
+    Camera::new(lookfrom, lookat, vup, 20.0, ASPECT_RATIO, aperture, dist_to_focus, 0.0, 1.0)
+}
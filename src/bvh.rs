@@ -0,0 +1,171 @@
+use std::cmp::Ordering;
+
+use crate::aabb::Aabb;
+use crate::hittable::{HitInfo, Hittable};
+use crate::ray::Ray;
+use crate::utils::Sampler;
+
+//BVH的划分策略
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BvhSplit {
+    //沿包围盒中心点分布最长的轴排序，从中间一分为二，建树快
+    Median,
+    //表面积启发式（Surface Area Heuristic）：在三个轴上找期望求交代价最小的划分位置，建树慢但查询更快
+    Sah,
+}
+
+//层次包围盒（Bounding Volume Hierarchy）的节点，本身也是一个Hittable
+pub struct BvhNode {
+    left: Box<dyn Hittable>,
+    right: Option<Box<dyn Hittable>>, //只有一个物体时右子树为空
+    bbox: Aabb,
+}
+
+impl BvhNode {
+    //objects不能为空，且每个物体在[time0, time1]内都必须有包围盒
+    pub fn new(objects: Vec<Box<dyn Hittable>>, time0: f64, time1: f64, split: BvhSplit) -> Self {
+        assert!(!objects.is_empty(), "BvhNode::new requires at least one object");
+
+        let mut items: Vec<(Aabb, Box<dyn Hittable>)> = objects
+            .into_iter()
+            .map(|object| {
+                let bbox = object.bounding_box(time0, time1).expect("object without bounding box in BvhNode::new");
+                (bbox, object)
+            })
+            .collect();
+
+        if items.len() == 1 {
+            let (bbox, object) = items.pop().unwrap();
+            return BvhNode { left: object, right: None, bbox };
+        }
+
+        Self::split_items(items, split)
+    }
+
+    fn build(mut items: Vec<(Aabb, Box<dyn Hittable>)>, split: BvhSplit) -> Box<dyn Hittable> {
+        if items.len() == 1 {
+            return items.pop().unwrap().1;
+        }
+
+        Box::new(Self::split_items(items, split))
+    }
+
+    //items至少包含两个物体
+    fn split_items(mut items: Vec<(Aabb, Box<dyn Hittable>)>, split: BvhSplit) -> BvhNode {
+        let bbox = items.iter().skip(1).fold(items[0].0, |b, item| b.surrounding(&item.0));
+
+        let (axis, mid) = match split {
+            BvhSplit::Median => (Self::longest_centroid_axis(&items), items.len() / 2),
+            BvhSplit::Sah => Self::sah_split(&mut items),
+        };
+
+        sort_by_centroid(&mut items, axis);
+        let right_items = items.split_off(mid);
+
+        BvhNode {
+            left: Self::build(items, split),
+            right: Some(Self::build(right_items, split)),
+            bbox,
+        }
+    }
+
+    //包围盒中心点分布范围最大的轴
+    fn longest_centroid_axis(items: &[(Aabb, Box<dyn Hittable>)]) -> usize {
+        let first = items[0].0.centroid();
+        let centroids = items.iter().fold(Aabb::new(first, first), |b, item| {
+            let c = item.0.centroid();
+            b.surrounding(&Aabb::new(c, c))
+        });
+        let extent = centroids.maximum - centroids.minimum;
+
+        if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    //对每个轴按中心点排序后扫描所有划分位置，代价 = 左包围盒面积 * 左侧物体数 + 右包围盒面积 * 右侧物体数
+    //（省略了除以父节点面积的常数项，它不影响比较结果）
+    fn sah_split(items: &mut [(Aabb, Box<dyn Hittable>)]) -> (usize, usize) {
+        let n = items.len();
+        let mut best = (0, n / 2);
+        let mut best_cost = f64::INFINITY;
+        let mut right_areas = vec![0.0; n];
+
+        for axis in 0..3 {
+            sort_by_centroid(items, axis);
+
+            //right_areas[i]为第i个到最后一个物体的包围盒面积
+            let mut right_box = items[n - 1].0;
+            for i in (1..n).rev() {
+                right_box = right_box.surrounding(&items[i].0);
+                right_areas[i] = right_box.surface_area();
+            }
+
+            let mut left_box = items[0].0;
+            for i in 1..n {
+                let cost = left_box.surface_area() * i as f64 + right_areas[i] * (n - i) as f64;
+                if cost < best_cost {
+                    best_cost = cost;
+                    best = (axis, i);
+                }
+                left_box = left_box.surrounding(&items[i].0);
+            }
+        }
+
+        best
+    }
+}
+
+fn sort_by_centroid(items: &mut [(Aabb, Box<dyn Hittable>)], axis: usize) {
+    items.sort_by(|a, b| {
+        a.0.centroid()[axis]
+            .partial_cmp(&b.0.centroid()[axis])
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        if !self.bbox.hit(r, t_min, t_max) {
+            return None;
+        }
+
+        let hit_left = self.left.hit(r, t_min, t_max, sampler);
+
+        //左子树已经命中的话，右子树只需要查找更近的交点
+        let closest_so_far = hit_left.as_ref().map_or(t_max, |info| info.t);
+        let hit_right = self.right.as_ref().and_then(|right| right.hit(r, t_min, closest_so_far, sampler));
+
+        hit_right.or(hit_left)
+    }
+
+    fn surface_hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        if !self.bbox.hit(r, t_min, t_max) {
+            return None;
+        }
+
+        let hit_left = self.left.surface_hit(r, t_min, t_max, sampler);
+        let closest_so_far = hit_left.as_ref().map_or(t_max, |info| info.t);
+        let hit_right = self.right.as_ref().and_then(|right| right.surface_hit(r, t_min, closest_so_far, sampler));
+
+        hit_right.or(hit_left)
+    }
+
+    fn transmittance(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> f64 {
+        if !self.bbox.hit(r, t_min, t_max) {
+            return 1.0;
+        }
+
+        let left = self.left.transmittance(r, t_min, t_max, sampler);
+        let right = self.right.as_ref().map_or(1.0, |right| right.transmittance(r, t_min, t_max, sampler));
+        left * right
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(self.bbox)
+    }
+}
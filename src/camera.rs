@@ -1,12 +1,78 @@
-use core::f64::INFINITY;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 
 use crate::ray::Ray;
 use crate::vector::{Vector3, Point3}; 
-use crate::utils::{degrees_to_radians, random_f64_range};
+use crate::utils::{degrees_to_radians, random_f64_range, Sampler};
 use crate::world::World;
+use crate::hittable::{HitInfo, Hittable};
+use crate::background::{Background, GradientBackground};
 use crate::color::Color;
 use crate::utils::random_f64;
 
+// 渲染参数
+pub struct RenderSettings {
+    pub image_width: usize,
+    pub image_height: usize,
+    pub samples_per_pixel: i32,
+    pub max_depth: i32,
+    pub threads: usize, // 渲染线程数，0表示使用全部CPU核心
+    pub seed: u64, // 随机数种子，种子相同时渲染结果完全一致（与线程数无关）
+    pub mis: MisHeuristic, // 合并光源采样和BSDF采样时使用的权重函数
+}
+
+impl RenderSettings {
+    // 实际使用的线程数，不会超过图片的行数
+    fn worker_count(&self) -> usize {
+        let threads = if self.threads == 0 {
+            thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+        } else {
+            self.threads
+        };
+
+        threads.min(self.image_height).max(1)
+    }
+}
+
+//多重重要性采样（MIS）的权重函数：同一个方向既可能由光源采样得到，也可能由BSDF采样得到，
+//两种策略的样本按各自的概率密度加权后相加，结果仍然是无偏的，并且总是接近两者中较好的那一个
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MisHeuristic {
+    //pdf / (pdf + other_pdf)
+    Balance,
+    //pdf^2 / (pdf^2 + other_pdf^2)，一种策略明显更好时更偏向它，通常噪点更少
+    Power,
+}
+
+impl MisHeuristic {
+    //用概率密度为pdf的策略采样到某个方向时，这个样本的权重，other_pdf是另一种策略采样到同一方向的概率密度
+    pub fn weight(&self, pdf: f64, other_pdf: f64) -> f64 {
+        let (a, b) = match self {
+            MisHeuristic::Balance => (pdf, other_pdf),
+            MisHeuristic::Power => (pdf * pdf, other_pdf * other_pdf),
+        };
+
+        if a + b <= 0.0 {
+            return 0.0;
+        }
+        a / (a + b)
+    }
+}
+
+impl FromStr for MisHeuristic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "balance" => Ok(MisHeuristic::Balance),
+            "power" => Ok(MisHeuristic::Power),
+            _ => Err(format!("unknown MIS heuristic `{}` (expected balance or power)", s)),
+        }
+    }
+}
+
 pub struct Camera {
     origin: Point3, // 镜头位置
     lower_left_corner: Point3, // 视窗左下角的坐标
@@ -19,9 +85,11 @@ pub struct Camera {
     lens_radius: f64,
     time_shutter_open: f64,
     time_shutter_close: f64,
+    background: Arc<dyn Background>,
 }
 
 impl Camera {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(lookfrom: Point3, lookat: Point3, vup: Vector3, vfov: f64, aspect_ratio: f64, aperture: f64, focus_distance: f64, time_shutter_open: f64, time_shutter_close: f64) -> Self {
         let theta = degrees_to_radians(vfov);
         let h = (theta/2.0).tan();
@@ -46,66 +114,225 @@ impl Camera {
             vertical,
             lower_left_corner,
             u,v,lens_radius,
-            time_shutter_open, time_shutter_close
+            time_shutter_open, time_shutter_close,
+            background: Arc::new(GradientBackground),
         }
     }
-    pub fn get_ray(&self, u: f64, v: f64) ->Ray {//离焦模糊算法
-        let rd = Vector3::random_in_unit_disk() * self.lens_radius;
+
+    pub fn background(&self) -> &dyn Background {
+        self.background.as_ref()
+    }
+
+    pub fn set_background(&mut self, background: Arc<dyn Background>) {
+        self.background = background;
+    }
+
+    //focus plane的宽高比
+    pub fn aspect_ratio(&self) -> f64 {
+        self.horizontal.length() / self.vertical.length()
+    }
+
+    //调整宽高比，垂直视角保持不变，只改变focus plane的宽度
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f64) {
+        let horizontal = self.horizontal * (aspect_ratio / self.aspect_ratio());
+        self.lower_left_corner = self.lower_left_corner + self.horizontal / 2.0 - horizontal / 2.0;
+        self.horizontal = horizontal;
+    }
+
+    //快门打开和关闭的时刻
+    pub fn shutter(&self) -> (f64, f64) {
+        (self.time_shutter_open, self.time_shutter_close)
+    }
+
+    pub fn get_ray(&self, u: f64, v: f64, sampler: &mut Sampler) ->Ray {//离焦模糊算法
+        let rd = Vector3::random_in_unit_disk(sampler) * self.lens_radius;
         //在相机的xy平面偏移
         let offset = self.u * rd.x + self.v * rd.y;
 
         //只有focus plane上的点可以完美成像，离该平面越远越模糊（也就是不能在viewport平面上聚焦），最后的时间参数用于实现运动模糊效果
-        Ray::new(self.origin + offset, self.lower_left_corner + self.horizontal * u + self.vertical * v - self.origin - offset, random_f64_range(self.time_shutter_open, self.time_shutter_close))
+        Ray::new(self.origin + offset, self.lower_left_corner + self.horizontal * u + self.vertical * v - self.origin - offset, random_f64_range(self.time_shutter_open, self.time_shutter_close, sampler))
+    }
+
+    pub fn take_photo(&self, world: &World, settings: &RenderSettings) ->Vec<Color> {
+        self.take_photo_with_progress(world, settings, &|_| {})
     }
 
-    pub fn take_photo(&self, world: &World, image_width: usize, image_height: usize, samples_per_pixel: i32, max_depth: i32) ->Vec<Color> {
-        let mut image_pixels = Vec::new();
+    // 与take_photo相同，每渲染完一行调用一次progress，参数为还没有渲染完的行数；progress可能在任何一个渲染线程中被调用
+    pub fn take_photo_with_progress(&self, world: &World, settings: &RenderSettings, progress: &(dyn Fn(usize) + Sync)) ->Vec<Color> {
+        let image_width = settings.image_width;
+        let image_height = settings.image_height;
+        let mut image_pixels = vec![Color::black(); image_width * image_height];
 
-        for i in (0 .. image_height).rev() {
-            println!("rendering remaining: {}", i);
-            for j in 0 .. image_width {
-                let mut pixel_color = Color::black();
-    
-                let mut k = 0;
-                while k < samples_per_pixel {
-                    let u = (j as f64 + random_f64())/ ((image_width-1) as f64);
-                    let v = (i as f64 + random_f64()) / ((image_height-1) as f64);
-    
-                    let ray = self.get_ray(u, v);//Ray::new(origin, lower_left_corner + horizontal * u + vertical * v - origin);
-                
-                    let color = self.ray_color(&ray, &world, max_depth);
-    
-                    pixel_color = pixel_color + color;
-    
-                    k = k + 1;
+        {
+            // 按扫描线分配任务：每个线程从共享的行迭代器中领取下一行，渲染完再领取，直到所有行都渲染完成
+            // 图片从上往下存储，第k行对应v方向上的第(image_height - 1 - k)行
+            let rows = Mutex::new(image_pixels.chunks_mut(image_width).enumerate());
+            let remaining = AtomicUsize::new(image_height);
+
+            thread::scope(|s| {
+                for _ in 0 .. settings.worker_count() {
+                    s.spawn(|| loop {
+                        let next_row = rows.lock().unwrap().next();
+                        let Some((k, row)) = next_row else {
+                            break;
+                        };
+
+                        self.render_row(world, settings, k, row);
+
+                        progress(remaining.fetch_sub(1, Ordering::Relaxed) - 1);
+                    });
                 }
-                
-                let pixel_color = pixel_color / samples_per_pixel as f64;
-                image_pixels.push(pixel_color);
-            }
+            });
         }
 
         image_pixels
     }
 
-    fn ray_color(&self, ray: &Ray, world: &World, depth: i32) ->Color {
+    // 渲染图片第k行的所有像素
+    fn render_row(&self, world: &World, settings: &RenderSettings, k: usize, row: &mut [Color]) {
+        let i = settings.image_height - 1 - k;
+
+        for (j, pixel) in row.iter_mut().enumerate() {
+            let mut sampler = Sampler::for_pixel(settings.seed, (k * settings.image_width + j) as u64);
+            let mut pixel_color = Color::black();
+
+            for _ in 0 .. settings.samples_per_pixel {
+                let u = (j as f64 + random_f64(&mut sampler))/ ((settings.image_width-1) as f64);
+                let v = (i as f64 + random_f64(&mut sampler)) / ((settings.image_height-1) as f64);
+
+                let ray = self.get_ray(u, v, &mut sampler);
+
+                let color = self.ray_color(&ray, world, settings.max_depth, settings.mis, &mut sampler);
+
+                pixel_color = pixel_color + color;
+            }
+
+            *pixel = pixel_color / settings.samples_per_pixel as f64;
+        }
+    }
+
+    fn ray_color(&self, ray: &Ray, world: &World, depth: i32, heuristic: MisHeuristic, sampler: &mut Sampler) ->Color {
+        self.trace(ray, world, depth, heuristic, None, sampler)
+    }
+
+    //路径追踪：在有概率密度的散射（漫反射、参与介质）处，直接光照由光源采样和BSDF采样两部分用MIS合并而成
+    //bsdf_pdf是上一个击中点按BSDF采样得到ray的概率密度，相机光线和镜面散射的光线为None
+    fn trace(&self, ray: &Ray, world: &World, depth: i32, heuristic: MisHeuristic, bsdf_pdf: Option<f64>, sampler: &mut Sampler) ->Color {
         if depth <= 0 {
             return Color::black();
         }
     
-        if let Some(hit_info) = world.hit(ray, 0.001, INFINITY) { //射线ray与球面相交
-            if let Some(scatter_info) = hit_info.material.scatter(ray, &hit_info) {
-                return scatter_info.attenuation.mul_color(&self.ray_color(&scatter_info.scattered, world, depth-1));
+        if let Some(hit_info) = world.hit(ray, 0.001, f64::INFINITY, sampler) { //射线ray与球面相交
+            //物体自身发出的光加上它反射（折射）的光
+            let mut emitted = hit_info.material.emitted(ray, &hit_info);
+
+            //BSDF采样击中了光源列表中的光源，这个方向也可能由上一个击中点的光源采样得到，发光按MIS权重计入
+            if let (true, Some(pdf)) = (hit_info.is_light, bsdf_pdf) {
+                let light_pdf = world.light_pdf(&ray.orig, &ray.dir, sampler);
+                emitted = emitted * heuristic.weight(pdf, light_pdf);
+            }
+
+            if let Some(scatter_info) = hit_info.material.scatter(ray, &hit_info, sampler) {
+                let direct = match scatter_info.pdf {
+                    Some(_) => {
+                        self.sample_light(ray, world, &hit_info, heuristic, sampler)
+                            + self.sample_background(ray, world, &hit_info, heuristic, sampler)
+                            + self.delta_lighting(ray, world, &hit_info, sampler)
+                    }
+                    None => Color::black(),
+                };
+                let indirect = self.trace(&scatter_info.scattered, world, depth-1, heuristic, scatter_info.pdf, sampler);
+
+                return emitted + direct + scatter_info.attenuation.mul_color(&indirect);
             }
                 
+            return emitted;
+        }
+
+        //BSDF采样的光线射向背景，这个方向也可能由背景的重要性采样得到，同样按MIS权重计入
+        let background = self.background.color(ray);
+        match bsdf_pdf {
+            Some(pdf) => background * heuristic.weight(pdf, self.background.pdf(&ray.dir)),
+            None => background,
+        }
+    }
+
+    //光源采样的部分：按光源的立体角概率密度采样一个方向，向它发出阴影射线，
+    //最近的表面是光源时，贡献为 发光 · 透射率 · eval · MIS权重 / pdf，否则光源被遮挡，贡献为0；
+    //阴影射线穿过参与介质时不在介质中随机碰撞，而是乘上介质的透射率，烟雾中的阴影因此噪点更少
+    fn sample_light(&self, ray: &Ray, world: &World, hit_info: &HitInfo, heuristic: MisHeuristic, sampler: &mut Sampler) -> Color {
+        let Some(direction) = world.sample_light(&hit_info.pos, sampler) else {
+            return Color::black();
+        };
+
+        let bsdf = hit_info.material.eval(ray, hit_info, &direction);
+        if bsdf.is_black() {
             return Color::black();
         }
-    
-        //背景渐变色
-        let unit_dir = ray.dir.unit();
-    
-        let t = 0.5*(unit_dir.y + 1.0);
-    
-        Color::new(1.0 - 0.5 * t, 1.0 - 0.3 * t, 1.0)
+
+        let shadow_ray = Ray::new(hit_info.pos, direction, ray.time);
+        match world.surface_hit(&shadow_ray, 0.001, f64::INFINITY, sampler) {
+            Some(light_hit) if light_hit.is_light => {
+                let light_pdf = world.light_pdf(&hit_info.pos, &direction, sampler);
+                if light_pdf <= 0.0 {
+                    return Color::black();
+                }
+                let transmittance = world.transmittance(&shadow_ray, 0.001, light_hit.t, sampler);
+                if transmittance <= 0.0 {
+                    return Color::black();
+                }
+                let bsdf_pdf = hit_info.material.pdf(ray, hit_info, &direction);
+                let weight = heuristic.weight(light_pdf, bsdf_pdf);
+
+                light_hit.material.emitted(&shadow_ray, &light_hit).mul_color(&bsdf) * (transmittance * weight / light_pdf)
+            }
+            _ => Color::black(),
+        }
     }
-}
\ No newline at end of file
+
+    //背景的重要性采样：按背景的亮度分布采样一个方向，阴影射线没有击中任何物体时得到背景的光
+    fn sample_background(&self, ray: &Ray, world: &World, hit_info: &HitInfo, heuristic: MisHeuristic, sampler: &mut Sampler) -> Color {
+        let Some(direction) = self.background.sample(sampler) else {
+            return Color::black();
+        };
+
+        let bsdf = hit_info.material.eval(ray, hit_info, &direction);
+        let background_pdf = self.background.pdf(&direction);
+        if bsdf.is_black() || background_pdf <= 0.0 {
+            return Color::black();
+        }
+
+        let shadow_ray = Ray::new(hit_info.pos, direction, ray.time);
+        if world.surface_hit(&shadow_ray, 0.001, f64::INFINITY, sampler).is_some() {
+            return Color::black();
+        }
+        let transmittance = world.transmittance(&shadow_ray, 0.001, f64::INFINITY, sampler);
+
+        let weight = heuristic.weight(background_pdf, hit_info.material.pdf(ray, hit_info, &direction));
+        self.background.color(&shadow_ray).mul_color(&bsdf) * (transmittance * weight / background_pdf)
+    }
+
+    //点光源、聚光灯和平行光的直接光照：BSDF采样不可能击中它们，所以每个光源都发出一条阴影射线，不需要MIS
+    fn delta_lighting(&self, ray: &Ray, world: &World, hit_info: &HitInfo, sampler: &mut Sampler) -> Color {
+        let mut result = Color::black();
+
+        for light in world.delta_lights() {
+            let Some(sample) = light.sample(&hit_info.pos) else {
+                continue;
+            };
+
+            let bsdf = hit_info.material.eval(ray, hit_info, &sample.direction);
+            if bsdf.is_black() {
+                continue;
+            }
+
+            let shadow_ray = Ray::new(hit_info.pos, sample.direction, ray.time);
+            if world.surface_hit(&shadow_ray, 0.001, sample.distance, sampler).is_none() {
+                let transmittance = world.transmittance(&shadow_ray, 0.001, sample.distance, sampler);
+                result = result + sample.irradiance.mul_color(&bsdf) * transmittance;
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::hittable::HitInfo;
+use crate::texture::{SolidColor, Texture};
+use crate::vector::Point3;
+
+//三维棋盘格纹理：把空间划分为边长为scale的立方体格子，相邻格子交替使用even和odd两种纹理
+//由于按空间位置而不是(u, v)计算，任何形状的物体都能贴上棋盘格
+pub struct CheckerTexture {
+    inv_scale: f64,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl CheckerTexture {
+    pub fn new(scale: f64, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        CheckerTexture {
+            inv_scale: 1.0 / scale,
+            even,
+            odd,
+        }
+    }
+
+    pub fn from_colors(scale: f64, even: &Color, odd: &Color) -> Self {
+        CheckerTexture::new(scale, Arc::new(SolidColor::new(even)), Arc::new(SolidColor::new(odd)))
+    }
+
+    //p所在格子使用的纹理
+    fn texture_at(&self, p: &Point3) -> &dyn Texture {
+        let x = (self.inv_scale * p.x).floor() as i64;
+        let y = (self.inv_scale * p.y).floor() as i64;
+        let z = (self.inv_scale * p.z).floor() as i64;
+
+        if (x + y + z).rem_euclid(2) == 0 {
+            self.even.as_ref()
+        } else {
+            self.odd.as_ref()
+        }
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.texture_at(p).value(u, v, p)
+    }
+
+    fn value_at(&self, hit_info: &HitInfo) -> Color {
+        self.texture_at(&hit_info.local_pos).value_at(hit_info)
+    }
+}
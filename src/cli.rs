@@ -0,0 +1,328 @@
+// 命令行参数的解析与校验
+
+use std::fmt;
+use std::path::PathBuf;
+
+use render::camera::MisHeuristic;
+use render::output::{self, ImageWriter};
+use render::postprocess::{PostProcess, ToneMap, TransferFunction};
+use render::scene::Scene;
+
+pub const USAGE: &str = "\
+Usage: render [OPTIONS] [SCENE]
+
+Renders SCENE, which is either a scene file or the name of a built-in scene
+(random-spheres, simple-light, cornell-box). Without SCENE the built-in
+random-spheres scene is rendered.
+Options override the settings given in the scene file.
+
+Options:
+  -W, --width <N>          image width in pixels (at least 2)
+  -H, --height <N>         image height in pixels (at least 2); defaults to width / aspect
+      --aspect <RATIO>     image aspect ratio, e.g. 16/9 or 1.5
+  -s, --spp <N>            samples per pixel (at least 1)
+  -d, --max-depth <N>      maximum number of ray bounces (at least 1)
+      --mis <HEURISTIC>    multiple importance sampling weights: balance or power (default: power)
+  -o, --output <PATH>      output image path
+  -f, --format <FORMAT>    output format: ppm, ppm-ascii, png, png16, pfm, hdr, exr, exr32
+                           (default: chosen from the output file extension)
+      --seed <N>           random seed; the same seed renders the same image
+  -j, --threads <N>        number of render threads, 0 = all CPU cores (default: 0)
+      --exposure <STOPS>   exposure compensation in stops (default: 0)
+      --tonemap <OP>       clamp, reinhard, reinhard-extended[:WHITE], aces or hable (default: clamp)
+      --encoding <ENC>     linear, srgb or gamma:N (default: gamma:2)
+  -h, --help               print this help and exit";
+
+#[derive(Debug)]
+pub struct CliError(String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+pub enum Command {
+    Help,
+    Render(Box<Options>),
+}
+
+//命令行选项，没有给出的选项为None，使用场景中的设置
+#[derive(Default)]
+pub struct Options {
+    pub scene: Option<String>,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub aspect: Option<f64>,
+    pub spp: Option<i32>,
+    pub max_depth: Option<i32>,
+    pub mis: Option<MisHeuristic>,
+    pub output: Option<PathBuf>,
+    pub format: Option<String>,
+    pub seed: Option<u64>,
+    pub threads: Option<usize>,
+    pub exposure: Option<f64>,
+    pub tone_map: Option<ToneMap>,
+    pub transfer: Option<TransferFunction>,
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, CliError> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        //同时支持“--width 400”和“--width=400”两种写法
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+
+        let mut value = || -> Result<String, CliError> {
+            inline_value.clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError(format!("option `{}` requires a value", name)))
+        };
+
+        match name.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-W" | "--width" => options.width = Some(parse_at_least(&name, &value()?, 2)?),
+            "-H" | "--height" => options.height = Some(parse_at_least(&name, &value()?, 2)?),
+            "--aspect" => options.aspect = Some(parse_aspect(&value()?)?),
+            "-s" | "--spp" => options.spp = Some(parse_count(&name, &value()?)?),
+            "-d" | "--max-depth" => options.max_depth = Some(parse_count(&name, &value()?)?),
+            "--mis" => options.mis = Some(value()?.parse().map_err(CliError)?),
+            "-o" | "--output" => options.output = Some(PathBuf::from(value()?)),
+            "-f" | "--format" => {
+                let format = value()?;
+                if output::writer_for_format(&format).is_none() {
+                    return Err(CliError(format!("unknown output format `{}`", format)));
+                }
+                options.format = Some(format);
+            }
+            "--seed" => options.seed = Some(parse_number(&name, &value()?)?),
+            "-j" | "--threads" => options.threads = Some(parse_number(&name, &value()?)?),
+            "--exposure" => {
+                let exposure: f64 = parse_number(&name, &value()?)?;
+                if !exposure.is_finite() {
+                    return Err(CliError(format!("invalid value for `{}`: must be finite", name)));
+                }
+                options.exposure = Some(exposure);
+            }
+            "--tonemap" => options.tone_map = Some(value()?.parse().map_err(CliError)?),
+            "--encoding" => options.transfer = Some(value()?.parse().map_err(CliError)?),
+            _ if name.starts_with('-') && name.len() > 1 => {
+                return Err(CliError(format!("unknown option `{}`", name)));
+            }
+            _ => {
+                if options.scene.is_some() {
+                    return Err(CliError(format!("unexpected argument `{}`", arg)));
+                }
+                options.scene = Some(arg);
+            }
+        }
+    }
+
+    Ok(Command::Render(Box::new(options)))
+}
+
+impl Options {
+    //用命令行选项覆盖场景中的渲染设置，并检查最终的图片尺寸
+    pub fn apply(&self, scene: &mut Scene) -> Result<(), CliError> {
+        let settings = &mut scene.settings;
+
+        let width = self.width.unwrap_or(settings.image_width);
+        let height = match self.height {
+            Some(height) => height,
+            None if self.width.is_some() || self.aspect.is_some() => {
+                let aspect = self.aspect.unwrap_or_else(|| scene.camera.aspect_ratio());
+                (width as f64 / aspect) as usize
+            }
+            None => settings.image_height,
+        };
+
+        //take_photo按(image_width-1)和(image_height-1)归一化像素坐标，宽高都至少要2个像素
+        if width < 2 || height < 2 {
+            return Err(CliError(format!("image size {}x{} is too small, width and height must be at least 2", width, height)));
+        }
+
+        settings.image_width = width;
+        settings.image_height = height;
+        scene.camera.set_aspect_ratio(width as f64 / height as f64);
+
+        if let Some(spp) = self.spp {
+            settings.samples_per_pixel = spp;
+        }
+        if let Some(max_depth) = self.max_depth {
+            settings.max_depth = max_depth;
+        }
+        if let Some(mis) = self.mis {
+            settings.mis = mis;
+        }
+        if let Some(seed) = self.seed {
+            settings.seed = seed;
+        }
+        if let Some(threads) = self.threads {
+            settings.threads = threads;
+        }
+        if let Some(output) = &self.output {
+            scene.output = output.clone();
+        }
+
+        Ok(())
+    }
+
+    //--format指定的编码器，没有指定时按输出文件的扩展名选择
+    pub fn writer(&self, scene: &Scene) -> Result<Box<dyn ImageWriter>, CliError> {
+        match &self.format {
+            Some(format) => Ok(output::writer_for_format(format).unwrap()),
+            None => output::writer_for_path(&scene.output).ok_or_else(|| {
+                CliError(format!("cannot tell the image format of `{}`, use --format", scene.output.display()))
+            }),
+        }
+    }
+
+    pub fn post_process(&self) -> PostProcess {
+        let default = PostProcess::default();
+
+        PostProcess::new(
+            self.exposure.unwrap_or(default.exposure),
+            self.tone_map.unwrap_or(default.tone_map),
+            self.transfer.unwrap_or(default.transfer),
+        )
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, CliError> {
+    value.parse().map_err(|_| CliError(format!("invalid value `{}` for `{}`", value, name)))
+}
+
+fn parse_at_least(name: &str, value: &str, min: usize) -> Result<usize, CliError> {
+    let n: usize = parse_number(name, value)?;
+    if n < min {
+        return Err(CliError(format!("`{}` must be at least {}, got {}", name, min, n)));
+    }
+    Ok(n)
+}
+
+//采样数和深度：至少为1，不能超过i32的范围
+fn parse_count(name: &str, value: &str) -> Result<i32, CliError> {
+    let n = parse_at_least(name, value, 1)?;
+    i32::try_from(n).map_err(|_| CliError(format!("`{}` must be at most {}, got {}", name, i32::MAX, n)))
+}
+
+//宽高比，支持a/b形式
+fn parse_aspect(value: &str) -> Result<f64, CliError> {
+    let parse = |x: &str| x.trim().parse::<f64>().ok();
+
+    let aspect = match value.split_once('/') {
+        Some((a, b)) => parse(a).zip(parse(b)).map(|(a, b)| a / b),
+        None => parse(value),
+    };
+
+    match aspect {
+        Some(aspect) if aspect.is_finite() && aspect > 0.0 => Ok(aspect),
+        _ => Err(CliError(format!("invalid aspect ratio `{}`", value))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use render::scene::parse_scene;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, CliError> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn options(args: &[&str]) -> Options {
+        match parse(args) {
+            Ok(Command::Render(options)) => *options,
+            Ok(Command::Help) => panic!("unexpected --help"),
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse(args) {
+            Err(e) => e.to_string(),
+            Ok(_) => panic!("{:?} should not parse", args),
+        }
+    }
+
+    #[test]
+    fn help_stops_parsing() {
+        assert!(matches!(parse(&["--help"]), Ok(Command::Help)));
+        assert!(matches!(parse(&["scene.txt", "-h", "--bogus"]), Ok(Command::Help)));
+    }
+
+    #[test]
+    fn accepts_both_value_forms() {
+        let options = options(&["--width=400", "--height", "300", "-s", "16", "--aspect", "16/9", "my.scene"]);
+        assert_eq!(options.width, Some(400));
+        assert_eq!(options.height, Some(300));
+        assert_eq!(options.spp, Some(16));
+        assert_eq!(options.aspect, Some(16.0 / 9.0));
+        assert_eq!(options.scene.as_deref(), Some("my.scene"));
+    }
+
+    #[test]
+    fn image_size_must_be_at_least_two_pixels() {
+        assert!(error(&["--width=1"]).contains("`--width` must be at least 2"));
+        assert!(error(&["--height", "1"]).contains("`--height` must be at least 2"));
+    }
+
+    #[test]
+    fn counts_must_fit_in_i32_and_be_at_least_one() {
+        assert!(error(&["--spp", "0"]).contains("`--spp` must be at least 1"));
+        assert!(error(&["--spp", "2147483648"]).contains("`--spp` must be at most 2147483647"));
+        assert!(error(&["-d", "0"]).contains("`-d` must be at least 1"));
+        assert_eq!(options(&["--spp", "2147483647"]).spp, Some(i32::MAX));
+    }
+
+    #[test]
+    fn rejects_bad_aspect_ratios() {
+        for aspect in ["0", "16/0", "abc", "-1.5"] {
+            assert!(error(&["--aspect", aspect]).contains("invalid aspect ratio"), "{}", aspect);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_formats_and_options() {
+        assert!(error(&["--format", "gif"]).contains("unknown output format `gif`"));
+        assert!(error(&["--frobnicate"]).contains("unknown option `--frobnicate`"));
+    }
+
+    #[test]
+    fn rejects_missing_values_and_extra_arguments() {
+        assert!(error(&["--width"]).contains("option `--width` requires a value"));
+        assert!(error(&["a.scene", "b.scene"]).contains("unexpected argument `b.scene`"));
+    }
+
+    #[test]
+    fn height_follows_width_and_aspect() {
+        let text = "render width=200 aspect=2\ncamera lookfrom=0,0,5 lookat=0,0,0\n";
+
+        //只给出宽度时沿用场景的宽高比
+        let mut scene = parse_scene(text).unwrap();
+        options(&["--width", "100"]).apply(&mut scene).unwrap();
+        assert_eq!((scene.settings.image_width, scene.settings.image_height), (100, 50));
+
+        //只给出宽高比时沿用场景的宽度
+        let mut scene = parse_scene(text).unwrap();
+        options(&["--aspect", "4"]).apply(&mut scene).unwrap();
+        assert_eq!((scene.settings.image_width, scene.settings.image_height), (200, 50));
+
+        //显式给出的高度优先
+        let mut scene = parse_scene(text).unwrap();
+        options(&["--width", "100", "--height", "80", "--aspect", "4"]).apply(&mut scene).unwrap();
+        assert_eq!((scene.settings.image_width, scene.settings.image_height), (100, 80));
+
+        //推导出的高度不足2个像素
+        let mut scene = parse_scene(text).unwrap();
+        let e = options(&["--aspect", "150"]).apply(&mut scene).unwrap_err();
+        assert!(e.to_string().contains("image size 200x1 is too small"), "{}", e);
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::{Add, Mul, Div};
 
-use crate::utils::{random_f64, random_f64_range};
+use crate::utils::{random_f64, random_f64_range, Sampler};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Color {
@@ -32,17 +32,26 @@ impl Color {
         Color {r: 0.0, g: 0.0, b: 0.0}
     }
 
+    //Rec. 709的相对亮度
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    pub fn is_black(&self) -> bool {
+        self.r == 0.0 && self.g == 0.0 && self.b == 0.0
+    }
+
     pub fn mul_color(&self, other: &Color) -> Color {
         Color::new(self.r * other.r, self.g * other.g, self.b * other.b)
     }
 
     #[inline]
-    pub fn random_color() -> Color {
-        return Color::new(random_f64(), random_f64(), random_f64());
+    pub fn random_color(sampler: &mut Sampler) -> Color {
+        Color::new(random_f64(sampler), random_f64(sampler), random_f64(sampler))
     }
     #[inline]
-    pub fn random_color_range(min: f64, max: f64) -> Color {
-        return Color::new(random_f64_range(min,max), random_f64_range(min,max), random_f64_range(min,max));
+    pub fn random_color_range(min: f64, max: f64, sampler: &mut Sampler) -> Color {
+        Color::new(random_f64_range(min,max,sampler), random_f64_range(min,max,sampler), random_f64_range(min,max,sampler))
     }
 }
 
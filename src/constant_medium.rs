@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::color::Color;
+use crate::hittable::{HitInfo, Hittable};
+use crate::isotropic::Isotropic;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::utils::{random_f64, Sampler};
+
+//密度均匀的参与介质（雾、烟），充满boundary围成的区域
+//boundary必须是封闭的凸物体：光线进入和离开边界各只有一个交点
+pub struct ConstantMedium {
+    boundary: Arc<dyn Hittable>,
+    neg_inv_density: f64, //-1/density
+    phase_function: Arc<dyn Material>,
+}
+
+impl ConstantMedium {
+    //density是单位长度上发生散射的概率密度，必须为正数
+    pub fn new(boundary: Arc<dyn Hittable>, density: f64, phase_function: Arc<dyn Material>) -> Self {
+        assert!(density > 0.0, "medium density must be positive");
+
+        ConstantMedium {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function,
+        }
+    }
+
+    //以颜色为反照率的各向同性介质
+    pub fn with_color(boundary: Arc<dyn Hittable>, density: f64, albedo: &Color) -> Self {
+        ConstantMedium::new(boundary, density, Arc::new(Isotropic::new(albedo)))
+    }
+
+    pub fn with_texture(boundary: Arc<dyn Hittable>, density: f64, albedo: Arc<dyn Texture>) -> Self {
+        ConstantMedium::new(boundary, density, Arc::new(Isotropic::with_texture(albedo)))
+    }
+
+    //光线在[t_min, t_max]内位于介质中的区间
+    fn interval(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<(f64, f64)> {
+        //光线所在直线进入和离开边界的位置，光线起点在介质内部时进入点在起点之后
+        let enter = self.boundary.hit(r, f64::NEG_INFINITY, f64::INFINITY, sampler)?;
+        let exit = self.boundary.hit(r, enter.t + 0.0001, f64::INFINITY, sampler)?;
+
+        let t_enter = enter.t.max(t_min).max(0.0);
+        let t_exit = exit.t.min(t_max);
+        if t_enter >= t_exit {
+            return None;
+        }
+        Some((t_enter, t_exit))
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        let (t_enter, t_exit) = self.interval(r, t_min, t_max, sampler)?;
+
+        //自由程服从指数分布：在介质中走过距离d而不发生散射的概率是exp(-density·d)
+        let ray_length = r.dir.length();
+        let distance_inside = (t_exit - t_enter) * ray_length;
+        let hit_distance = self.neg_inv_density * (1.0 - random_f64(sampler)).ln();
+        if hit_distance > distance_inside {
+            return None;
+        }
+
+        //散射点没有表面，法线和表面坐标没有意义，法线取与光线相反的方向
+        let t = t_enter + hit_distance / ray_length;
+        Some(HitInfo::new(r, r.at(t), &-r.dir.unit(), t, 0.0, 0.0, self.phase_function.as_ref()))
+    }
+
+    fn surface_hit(&self, _r: &Ray, _t_min: f64, _t_max: f64, _sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        None
+    }
+
+    //密度均匀，透射率可以直接算出：exp(-density·d)
+    fn transmittance(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> f64 {
+        match self.interval(r, t_min, t_max, sampler) {
+            Some((t_enter, t_exit)) => ((t_exit - t_enter) * r.dir.length() / self.neg_inv_density).exp(),
+            None => 1.0,
+        }
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        self.boundary.bounding_box(time0, time1)
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::aarect::{XyRect, XzRect, YzRect};
+use crate::hittable::{FlipFace, HitInfo, Hittable};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::utils::Sampler;
+use crate::vector::Point3;
+use crate::world::World;
+
+//轴对齐的长方体（盒子），由六个矩形面组成，每个面的外法线都指向盒子外面
+//不叫Box是为了不和标准库的Box冲突
+pub struct Cuboid {
+    pub minimum: Point3,
+    pub maximum: Point3,
+    sides: World,
+}
+
+impl Cuboid {
+    pub fn new(p0: &Point3, p1: &Point3, material: Arc<dyn Material>) -> Self {
+        let minimum = Point3::new(p0.x.min(p1.x), p0.y.min(p1.y), p0.z.min(p1.z));
+        let maximum = Point3::new(p0.x.max(p1.x), p0.y.max(p1.y), p0.z.max(p1.z));
+        let (min, max) = (minimum, maximum);
+
+        //最小坐标一侧的三个面需要把矩形默认指向正方向的外法线反过来
+        let mut sides = World::new();
+        sides.add(Box::new(XyRect::new(min.x, max.x, min.y, max.y, max.z, material.clone())));
+        sides.add(Box::new(FlipFace::new(XyRect::new(min.x, max.x, min.y, max.y, min.z, material.clone()))));
+        sides.add(Box::new(XzRect::new(min.x, max.x, min.z, max.z, max.y, material.clone())));
+        sides.add(Box::new(FlipFace::new(XzRect::new(min.x, max.x, min.z, max.z, min.y, material.clone()))));
+        sides.add(Box::new(YzRect::new(min.y, max.y, min.z, max.z, max.x, material.clone())));
+        sides.add(Box::new(FlipFace::new(YzRect::new(min.y, max.y, min.z, max.z, min.x, material))));
+
+        Cuboid { minimum, maximum, sides }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        self.sides.hit(r, t_min, t_max, sampler)
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(Aabb::new(self.minimum, self.maximum))
+    }
+}
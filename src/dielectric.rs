@@ -3,7 +3,7 @@ use crate::material::{Material, ScatterInfo};
 use crate::ray::Ray;
 use crate::hittable::HitInfo;
 use crate::vector::*;
-use crate::utils::random_f64;
+use crate::utils::{random_f64, Sampler};
 
 pub struct Dielectric {
     pub ir: f64 //材质的折射率
@@ -20,13 +20,13 @@ impl Dielectric {
         let mut r0 = (1.0 - ref_idx) / (1.0 + ref_idx);
         r0 = r0 * r0;
         let t = (1.0 - cosine) * (1.0 - cosine);
-        return r0 + (1.0 - r0) * (t * t * (1.0 - cosine));
+        r0 + (1.0 - r0) * (t * t * (1.0 - cosine))
         //return r0 + (1.0 - r0) * (1.0 - cosine).powi(5);
     }
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, r_in: &Ray, hit_info: &HitInfo) -> Option<ScatterInfo> {
+    fn scatter(&self, r_in: &Ray, hit_info: &HitInfo, sampler: &mut Sampler) -> Option<ScatterInfo> {
         let attenuation = Color::new(1.0, 1.0, 1.0);
 
         //入射光线所在介质的折射率与光线即将进入（如果发生折射）的介质折射率的比值
@@ -38,7 +38,7 @@ impl Material for Dielectric {
         let sin_theta = (1.0 - cos_theta*cos_theta).sqrt();
 
         let cannot_refract = refraction_ratio * sin_theta > 1.0; //发生全反射
-        let direction = if cannot_refract || Self::reflectance(cos_theta, refraction_ratio) > random_f64() {
+        let direction = if cannot_refract || Self::reflectance(cos_theta, refraction_ratio) > random_f64(sampler) {
             //计算反射光线的方向
             Vector3::reflect(&unit_direction, &hit_info.normal)
         } else {
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::material::{Material, ScatterInfo};
+use crate::ray::Ray;
+use crate::hittable::HitInfo;
+use crate::utils::Sampler;
+use crate::texture::{SolidColor, Texture};
+
+//漫射光源：自身发光，不反射光线
+pub struct DiffuseLight {
+    pub emit: Arc<dyn Texture>
+}
+
+impl DiffuseLight {
+    pub fn new(emit: &Color) -> Self {
+        DiffuseLight::with_texture(Arc::new(SolidColor::new(emit)))
+    }
+
+    pub fn with_texture(emit: Arc<dyn Texture>) -> Self {
+        DiffuseLight {
+            emit
+        }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _r_in: &Ray, _hit_info: &HitInfo, _sampler: &mut Sampler) -> Option<ScatterInfo> {
+        None
+    }
+
+    fn emitted(&self, _r_in: &Ray, hit_info: &HitInfo) -> Color {
+        self.emit.value_at(hit_info)
+    }
+
+    fn is_emissive(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,26 @@
+use crate::color::Color;
+use crate::light::{Light, LightSample};
+use crate::vector::{Point3, Vector3};
+
+//平行光（如太阳）：来自无穷远处，所有光线都沿direction方向传播，垂直于光线的平面上的照度处处为color · intensity
+pub struct DirectionalLight {
+    direction: Vector3,
+    pub irradiance: Color,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: &Vector3, color: &Color, intensity: f64) -> Self {
+        DirectionalLight { direction: direction.unit(), irradiance: *color * intensity }
+    }
+
+    //光线传播的方向
+    pub fn direction(&self) -> Vector3 {
+        self.direction
+    }
+}
+
+impl Light for DirectionalLight {
+    fn sample(&self, _p: &Point3) -> Option<LightSample> {
+        Some(LightSample { direction: -self.direction, distance: f64::INFINITY, irradiance: self.irradiance })
+    }
+}
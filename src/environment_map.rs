@@ -0,0 +1,169 @@
+use std::f64::consts::PI;
+use std::io;
+use std::path::Path;
+
+use crate::background::Background;
+use crate::color::Color;
+use crate::output::{load_image, Image};
+use crate::ray::Ray;
+use crate::utils::{degrees_to_radians, random_f64, Sampler};
+use crate::vector::Vector3;
+
+//等距柱状投影（equirectangular）的环境贴图，一般是Radiance .hdr或OpenEXR格式的高动态范围全景图
+//图片的第一行是正上方（+y），最后一行是正下方，图片中间一列朝向-z，u增大时绕y轴从-x经过-z转向+x。
+//rotation让环境绕y轴旋转（角度），intensity缩放亮度。
+//
+//取像素用最近邻，像素在整个球面上按 亮度·sinθ 的分段常数分布做重要性采样（θ是与+y轴的夹角，
+//sinθ补偿了靠近两极的像素所占立体角变小），贴图中很小很亮的太阳也能被直接光照采样找到，不会产生萤火虫噪点
+pub struct EnvironmentMap {
+    image: Image,
+    intensity: f64,
+    sin_rotation: f64,
+    cos_rotation: f64,
+    distribution: Option<Distribution2d>, //全黑的贴图没有采样分布
+}
+
+impl EnvironmentMap {
+    pub fn new(image: Image, intensity: f64, rotation: f64) -> Self {
+        let (width, height) = (image.width, image.height);
+        let rows: Vec<Vec<f64>> = (0..height).map(|j| {
+            let sin_theta = (PI * (j as f64 + 0.5) / height as f64).sin();
+            (0..width).map(|i| image.pixels[j * width + i].luminance().max(0.0) * sin_theta).collect()
+        }).collect();
+        let distribution = Distribution2d::new(rows);
+
+        let rotation = degrees_to_radians(rotation);
+        EnvironmentMap { image, intensity, sin_rotation: rotation.sin(), cos_rotation: rotation.cos(), distribution }
+    }
+
+    pub fn load(path: &Path, intensity: f64, rotation: f64) -> io::Result<Self> {
+        let image = load_image(path)?;
+        if image.width == 0 || image.height == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("empty image: {}", path.display())));
+        }
+
+        Ok(EnvironmentMap::new(image, intensity, rotation))
+    }
+
+    //世界坐标系中的方向对应的贴图坐标(u, v)，都在[0, 1]之间，v = 0是正上方
+    fn direction_to_uv(&self, direction: &Vector3) -> (f64, f64) {
+        let d = direction.unit();
+        //先把方向反向旋转到贴图自身的坐标系中
+        let x = self.cos_rotation * d.x - self.sin_rotation * d.z;
+        let z = self.sin_rotation * d.x + self.cos_rotation * d.z;
+
+        let theta = d.y.clamp(-1.0, 1.0).acos();
+        let phi = x.atan2(-z);
+        (0.5 + phi / (2.0 * PI), theta / PI)
+    }
+
+    fn uv_to_direction(&self, u: f64, v: f64) -> Vector3 {
+        let theta = v * PI;
+        let phi = (u - 0.5) * 2.0 * PI;
+        let (x, y, z) = (theta.sin() * phi.sin(), theta.cos(), -theta.sin() * phi.cos());
+
+        Vector3::new(self.cos_rotation * x + self.sin_rotation * z, y, -self.sin_rotation * x + self.cos_rotation * z)
+    }
+
+    fn pixel(&self, u: f64, v: f64) -> (usize, usize) {
+        let i = ((u * self.image.width as f64) as usize).min(self.image.width - 1);
+        let j = ((v * self.image.height as f64) as usize).min(self.image.height - 1);
+        (i, j)
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn color(&self, ray: &Ray) -> Color {
+        let (u, v) = self.direction_to_uv(&ray.dir);
+        let (i, j) = self.pixel(u, v);
+        self.image.pixels[j * self.image.width + i] * self.intensity
+    }
+
+    fn sample(&self, sampler: &mut Sampler) -> Option<Vector3> {
+        let distribution = self.distribution.as_ref()?;
+        let (u, v) = distribution.sample(random_f64(sampler), random_f64(sampler));
+        Some(self.uv_to_direction(u, v))
+    }
+
+    //贴图上的概率密度p(u, v)换算到立体角：dω = 2π · π · sinθ · du · dv
+    fn pdf(&self, direction: &Vector3) -> f64 {
+        let Some(distribution) = &self.distribution else {
+            return 0.0;
+        };
+
+        let (u, v) = self.direction_to_uv(direction);
+        let sin_theta = (v * PI).sin();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+
+        let (i, j) = self.pixel(u, v);
+        distribution.pdf(i, j) / (2.0 * PI * PI * sin_theta)
+    }
+}
+
+//[0, 1)上的分段常数分布，第i段的函数值为func[i]
+struct Distribution1d {
+    func: Vec<f64>,
+    cdf: Vec<f64>,
+    integral: f64, //函数在[0, 1)上的积分
+}
+
+impl Distribution1d {
+    fn new(func: Vec<f64>) -> Self {
+        let n = func.len() as f64;
+        let mut cdf = Vec::with_capacity(func.len() + 1);
+        cdf.push(0.0);
+        for f in &func {
+            cdf.push(cdf.last().unwrap() + f / n);
+        }
+
+        let integral = *cdf.last().unwrap();
+        if integral > 0.0 {
+            for c in cdf.iter_mut() {
+                *c /= integral;
+            }
+        }
+
+        Distribution1d { func, cdf, integral }
+    }
+
+    //按分布把[0, 1)上均匀分布的xi映射为连续的样本x，同时返回x所在的段
+    fn sample(&self, xi: f64) -> (f64, usize) {
+        //最后一个cdf[i] <= xi的i，跳过函数值为0的段
+        let i = (self.cdf.partition_point(|&c| c <= xi) - 1).min(self.func.len() - 1);
+        let width = self.cdf[i + 1] - self.cdf[i];
+        let offset = if width > 0.0 { (xi - self.cdf[i]) / width } else { 0.0 };
+
+        ((i as f64 + offset) / self.func.len() as f64, i)
+    }
+}
+
+//单位正方形上的分段常数分布：先按每行的积分（边缘分布）选一行v，再在这一行中按条件分布选u
+struct Distribution2d {
+    rows: Vec<Distribution1d>,
+    marginal: Distribution1d,
+}
+
+impl Distribution2d {
+    fn new(rows: Vec<Vec<f64>>) -> Option<Self> {
+        let rows: Vec<Distribution1d> = rows.into_iter().map(Distribution1d::new).collect();
+        let marginal = Distribution1d::new(rows.iter().map(|row| row.integral).collect());
+        if marginal.integral <= 0.0 {
+            return None;
+        }
+
+        Some(Distribution2d { rows, marginal })
+    }
+
+    fn sample(&self, xi_u: f64, xi_v: f64) -> (f64, f64) {
+        let (v, j) = self.marginal.sample(xi_v);
+        let (u, _) = self.rows[j].sample(xi_u);
+        (u, v)
+    }
+
+    //第j行第i列的概率密度（相对于单位正方形的面积）
+    fn pdf(&self, i: usize, j: usize) -> f64 {
+        self.rows[j].func[i] / self.marginal.integral
+    }
+}
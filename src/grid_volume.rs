@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::color::Color;
+use crate::diffuse_light::DiffuseLight;
+use crate::hittable::{HitInfo, Hittable};
+use crate::isotropic::Isotropic;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::utils::{random_f64, Sampler};
+use crate::vector::Point3;
+use crate::voxel_grid::VoxelGrid;
+
+//密度由体素网格给出的非均匀参与介质（如模拟得到的烟雾），网格拉伸后充满[minimum, maximum]这个长方体
+//
+//p处的吸收系数和散射系数分别为absorption·density(p)和scattering·density(p)，density(p)是网格的三线性插值。
+//介质中的自由程用delta tracking采样：以整个网格上消光系数的最大值（majorant）为密度采样候选碰撞点，
+//再按真实消光系数与majorant之比决定是真实碰撞还是虚碰撞（继续前进），结果是无偏的。
+//真实碰撞按吸收系数和散射系数之比分为吸收和散射：散射时使用各向同性相函数，吸收时路径终止，
+//并带回自发光emission / absorption，即发光系数emission·density(p)在路径上的积分
+pub struct GridVolume {
+    grid: Arc<VoxelGrid>,
+    bbox: Aabb,
+    absorption: f64,
+    scattering: f64,
+    majorant: f64, //消光系数的上界
+    phase_function: Arc<dyn Material>,
+    absorber: Arc<dyn Material>, //吸收事件的材质：不散射，发出emission / absorption的光
+}
+
+impl GridVolume {
+    //absorption和scattering必须非负；默认散射的反照率为白色，不发光
+    pub fn new(grid: Arc<VoxelGrid>, minimum: &Point3, maximum: &Point3, absorption: f64, scattering: f64) -> Self {
+        assert!(absorption >= 0.0 && scattering >= 0.0, "volume coefficients must be non-negative");
+        assert!(minimum.x < maximum.x && minimum.y < maximum.y && minimum.z < maximum.z, "volume bounds must not be empty");
+
+        let majorant = (absorption + scattering) * grid.max();
+        GridVolume {
+            grid,
+            bbox: Aabb::new(*minimum, *maximum),
+            absorption,
+            scattering,
+            majorant,
+            phase_function: Arc::new(Isotropic::new(&Color::new(1.0, 1.0, 1.0))),
+            absorber: Arc::new(DiffuseLight::new(&Color::black())),
+        }
+    }
+
+    //散射的颜色
+    pub fn set_albedo(&mut self, albedo: &Color) {
+        self.phase_function = Arc::new(Isotropic::new(albedo));
+    }
+
+    //发光系数，按网格密度缩放；只有吸收的物质才能发光，所以absorption必须大于0
+    pub fn set_emission(&mut self, emission: &Color) {
+        assert!(self.absorption > 0.0, "an emissive volume needs a positive absorption coefficient");
+
+        let radiance = *emission / self.absorption;
+        self.absorber = Arc::new(DiffuseLight::new(&radiance));
+    }
+
+    pub fn grid(&self) -> &VoxelGrid {
+        &self.grid
+    }
+
+    pub fn majorant(&self) -> f64 {
+        self.majorant
+    }
+
+    //p处的网格密度
+    pub fn density(&self, p: &Point3) -> f64 {
+        let size = self.bbox.maximum - self.bbox.minimum;
+        let q = *p - self.bbox.minimum;
+        self.grid.sample(&Point3::new(q.x / size.x, q.y / size.y, q.z / size.z))
+    }
+}
+
+impl Hittable for GridVolume {
+    //delta tracking采样第一个真实碰撞点，没有发生真实碰撞时光线穿过介质
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        if self.majorant <= 0.0 {
+            return None;
+        }
+        let (mut t, t_exit) = self.bbox.intersect(r, t_min, t_max)?;
+
+        //t按没有归一化的方向计算，majorant是单位长度上的碰撞密度
+        let step = 1.0 / (self.majorant * r.dir.length());
+        loop {
+            t -= (1.0 - random_f64(sampler)).ln() * step;
+            if t >= t_exit {
+                return None;
+            }
+
+            let pos = r.at(t);
+            let density = self.density(&pos);
+            let xi = random_f64(sampler) * self.majorant;
+            let material = if xi < self.absorption * density {
+                self.absorber.as_ref()
+            } else if xi < (self.absorption + self.scattering) * density {
+                self.phase_function.as_ref()
+            } else {
+                continue;
+            };
+
+            //碰撞点没有表面，法线取与光线相反的方向
+            return Some(HitInfo::new(r, pos, &-r.dir.unit(), t, 0.0, 0.0, material));
+        }
+    }
+
+    fn surface_hit(&self, _r: &Ray, _t_min: f64, _t_max: f64, _sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        None
+    }
+
+    //阴影射线穿过介质的透射率，用ratio tracking无偏估计：
+    //在同样的候选碰撞点上不做随机选择，而是把透射率乘以虚碰撞的概率1 - σt(p) / majorant
+    fn transmittance(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> f64 {
+        let (mut t, t_exit) = match self.bbox.intersect(r, t_min, t_max) {
+            Some(interval) if self.majorant > 0.0 => interval,
+            _ => return 1.0,
+        };
+
+        let step = 1.0 / (self.majorant * r.dir.length());
+        let extinction = self.absorption + self.scattering;
+        let mut transmittance = 1.0;
+        loop {
+            t -= (1.0 - random_f64(sampler)).ln() * step;
+            if t >= t_exit {
+                return transmittance;
+            }
+            transmittance *= 1.0 - extinction * self.density(&r.at(t)) / self.majorant;
+        }
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(self.bbox)
+    }
+}
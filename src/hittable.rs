@@ -1,17 +1,26 @@
+use crate::color::Color;
+use crate::utils::Sampler;
 use crate::vector::{Vector3, Point3};
 use crate::ray::Ray;
 use crate::material::Material;
+use crate::aabb::Aabb;
+
 pub struct HitInfo<'a> {
     pub pos: Point3,
     pub normal: Vector3,
     pub t: f64,
     pub front_face: bool, //光线是否来自于正面，对于球体来说，正面指的球体的外面
+    pub u: f64, //击中点的表面坐标，用于纹理映射
+    pub v: f64,
+    pub local_pos: Point3, //击中点在物体自身空间中的位置，实体纹理按它取值，使纹理随物体的变换一起移动
+    pub is_light: bool, //击中的是World光源列表中的物体，它的发光已经由直接光照采样计入
+    pub vertex_color: Option<Color>, //网格的顶点颜色在击中点的插值，没有顶点颜色时为None
 
     pub material: &'a dyn Material
 }
 
 impl<'a> HitInfo<'a> {
-    pub fn new(r: &Ray, pos: Point3, outward_normal: &Vector3, t: f64, material: &'a dyn Material) ->Self {
+    pub fn new(r: &Ray, pos: Point3, outward_normal: &Vector3, t: f64, u: f64, v: f64, material: &'a dyn Material) ->Self {
         let front_face = !HitInfo::with_same_direction(&r.dir, outward_normal);
         let normal = HitInfo::correct_normal(front_face, outward_normal);
 
@@ -20,6 +29,11 @@ impl<'a> HitInfo<'a> {
             normal,
             t,
             front_face,
+            u,
+            v,
+            local_pos: pos,
+            is_light: false,
+            vertex_color: None,
 
             material,
         }
@@ -44,6 +58,89 @@ impl<'a> HitInfo<'a> {
     }
 }
 
-pub trait Hittable {
-    fn hit(&self, r: &Ray, t_min: f64, t_max: f64) -> Option<HitInfo>;
+//sampler供求交时需要随机采样的物体（如参与介质）使用
+pub trait Hittable: Send + Sync {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>>;
+
+    //物体在[time0, time1]时间段内的包围盒，无法用包围盒界定的物体（如无限大平面）返回None
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb>;
+
+    //从origin沿direction看向物体时，按立体角计算的采样概率密度，与random配对用于对光源做重要性采样
+    //不能作为光源采样的物体返回0
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vector3, _sampler: &mut Sampler) -> f64 {
+        0.0
+    }
+
+    //从origin指向物体上随机一点的方向（不一定是单位向量），分布的概率密度由pdf_value给出
+    fn random(&self, _origin: &Point3, _sampler: &mut Sampler) -> Vector3 {
+        Vector3::new(1.0, 0.0, 0.0)
+    }
+
+    //与hit相同，但忽略参与介质，阴影射线用它寻找挡住光线的表面；介质对光线的遮挡由transmittance给出
+    fn surface_hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        self.hit(r, t_min, t_max, sampler)
+    }
+
+    //光线在[t_min, t_max]内穿过物体中参与介质的透射率，表面不影响透射率
+    fn transmittance(&self, _r: &Ray, _t_min: f64, _t_max: f64, _sampler: &mut Sampler) -> f64 {
+        1.0
+    }
+}
+
+//在面积为area的物体表面上均匀取点时，换算到origin处立体角上的概率密度：距离^2 / (|cosθ|·面积)，
+//θ是direction与击中点法线的夹角；沿direction看不到物体时为0
+pub(crate) fn area_pdf_value(object: &dyn Hittable, area: f64, origin: &Point3, direction: &Vector3, sampler: &mut Sampler) -> f64 {
+    let Some(hit_info) = object.hit(&Ray::new(*origin, *direction, 0.0), 0.001, f64::INFINITY, sampler) else {
+        return 0.0;
+    };
+
+    let distance_squared = hit_info.t * hit_info.t * direction.length_squared();
+    let cosine = (direction.dot(&hit_info.normal) / direction.length()).abs();
+    if cosine <= 0.0 {
+        return 0.0;
+    }
+
+    distance_squared / (cosine * area)
+}
+
+//把物体的外法线反向，物体本身不变，用于让矩形等单面物体的“正面”朝向另一侧
+pub struct FlipFace<T: Hittable> {
+    pub object: T,
+}
+
+impl<T: Hittable> FlipFace<T> {
+    pub fn new(object: T) -> Self {
+        FlipFace { object }
+    }
+}
+
+impl<T: Hittable> Hittable for FlipFace<T> {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        //HitInfo中的法线总是与光线方向相反，外法线反向只改变光线来自正面还是背面
+        let mut hit_info = self.object.hit(r, t_min, t_max, sampler)?;
+        hit_info.front_face = !hit_info.front_face;
+        Some(hit_info)
+    }
+
+    fn surface_hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        let mut hit_info = self.object.surface_hit(r, t_min, t_max, sampler)?;
+        hit_info.front_face = !hit_info.front_face;
+        Some(hit_info)
+    }
+
+    fn transmittance(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> f64 {
+        self.object.transmittance(r, t_min, t_max, sampler)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        self.object.bounding_box(time0, time1)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3, sampler: &mut Sampler) -> f64 {
+        self.object.pdf_value(origin, direction, sampler)
+    }
+
+    fn random(&self, origin: &Point3, sampler: &mut Sampler) -> Vector3 {
+        self.object.random(origin, sampler)
+    }
 }
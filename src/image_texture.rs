@@ -0,0 +1,40 @@
+use std::io;
+use std::path::Path;
+
+use crate::color::Color;
+use crate::output::{load_image, Image};
+use crate::texture::Texture;
+use crate::vector::Point3;
+
+//图片纹理：u从左到右、v从下到上覆盖整张图片，取最近的像素
+pub struct ImageTexture {
+    image: Image,
+}
+
+impl ImageTexture {
+    pub fn new(image: Image) -> Self {
+        ImageTexture { image }
+    }
+
+    //支持ppm、png、hdr、exr，8位和16位的图片按sRGB解码为线性颜色值
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let image = load_image(path)?;
+        if image.width == 0 || image.height == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("empty image: {}", path.display())));
+        }
+
+        Ok(ImageTexture::new(image))
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _p: &Point3) -> Color {
+        let u = u.clamp(0.0, 1.0);
+        let v = 1.0 - v.clamp(0.0, 1.0); //图片的第一行在最上面
+
+        let i = ((u * self.image.width as f64) as usize).min(self.image.width - 1);
+        let j = ((v * self.image.height as f64) as usize).min(self.image.height - 1);
+
+        self.image.pixels[j * self.image.width + i]
+    }
+}
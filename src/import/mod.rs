@@ -0,0 +1,44 @@
+// 三维模型文件的导入，网格格式转换为TriangleMesh，体素格式转换为VoxelGrid
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+mod obj;
+mod ply;
+mod stl;
+mod voxels;
+
+pub use self::obj::{load_mtl, load_obj, parse_obj, ObjMesh};
+pub use self::ply::{load_ply, parse_ply};
+pub use self::stl::{load_stl, parse_stl};
+pub use self::voxels::{load_raw_voxels, load_vgrid, parse_raw_voxels, parse_vgrid};
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io { path: PathBuf, error: io::Error },
+    Parse { path: PathBuf, line: usize, message: String },
+    Data { path: PathBuf, offset: u64, message: String }, //二进制数据中的错误，offset为出错位置的字节偏移
+}
+
+impl ImportError {
+    fn io(path: &Path, error: io::Error) -> Self {
+        ImportError::Io { path: path.to_path_buf(), error }
+    }
+
+    fn parse(path: &Path, line: usize, message: String) -> Self {
+        ImportError::Parse { path: path.to_path_buf(), line, message }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            ImportError::Parse { path, line, message } => write!(f, "{}:{}: {}", path.display(), line, message),
+            ImportError::Data { path, offset, message } => write!(f, "{}: at byte {}: {}", path.display(), offset, message),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
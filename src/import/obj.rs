@@ -0,0 +1,379 @@
+// Wavefront OBJ和MTL文件的导入
+//
+// 支持的OBJ指令：v、vt、vn、f（任意边数的多边形按扇形剖分为三角形，顶点写作i、i/t、i//n或i/t/n，
+// 负数下标表示相对于当前已定义元素的倒数位置）、g、o、usemtl、mtllib、s。其余指令（l、p、曲线曲面等）被忽略。
+//
+// MTL材质按以下规则转换，前面的规则优先：
+//   Ke不为0                           -> DiffuseLight，发光颜色为Ke
+//   d小于1、Tr大于0或illum为4、6、7    -> Dielectric，折射率为Ni（默认1.5）
+//   Ks最大分量大于Kd最大分量           -> Metal，颜色为Ks，fuzz由高光指数Ns换算：sqrt(2 / (Ns + 2))
+//   其他                               -> Lambertian，颜色为Kd，给出map_Kd时使用图片纹理
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::dielectric::Dielectric;
+use crate::diffuse_light::DiffuseLight;
+use crate::image_texture::ImageTexture;
+use crate::lambertian::Lambertian;
+use crate::material::Material;
+use crate::metal::Metal;
+use crate::triangle_mesh::{MeshData, MeshFace, TriangleMesh};
+use crate::vector::{Point3, Vector3};
+use super::ImportError;
+
+//OBJ文件中同一个组里使用同一种材质的三角形
+pub struct ObjMesh {
+    pub group: String, //g或o给出的名称，没有时为空
+    pub material: String, //usemtl给出的材质名称，没有时为空
+    pub mesh: TriangleMesh,
+}
+
+//读取OBJ文件及其引用的MTL文件，按组和材质拆分成若干网格
+//没有指定材质或材质在MTL文件中找不到时使用default_material
+pub fn load_obj(path: &Path, default_material: Arc<dyn Material>) -> Result<Vec<ObjMesh>, ImportError> {
+    let text = fs::read_to_string(path).map_err(|e| ImportError::io(path, e))?;
+    parse_obj(&text, path, default_material)
+}
+
+//解析OBJ文本，path用于错误信息和查找mtllib引用的文件（相对于path所在的目录）
+pub fn parse_obj(text: &str, path: &Path, default_material: Arc<dyn Material>) -> Result<Vec<ObjMesh>, ImportError> {
+    let mut parser = ObjParser::new(path, default_material);
+
+    for (index, line) in text.lines().enumerate() {
+        parser.parse_line(index + 1, line)?;
+    }
+
+    parser.finish()
+}
+
+//读取MTL文件，返回材质名称到材质的映射
+pub fn load_mtl(path: &Path) -> Result<HashMap<String, Arc<dyn Material>>, ImportError> {
+    let text = fs::read_to_string(path).map_err(|e| ImportError::io(path, e))?;
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+    let error = |line: usize, message: String| ImportError::parse(path, line, message);
+
+    let mut entries: Vec<MtlEntry> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let mut tokens = line.split('#').next().unwrap_or("").split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if args.is_empty() {
+                return Err(error(line_number, "newmtl: missing material name".to_string()));
+            }
+            entries.push(MtlEntry::new(args.join(" ")));
+            continue;
+        }
+
+        let entry = match entries.last_mut() {
+            Some(entry) => entry,
+            //newmtl之前只允许出现不影响材质的指令
+            None => return Err(error(line_number, format!("{}: no material defined yet, expected newmtl first", keyword))),
+        };
+
+        let number = |i: usize| -> Result<f64, ImportError> {
+            let arg = args.get(i).ok_or_else(|| error(line_number, format!("{}: missing value", keyword)))?;
+            arg.parse::<f64>().ok().filter(|v| v.is_finite())
+                .ok_or_else(|| error(line_number, format!("{}: expected a number, got `{}`", keyword, arg)))
+        };
+        //颜色可以只写一个数，表示三个分量相同
+        let color = || -> Result<Color, ImportError> {
+            let r = number(0)?;
+            if args.len() == 1 {
+                return Ok(Color::new(r, r, r));
+            }
+            Ok(Color::new(r, number(1)?, number(2)?))
+        };
+
+        match keyword {
+            "Kd" => entry.kd = color()?,
+            "Ks" => entry.ks = color()?,
+            "Ke" => entry.ke = color()?,
+            "Ns" => entry.ns = number(0)?,
+            "Ni" => entry.ni = Some(number(0)?),
+            "d" => entry.dissolve = number(0)?,
+            "Tr" => entry.dissolve = 1.0 - number(0)?,
+            "illum" => entry.illum = number(0)? as i32,
+            "map_Kd" => {
+                //贴图选项（如-s 1 1 1）写在文件名之前，这里只取最后一个参数作为文件名
+                let file = args.last().ok_or_else(|| error(line_number, "map_Kd: missing file name".to_string()))?;
+                let texture = ImageTexture::load(&base_dir.join(file))
+                    .map_err(|e| error(line_number, format!("map_Kd: cannot load image `{}`: {}", file, e)))?;
+                entry.map_kd = Some(Arc::new(texture));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(entries.into_iter().map(|entry| (entry.name.clone(), entry.into_material())).collect())
+}
+
+//MTL文件中的一个材质
+struct MtlEntry {
+    name: String,
+    kd: Color,
+    ks: Color,
+    ke: Color,
+    ns: f64,
+    ni: Option<f64>,
+    dissolve: f64,
+    illum: i32,
+    map_kd: Option<Arc<ImageTexture>>,
+}
+
+impl MtlEntry {
+    fn new(name: String) -> Self {
+        MtlEntry {
+            name,
+            kd: Color::new(0.8, 0.8, 0.8),
+            ks: Color::black(),
+            ke: Color::black(),
+            ns: 0.0,
+            ni: None,
+            dissolve: 1.0,
+            illum: 2,
+            map_kd: None,
+        }
+    }
+
+    fn into_material(self) -> Arc<dyn Material> {
+        let max = |c: &Color| c.f64_r().max(c.f64_g()).max(c.f64_b());
+
+        if max(&self.ke) > 0.0 {
+            return Arc::new(DiffuseLight::new(&self.ke));
+        }
+        if self.dissolve < 1.0 || matches!(self.illum, 4 | 6 | 7) {
+            return Arc::new(Dielectric::new(self.ni.unwrap_or(1.5)));
+        }
+        if max(&self.ks) > max(&self.kd) {
+            let fuzz = (2.0 / (self.ns.max(0.0) + 2.0)).sqrt();
+            return Arc::new(Metal::new(&self.ks, fuzz));
+        }
+
+        match self.map_kd {
+            Some(texture) => Arc::new(Lambertian::with_texture(texture)),
+            None => Arc::new(Lambertian::new(&self.kd)),
+        }
+    }
+}
+
+//网格按(组名, 材质名, 是否平滑)拆分
+type MeshKey = (String, String, bool);
+
+struct ObjParser<'a> {
+    path: &'a Path,
+    default_material: Arc<dyn Material>,
+    materials: HashMap<String, Arc<dyn Material>>,
+    positions: Vec<Point3>,
+    normals: Vec<Vector3>,
+    uvs: Vec<(f64, f64)>,
+    group: String,
+    material: String,
+    smooth: bool,
+    meshes: Vec<(MeshKey, MeshBuilder)>,
+}
+
+impl<'a> ObjParser<'a> {
+    fn new(path: &'a Path, default_material: Arc<dyn Material>) -> Self {
+        ObjParser {
+            path,
+            default_material,
+            materials: HashMap::new(),
+            positions: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            group: String::new(),
+            material: String::new(),
+            smooth: false,
+            meshes: Vec::new(),
+        }
+    }
+
+    fn error(&self, line: usize, message: String) -> ImportError {
+        ImportError::parse(self.path, line, message)
+    }
+
+    fn parse_line(&mut self, line: usize, text: &str) -> Result<(), ImportError> {
+        let mut tokens = text.split('#').next().unwrap_or("").split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => return Ok(()),
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => {
+                let v = self.numbers(line, keyword, &args, 3)?;
+                self.positions.push(Point3::new(v[0], v[1], v[2]));
+            }
+            "vn" => {
+                let n = self.numbers(line, keyword, &args, 3)?;
+                self.normals.push(Vector3::new(n[0], n[1], n[2]));
+            }
+            "vt" => {
+                //v可以省略，默认为0
+                let t = self.numbers(line, keyword, &args, 1)?;
+                let v = if args.len() > 1 { self.numbers(line, keyword, &args[1..], 1)?[0] } else { 0.0 };
+                self.uvs.push((t[0], v));
+            }
+            "f" => self.parse_face(line, &args)?,
+            "g" | "o" => self.group = args.join(" "),
+            "usemtl" => self.material = args.join(" "),
+            "s" => self.smooth = !matches!(args.first(), None | Some(&"off") | Some(&"0")),
+            "mtllib" => {
+                let base_dir = self.path.parent().unwrap_or(Path::new(""));
+                for file in &args {
+                    let materials = load_mtl(&base_dir.join(file))?;
+                    self.materials.extend(materials);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    //前count个参数解析为数值，多出的参数（如v后面的权重或顶点颜色）被忽略
+    fn numbers(&self, line: usize, keyword: &str, args: &[&str], count: usize) -> Result<Vec<f64>, ImportError> {
+        if args.len() < count {
+            return Err(self.error(line, format!("{}: expected {} numbers, got {}", keyword, count, args.len())));
+        }
+
+        args[..count].iter()
+            .map(|arg| arg.parse::<f64>().ok().filter(|v| v.is_finite())
+                .ok_or_else(|| self.error(line, format!("{}: expected a number, got `{}`", keyword, arg))))
+            .collect()
+    }
+
+    fn parse_face(&mut self, line: usize, args: &[&str]) -> Result<(), ImportError> {
+        if args.len() < 3 {
+            return Err(self.error(line, format!("f: a face needs at least 3 vertices, got {}", args.len())));
+        }
+
+        let mut vertices = Vec::with_capacity(args.len());
+        for arg in args {
+            let mut parts = arg.split('/');
+            let position = self.index(line, parts.next(), self.positions.len(), "vertex")?
+                .ok_or_else(|| self.error(line, format!("f: missing vertex index in `{}`", arg)))?;
+            let uv = self.index(line, parts.next(), self.uvs.len(), "texture coordinate")?;
+            let normal = self.index(line, parts.next(), self.normals.len(), "normal")?;
+            if parts.next().is_some() {
+                return Err(self.error(line, format!("f: bad vertex `{}`", arg)));
+            }
+            vertices.push((position, uv, normal));
+        }
+
+        let key = (self.group.clone(), self.material.clone(), self.smooth);
+        let builder = match self.meshes.iter().position(|(k, _)| *k == key) {
+            Some(i) => &mut self.meshes[i].1,
+            None => {
+                self.meshes.push((key, MeshBuilder::default()));
+                &mut self.meshes.last_mut().unwrap().1
+            }
+        };
+
+        //凸多边形按扇形剖分：(0, i, i+1)
+        for i in 1..vertices.len() - 1 {
+            let corners = [vertices[0], vertices[i], vertices[i + 1]];
+            builder.add_face(&corners, &self.positions, &self.normals, &self.uvs);
+        }
+
+        Ok(())
+    }
+
+    //解析顶点中的一个下标，返回从0开始的下标；空字符串表示省略
+    fn index(&self, line: usize, text: Option<&str>, count: usize, what: &str) -> Result<Option<usize>, ImportError> {
+        let text = match text {
+            Some(text) if !text.is_empty() => text,
+            _ => return Ok(None),
+        };
+
+        let i: i64 = text.parse()
+            .map_err(|_| self.error(line, format!("f: bad {} index `{}`", what, text)))?;
+        let resolved = if i > 0 { i - 1 } else { count as i64 + i };
+
+        if i == 0 || resolved < 0 || resolved >= count as i64 {
+            return Err(self.error(line, format!("f: {} index {} out of range ({} defined so far)", what, i, count)));
+        }
+
+        Ok(Some(resolved as usize))
+    }
+
+    fn finish(self) -> Result<Vec<ObjMesh>, ImportError> {
+        let mut meshes = Vec::with_capacity(self.meshes.len());
+
+        for ((group, material_name, smooth), builder) in self.meshes {
+            let material = self.materials.get(&material_name).cloned().unwrap_or_else(|| self.default_material.clone());
+            let mut data = builder.data;
+
+            //所有面都给出了顶点法线时直接插值；开启了平滑组但没有法线时自动计算顶点法线
+            let has_normals = data.faces.iter().all(|f| f.normals.is_some());
+            if smooth && !has_normals {
+                data.compute_vertex_normals();
+            }
+            let smooth = smooth || has_normals;
+
+            //MeshBuilder重新编号后的下标一定在范围之内
+            let mesh = TriangleMesh::new(data, material, smooth).expect("remapped OBJ indices are always in range");
+            meshes.push(ObjMesh { group, material: material_name, mesh });
+        }
+
+        Ok(meshes)
+    }
+}
+
+//一个网格的顶点缓冲区，只保存被该网格用到的顶点，下标重新编号
+#[derive(Default)]
+struct MeshBuilder {
+    data: MeshData,
+    position_map: HashMap<usize, usize>,
+    normal_map: HashMap<usize, usize>,
+    uv_map: HashMap<usize, usize>,
+}
+
+type Corner = (usize, Option<usize>, Option<usize>);
+
+impl MeshBuilder {
+    fn add_face(&mut self, corners: &[Corner; 3], positions: &[Point3], normals: &[Vector3], uvs: &[(f64, f64)]) {
+        let mut face = MeshFace::new([0; 3]);
+        let mut face_uvs = [0; 3];
+        let mut face_normals = [0; 3];
+
+        for (k, &(p, t, n)) in corners.iter().enumerate() {
+            face.positions[k] = remap(&mut self.position_map, &mut self.data.positions, positions, p);
+            if let Some(t) = t {
+                face_uvs[k] = remap(&mut self.uv_map, &mut self.data.uvs, uvs, t);
+            }
+            if let Some(n) = n {
+                face_normals[k] = remap(&mut self.normal_map, &mut self.data.normals, normals, n);
+            }
+        }
+
+        //三个顶点都给出了纹理坐标或法线时才使用
+        if corners.iter().all(|c| c.1.is_some()) {
+            face.uvs = Some(face_uvs);
+        }
+        if corners.iter().all(|c| c.2.is_some()) {
+            face.normals = Some(face_normals);
+        }
+
+        self.data.faces.push(face);
+    }
+}
+
+//把全局下标index对应的元素加入网格自己的缓冲区，返回它在网格中的下标
+fn remap<T: Copy>(map: &mut HashMap<usize, usize>, local: &mut Vec<T>, global: &[T], index: usize) -> usize {
+    *map.entry(index).or_insert_with(|| {
+        local.push(global[index]);
+        local.len() - 1
+    })
+}
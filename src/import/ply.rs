@@ -0,0 +1,381 @@
+// PLY（Stanford多边形格式）文件的导入，支持ascii、binary_little_endian和binary_big_endian三种编码
+//
+// vertex元素中识别x、y、z（位置），nx、ny、nz（法线），u、v / s、t / texture_u、texture_v（纹理坐标），
+// red、green、blue（顶点颜色，整数类型按最大值归一化，颜色值按sRGB解码为线性值）；
+// face元素中识别vertex_indices或vertex_index列表，多边形按扇形剖分为三角形。其余元素和属性被跳过。
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::lambertian::Lambertian;
+use crate::material::Material;
+use crate::output::srgb_to_linear;
+use crate::triangle_mesh::{MeshData, MeshFace, TriangleMesh};
+use crate::vector::{Point3, Vector3};
+use crate::vertex_color_texture::VertexColorTexture;
+use super::ImportError;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Encoding {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Option<Scalar> {
+        match name {
+            "char" | "int8" => Some(Scalar::I8),
+            "uchar" | "uint8" => Some(Scalar::U8),
+            "short" | "int16" => Some(Scalar::I16),
+            "ushort" | "uint16" => Some(Scalar::U16),
+            "int" | "int32" => Some(Scalar::I32),
+            "uint" | "uint32" => Some(Scalar::U32),
+            "float" | "float32" => Some(Scalar::F32),
+            "double" | "float64" => Some(Scalar::F64),
+            _ => None,
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+
+    //整数类型的颜色按该类型的最大值归一化
+    fn color_scale(self) -> f64 {
+        match self {
+            Scalar::I8 => 127.0,
+            Scalar::U8 => 255.0,
+            Scalar::I16 => 32767.0,
+            Scalar::U16 => 65535.0,
+            Scalar::I32 => 2147483647.0,
+            Scalar::U32 => 4294967295.0,
+            Scalar::F32 | Scalar::F64 => 1.0,
+        }
+    }
+}
+
+enum PropertyType {
+    Scalar(Scalar),
+    List { count: Scalar, item: Scalar },
+}
+
+struct Property {
+    name: String,
+    kind: PropertyType,
+}
+
+struct Element {
+    line: usize, //element所在的文件头行号
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+impl Element {
+    fn scalar_index(&self, names: &[&str]) -> Option<usize> {
+        self.properties.iter().position(|p| names.contains(&p.name.as_str()) && matches!(p.kind, PropertyType::Scalar(_)))
+    }
+}
+
+//读取PLY文件，有顶点颜色时材质为使用顶点颜色纹理的Lambertian，否则使用material
+//有顶点法线时使用平滑着色
+pub fn load_ply(path: &Path, material: Arc<dyn Material>) -> Result<TriangleMesh, ImportError> {
+    let data = fs::read(path).map_err(|e| ImportError::io(path, e))?;
+    parse_ply(&data, path, material)
+}
+
+//解析PLY文件的内容，path只用于错误信息
+pub fn parse_ply(data: &[u8], path: &Path, material: Arc<dyn Material>) -> Result<TriangleMesh, ImportError> {
+    let (encoding, elements, header_lines, body_start) = parse_header(data, path)?;
+    let mut body = Body::new(data, body_start, header_lines, encoding, path)?;
+
+    let mut mesh = MeshData::new();
+    let mut colors: Vec<Color> = Vec::new();
+    let mut has_colors = false;
+    let mut has_normals = false;
+    let mut row = Vec::new();
+    let mut list = Vec::new();
+
+    for element in &elements {
+        let is_vertex = element.name == "vertex";
+        let is_face = element.name == "face";
+
+        let position = [element.scalar_index(&["x"]), element.scalar_index(&["y"]), element.scalar_index(&["z"])];
+        let normal = [element.scalar_index(&["nx"]), element.scalar_index(&["ny"]), element.scalar_index(&["nz"])];
+        let uv = [element.scalar_index(&["u", "s", "texture_u"]), element.scalar_index(&["v", "t", "texture_v"])];
+        let color = [element.scalar_index(&["red"]), element.scalar_index(&["green"]), element.scalar_index(&["blue"])];
+        let indices = element.properties.iter().position(|p| {
+            (p.name == "vertex_indices" || p.name == "vertex_index") && matches!(p.kind, PropertyType::List { .. })
+        });
+
+        if is_vertex {
+            if position.iter().any(Option::is_none) {
+                return Err(ImportError::parse(path, element.line, "vertex element has no x, y, z properties".to_string()));
+            }
+            has_normals = normal.iter().all(Option::is_some);
+            has_colors = color.iter().all(Option::is_some);
+        }
+        if is_face && indices.is_none() {
+            return Err(ImportError::parse(path, element.line, "face element has no vertex_indices property".to_string()));
+        }
+
+        for _ in 0..element.count {
+            row.clear();
+            for (k, property) in element.properties.iter().enumerate() {
+                match property.kind {
+                    PropertyType::Scalar(scalar) => row.push(body.read(scalar)?),
+                    PropertyType::List { count, item } => {
+                        let n = body.read(count)?;
+                        if n < 0.0 || n.fract() != 0.0 {
+                            return Err(body.error(format!("bad list length {}", n)));
+                        }
+                        let is_indices = Some(k) == indices;
+                        if is_indices {
+                            list.clear();
+                        }
+                        for _ in 0..n as usize {
+                            let value = body.read(item)?;
+                            if is_indices {
+                                list.push(value);
+                            }
+                        }
+                        row.push(0.0);
+                    }
+                }
+            }
+
+            if is_vertex {
+                let get = |i: Option<usize>| row[i.unwrap()];
+                mesh.positions.push(Point3::new(get(position[0]), get(position[1]), get(position[2])));
+                if has_normals {
+                    mesh.normals.push(Vector3::new(get(normal[0]), get(normal[1]), get(normal[2])));
+                }
+                if let [Some(u), Some(v)] = uv {
+                    mesh.uvs.push((row[u], row[v]));
+                }
+                if has_colors {
+                    let channel = |i: Option<usize>| {
+                        let scale = match element.properties[i.unwrap()].kind {
+                            PropertyType::Scalar(scalar) => scalar.color_scale(),
+                            PropertyType::List { .. } => 1.0,
+                        };
+                        srgb_to_linear((row[i.unwrap()] / scale).clamp(0.0, 1.0))
+                    };
+                    colors.push(Color::new(channel(color[0]), channel(color[1]), channel(color[2])));
+                }
+            } else if is_face {
+                add_polygon(&mut mesh, &list, &body)?;
+            }
+        }
+    }
+
+    let has_uvs = !mesh.uvs.is_empty();
+    for face in &mut mesh.faces {
+        if has_normals {
+            face.normals = Some(face.positions);
+        }
+        if has_uvs {
+            face.uvs = Some(face.positions);
+        }
+    }
+
+    let material: Arc<dyn Material> = if has_colors {
+        mesh.colors = colors;
+        Arc::new(Lambertian::with_texture(Arc::new(VertexColorTexture::new())))
+    } else {
+        material
+    };
+
+    //add_polygon已经检查过顶点下标
+    Ok(TriangleMesh::new(mesh, material, has_normals).expect("PLY face indices are checked while reading"))
+}
+
+//把一个多边形按扇形剖分为三角形加入网格，检查顶点下标
+fn add_polygon(mesh: &mut MeshData, indices: &[f64], body: &Body) -> Result<(), ImportError> {
+    if indices.len() < 3 {
+        return Err(body.error(format!("a face needs at least 3 vertices, got {}", indices.len())));
+    }
+
+    let mut vertices = Vec::with_capacity(indices.len());
+    for &i in indices {
+        if i < 0.0 || i.fract() != 0.0 || i as usize >= mesh.positions.len() {
+            return Err(body.error(format!("vertex index {} out of range ({} vertices)", i, mesh.positions.len())));
+        }
+        vertices.push(i as usize);
+    }
+
+    for k in 1..vertices.len() - 1 {
+        mesh.faces.push(MeshFace::new([vertices[0], vertices[k], vertices[k + 1]]));
+    }
+
+    Ok(())
+}
+
+//解析文件头，返回编码、元素列表、文件头的行数和数据部分的起始字节
+fn parse_header(data: &[u8], path: &Path) -> Result<(Encoding, Vec<Element>, usize, usize), ImportError> {
+    let mut encoding = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut offset = 0;
+    let mut line_number = 0;
+
+    loop {
+        let end = match data[offset..].iter().position(|&b| b == b'\n') {
+            Some(end) => offset + end,
+            None => return Err(ImportError::parse(path, line_number + 1, "unexpected end of file in PLY header".to_string())),
+        };
+        let line = String::from_utf8_lossy(&data[offset..end]).trim_end_matches('\r').to_string();
+        offset = end + 1;
+        line_number += 1;
+
+        let error = |message: String| ImportError::parse(path, line_number, message);
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        if line_number == 1 {
+            if line != "ply" {
+                return Err(error("not a PLY file".to_string()));
+            }
+            continue;
+        }
+
+        match tokens.as_slice() {
+            [] => {}
+            ["comment", ..] | ["obj_info", ..] => {}
+            ["format", format, _version] => {
+                encoding = Some(match *format {
+                    "ascii" => Encoding::Ascii,
+                    "binary_little_endian" => Encoding::BinaryLittleEndian,
+                    "binary_big_endian" => Encoding::BinaryBigEndian,
+                    _ => return Err(error(format!("unknown PLY format `{}`", format))),
+                });
+            }
+            ["element", name, count] => {
+                let count = count.parse().map_err(|_| error(format!("bad element count `{}`", count)))?;
+                elements.push(Element { line: line_number, name: name.to_string(), count, properties: Vec::new() });
+            }
+            ["property", "list", count, item, name] => {
+                let element = elements.last_mut().ok_or_else(|| error("property before any element".to_string()))?;
+                let count = Scalar::parse(count).ok_or_else(|| error(format!("unknown property type `{}`", count)))?;
+                let item = Scalar::parse(item).ok_or_else(|| error(format!("unknown property type `{}`", item)))?;
+                element.properties.push(Property { name: name.to_string(), kind: PropertyType::List { count, item } });
+            }
+            ["property", scalar, name] => {
+                let element = elements.last_mut().ok_or_else(|| error("property before any element".to_string()))?;
+                let scalar = Scalar::parse(scalar).ok_or_else(|| error(format!("unknown property type `{}`", scalar)))?;
+                element.properties.push(Property { name: name.to_string(), kind: PropertyType::Scalar(scalar) });
+            }
+            ["end_header"] => break,
+            _ => return Err(error(format!("unexpected PLY header line `{}`", line))),
+        }
+    }
+
+    let encoding = encoding.ok_or_else(|| ImportError::parse(path, line_number, "missing format line in PLY header".to_string()))?;
+    //没有属性的元素不占数据，读取它时不会消耗输入，计数很大的话会空转很久
+    if let Some(element) = elements.iter().find(|e| e.properties.is_empty() && e.count > 0) {
+        return Err(ImportError::parse(path, element.line, format!("element `{}` has {} items but no properties", element.name, element.count)));
+    }
+    Ok((encoding, elements, line_number, offset))
+}
+
+//PLY的数据部分：文本格式按空白字符切分成数值并记录行号，二进制格式按字节偏移读取
+enum Source<'a> {
+    Ascii { tokens: Vec<(usize, &'a str)>, next: usize },
+    Binary { data: &'a [u8], offset: usize, big_endian: bool },
+}
+
+struct Body<'a> {
+    source: Source<'a>,
+    path: &'a Path,
+}
+
+impl<'a> Body<'a> {
+    fn new(data: &'a [u8], start: usize, header_lines: usize, encoding: Encoding, path: &'a Path) -> Result<Self, ImportError> {
+        let source = match encoding {
+            Encoding::Ascii => {
+                let text = std::str::from_utf8(&data[start..])
+                    .map_err(|_| ImportError::parse(path, header_lines + 1, "ASCII PLY data is not valid UTF-8".to_string()))?;
+                let tokens = text.lines().enumerate()
+                    .flat_map(|(i, line)| line.split_whitespace().map(move |token| (header_lines + i + 1, token)))
+                    .collect();
+                Source::Ascii { tokens, next: 0 }
+            }
+            Encoding::BinaryLittleEndian => Source::Binary { data, offset: start, big_endian: false },
+            Encoding::BinaryBigEndian => Source::Binary { data, offset: start, big_endian: true },
+        };
+
+        Ok(Body { source, path })
+    }
+
+    //文本格式报告行号，二进制格式报告字节偏移
+    fn error(&self, message: String) -> ImportError {
+        match &self.source {
+            Source::Ascii { tokens, next } => {
+                let line = tokens.get(next.saturating_sub(1)).map_or(0, |t| t.0);
+                ImportError::parse(self.path, line, message)
+            }
+            Source::Binary { offset, .. } => ImportError::Data { path: self.path.to_path_buf(), offset: *offset as u64, message },
+        }
+    }
+
+    fn read(&mut self, scalar: Scalar) -> Result<f64, ImportError> {
+        match &mut self.source {
+            Source::Ascii { tokens, next } => {
+                let (line, token) = match tokens.get(*next) {
+                    Some(&token) => token,
+                    None => {
+                        let line = tokens.last().map_or(0, |t| t.0);
+                        return Err(ImportError::parse(self.path, line, "unexpected end of PLY data".to_string()));
+                    }
+                };
+                *next += 1;
+                token.parse::<f64>().ok().filter(|v| v.is_finite())
+                    .ok_or_else(|| ImportError::parse(self.path, line, format!("expected a number, got `{}`", token)))
+            }
+            Source::Binary { data, offset, big_endian } => {
+                let size = scalar.size();
+                if *offset + size > data.len() {
+                    let message = "unexpected end of PLY data".to_string();
+                    return Err(ImportError::Data { path: self.path.to_path_buf(), offset: *offset as u64, message });
+                }
+
+                let mut bytes = [0u8; 8];
+                bytes[..size].copy_from_slice(&data[*offset..*offset + size]);
+                if *big_endian {
+                    bytes[..size].reverse();
+                }
+                *offset += size;
+
+                let value = match scalar {
+                    Scalar::I8 => bytes[0] as i8 as f64,
+                    Scalar::U8 => bytes[0] as f64,
+                    Scalar::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    Scalar::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    Scalar::I32 => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+                    Scalar::U32 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+                    Scalar::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+                    Scalar::F64 => f64::from_le_bytes(bytes),
+                };
+                Ok(value)
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// STL文件的导入，支持二进制和文本两种格式
+//
+// STL的每个三角形单独保存三个顶点，没有共享顶点，也没有纹理坐标和顶点法线，导入后使用平面着色。
+// 三角形自带的法线被忽略，正反面由顶点的环绕顺序决定。
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::material::Material;
+use crate::triangle_mesh::{MeshData, MeshFace, TriangleMesh};
+use crate::vector::Point3;
+use super::ImportError;
+
+//二进制STL：80字节文件头、4字节三角形数，之后每个三角形50字节
+const HEADER_SIZE: usize = 84;
+const TRIANGLE_SIZE: usize = 50;
+
+pub fn load_stl(path: &Path, material: Arc<dyn Material>) -> Result<TriangleMesh, ImportError> {
+    let data = fs::read(path).map_err(|e| ImportError::io(path, e))?;
+    parse_stl(&data, path, material)
+}
+
+//解析STL文件的内容，path只用于错误信息
+//有些二进制STL的文件头也以“solid”开头，所以先按文件长度判断是否为二进制格式
+pub fn parse_stl(data: &[u8], path: &Path, material: Arc<dyn Material>) -> Result<TriangleMesh, ImportError> {
+    let mesh = if is_binary(data) {
+        parse_binary(data)
+    } else if data.trim_ascii_start().starts_with(b"solid") {
+        parse_ascii(data, path)?
+    } else {
+        let message = "not an STL file: too short for binary STL and no `solid` keyword".to_string();
+        return Err(ImportError::Data { path: path.to_path_buf(), offset: 0, message });
+    };
+
+    Ok(TriangleMesh::new(mesh, material, false).expect("STL faces always reference their own vertices"))
+}
+
+fn is_binary(data: &[u8]) -> bool {
+    if data.len() < HEADER_SIZE {
+        return false;
+    }
+    let count = u32::from_le_bytes([data[80], data[81], data[82], data[83]]) as usize;
+    count.checked_mul(TRIANGLE_SIZE).and_then(|n| n.checked_add(HEADER_SIZE)) == Some(data.len())
+}
+
+fn parse_binary(data: &[u8]) -> MeshData {
+    let mut mesh = MeshData::new();
+
+    //每个三角形：法线（3个f32）、三个顶点（各3个f32）、2字节属性
+    for triangle in data[HEADER_SIZE..].chunks_exact(TRIANGLE_SIZE) {
+        let read_f32 = |at: usize| f32::from_le_bytes([triangle[at], triangle[at + 1], triangle[at + 2], triangle[at + 3]]) as f64;
+
+        let first = mesh.positions.len();
+        for k in 0..3 {
+            let at = 12 + 12 * k;
+            mesh.positions.push(Point3::new(read_f32(at), read_f32(at + 4), read_f32(at + 8)));
+        }
+        mesh.faces.push(MeshFace::new([first, first + 1, first + 2]));
+    }
+
+    mesh
+}
+
+fn parse_ascii(data: &[u8], path: &Path) -> Result<MeshData, ImportError> {
+    let text = std::str::from_utf8(data)
+        .map_err(|_| ImportError::Data { path: path.to_path_buf(), offset: 0, message: "ASCII STL is not valid UTF-8".to_string() })?;
+
+    let mut mesh = MeshData::new();
+    let mut facet: Vec<Point3> = Vec::new();
+    let mut in_facet = false;
+    let mut last_line = 0;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        last_line = line_number;
+        let error = |message: String| ImportError::parse(path, line_number, message);
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        match tokens.as_slice() {
+            [] => {}
+            ["solid", ..] | ["endsolid", ..] | ["outer", "loop"] | ["endloop"] => {}
+            ["facet", ..] => {
+                if in_facet {
+                    return Err(error("facet: previous facet is not closed with endfacet".to_string()));
+                }
+                in_facet = true;
+                facet.clear();
+            }
+            ["vertex", x, y, z] => {
+                if !in_facet {
+                    return Err(error("vertex outside of a facet".to_string()));
+                }
+                let parse = |s: &str| s.parse::<f64>().ok().filter(|v| v.is_finite())
+                    .ok_or_else(|| error(format!("vertex: expected a number, got `{}`", s)));
+                facet.push(Point3::new(parse(x)?, parse(y)?, parse(z)?));
+            }
+            ["endfacet"] => {
+                if facet.len() != 3 {
+                    return Err(error(format!("endfacet: a facet needs exactly 3 vertices, got {}", facet.len())));
+                }
+                let first = mesh.positions.len();
+                mesh.positions.append(&mut facet);
+                mesh.faces.push(MeshFace::new([first, first + 1, first + 2]));
+                in_facet = false;
+            }
+            _ => return Err(error(format!("unexpected STL line `{}`", line.trim()))),
+        }
+    }
+
+    if in_facet {
+        return Err(ImportError::parse(path, last_line, "unexpected end of file inside a facet".to_string()));
+    }
+
+    Ok(mesh)
+}
@@ -0,0 +1,157 @@
+// 体素网格文件的导入
+//
+// raw格式没有文件头，只有nx*ny*nz个小端序的32位浮点数，分辨率需要另外给出。
+// vgrid格式是带文本文件头的体素网格，文件头每行一条，以end_header结束：
+//
+//   vgrid
+//   comment 任意注释
+//   resolution 64 64 64
+//   format binary_little_endian
+//   end_header
+//
+// format可以是ascii、binary_little_endian或binary_big_endian。文件头之后是nx*ny*nz个体素值，
+// 二进制格式为32位浮点数，ascii格式为空白字符分隔的数值。两种格式的体素都按x变化最快、其次y、最后z的顺序排列，
+// 值必须是非负的有限数。
+
+use std::fs;
+use std::path::Path;
+
+use crate::voxel_grid::VoxelGrid;
+use super::ImportError;
+
+//读取vgrid文件
+pub fn load_vgrid(path: &Path) -> Result<VoxelGrid, ImportError> {
+    let data = fs::read(path).map_err(|e| ImportError::io(path, e))?;
+    parse_vgrid(&data, path)
+}
+
+//读取raw文件，resolution为网格的分辨率
+pub fn load_raw_voxels(path: &Path, resolution: [usize; 3]) -> Result<VoxelGrid, ImportError> {
+    let data = fs::read(path).map_err(|e| ImportError::io(path, e))?;
+    parse_raw_voxels(&data, path, resolution)
+}
+
+//解析raw文件的内容，path只用于错误信息
+pub fn parse_raw_voxels(data: &[u8], path: &Path, resolution: [usize; 3]) -> Result<VoxelGrid, ImportError> {
+    read_binary(data, 0, path, resolution, false)
+}
+
+//解析vgrid文件的内容，path只用于错误信息
+pub fn parse_vgrid(data: &[u8], path: &Path) -> Result<VoxelGrid, ImportError> {
+    let mut resolution = None;
+    let mut format = None;
+    let mut offset = 0;
+    let mut line_number = 0;
+
+    loop {
+        let end = match data[offset..].iter().position(|&b| b == b'\n') {
+            Some(end) => offset + end,
+            None => return Err(ImportError::parse(path, line_number + 1, "unexpected end of file in vgrid header".to_string())),
+        };
+        let line = String::from_utf8_lossy(&data[offset..end]).trim_end_matches('\r').to_string();
+        offset = end + 1;
+        line_number += 1;
+
+        let error = |message: String| ImportError::parse(path, line_number, message);
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        if line_number == 1 {
+            if line != "vgrid" {
+                return Err(error("not a vgrid file".to_string()));
+            }
+            continue;
+        }
+
+        match tokens.as_slice() {
+            [] | ["comment", ..] => {}
+            ["resolution", nx, ny, nz] => {
+                let parse = |s: &str| s.parse::<usize>().ok().filter(|&n| n > 0)
+                    .ok_or_else(|| error(format!("bad grid resolution `{}`", s)));
+                let size = [parse(nx)?, parse(ny)?, parse(nz)?];
+                if byte_count(size).is_none() {
+                    return Err(error(format!("grid resolution {}x{}x{} is too large", size[0], size[1], size[2])));
+                }
+                resolution = Some(size);
+            }
+            ["format", name] => {
+                format = Some(match *name {
+                    "ascii" | "binary_little_endian" | "binary_big_endian" => name.to_string(),
+                    _ => return Err(error(format!("unknown vgrid format `{}`", name))),
+                });
+            }
+            ["end_header"] => break,
+            _ => return Err(error(format!("unexpected vgrid header line `{}`", line))),
+        }
+    }
+
+    let missing = |what: &str| ImportError::parse(path, line_number, format!("missing {} line in vgrid header", what));
+    let resolution = resolution.ok_or_else(|| missing("resolution"))?;
+    match format.ok_or_else(|| missing("format"))?.as_str() {
+        "ascii" => read_ascii(&data[offset..], line_number, path, resolution),
+        "binary_big_endian" => read_binary(data, offset, path, resolution, true),
+        _ => read_binary(data, offset, path, resolution, false),
+    }
+}
+
+//体素个数，溢出时为None
+fn voxel_count(resolution: [usize; 3]) -> Option<usize> {
+    resolution[0].checked_mul(resolution[1])?.checked_mul(resolution[2])
+}
+
+//二进制体素数据的字节数，溢出时为None
+fn byte_count(resolution: [usize; 3]) -> Option<usize> {
+    voxel_count(resolution)?.checked_mul(4)
+}
+
+fn read_binary(data: &[u8], start: usize, path: &Path, resolution: [usize; 3], big_endian: bool) -> Result<VoxelGrid, ImportError> {
+    let error = |offset: usize, message: String| ImportError::Data { path: path.to_path_buf(), offset: offset as u64, message };
+
+    let [nx, ny, nz] = resolution;
+    let expected = byte_count(resolution)
+        .ok_or_else(|| error(start, format!("grid resolution {}x{}x{} is too large", nx, ny, nz)))?;
+    let count = expected / 4;
+    if data.len() - start != expected {
+        let message = format!("a {}x{}x{} grid needs {} bytes of voxel data, got {}", nx, ny, nz, expected, data.len() - start);
+        return Err(error(start, message));
+    }
+
+    let mut values = Vec::with_capacity(count);
+    for (i, chunk) in data[start..].chunks_exact(4).enumerate() {
+        let bytes = [chunk[0], chunk[1], chunk[2], chunk[3]];
+        let value = if big_endian { f32::from_be_bytes(bytes) } else { f32::from_le_bytes(bytes) };
+        if !value.is_finite() || value < 0.0 {
+            return Err(error(start + i * 4, format!("invalid voxel value {}", value)));
+        }
+        values.push(value);
+    }
+
+    VoxelGrid::new(resolution, values).map_err(|message| error(start, message))
+}
+
+fn read_ascii(text: &[u8], header_lines: usize, path: &Path, resolution: [usize; 3]) -> Result<VoxelGrid, ImportError> {
+    let text = std::str::from_utf8(text)
+        .map_err(|_| ImportError::parse(path, header_lines + 1, "ASCII vgrid data is not valid UTF-8".to_string()))?;
+
+    //分辨率来自文件头，不按它预先分配内存，数据不足时在最后报告个数不符
+    let count = voxel_count(resolution).unwrap_or(usize::MAX);
+    let mut values = Vec::new();
+    let mut last_line = header_lines;
+    for (i, line) in text.lines().enumerate() {
+        let line_number = header_lines + i + 1;
+        for token in line.split_whitespace() {
+            if values.len() == count {
+                return Err(ImportError::parse(path, line_number, format!("more than {} voxel values", count)));
+            }
+            let value = token.parse::<f32>().ok().filter(|v| v.is_finite() && *v >= 0.0)
+                .ok_or_else(|| ImportError::parse(path, line_number, format!("expected a non-negative number, got `{}`", token)))?;
+            values.push(value);
+            last_line = line_number;
+        }
+    }
+
+    if values.len() != count {
+        return Err(ImportError::parse(path, last_line, format!("expected {} voxel values, got {}", count, values.len())));
+    }
+
+    VoxelGrid::new(resolution, values).map_err(|message| ImportError::parse(path, last_line, message))
+}
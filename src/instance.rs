@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::animation::AnimatedTransform;
+use crate::hittable::{HitInfo, Hittable};
+use crate::matrix::Transform;
+use crate::ray::Ray;
+use crate::utils::Sampler;
+use crate::vector::Point3;
+
+//物体的实例：对共享的物体施加一个变换，同一个物体（如导入一次的网格）可以用不同的变换放置很多次
+pub struct Instance {
+    object: Arc<dyn Hittable>,
+    transform: Transform, //物体空间到世界空间
+}
+
+impl Instance {
+    pub fn new(object: Arc<dyn Hittable>, transform: Transform) -> Self {
+        Instance { object, transform }
+    }
+
+    pub fn transform(&self) -> &Transform {
+        &self.transform
+    }
+}
+
+impl Hittable for Instance {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        let hit_info = self.object.hit(&object_ray(&self.transform, r), t_min, t_max, sampler)?;
+        Some(to_world(hit_info, &self.transform))
+    }
+
+    fn surface_hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        let hit_info = self.object.surface_hit(&object_ray(&self.transform, r), t_min, t_max, sampler)?;
+        Some(to_world(hit_info, &self.transform))
+    }
+
+    fn transmittance(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> f64 {
+        self.object.transmittance(&object_ray(&self.transform, r), t_min, t_max, sampler)
+    }
+
+    //变换物体空间包围盒的8个顶点，取它们的包围盒
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        let bbox = self.object.bounding_box(time0, time1)?;
+
+        let mut minimum = Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut maximum = Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for i in 0..8 {
+            let corner = Point3::new(
+                if i & 1 == 0 { bbox.minimum.x } else { bbox.maximum.x },
+                if i & 2 == 0 { bbox.minimum.y } else { bbox.maximum.y },
+                if i & 4 == 0 { bbox.minimum.z } else { bbox.maximum.z },
+            );
+            let p = self.transform.point(&corner);
+
+            minimum = Point3::new(minimum.x.min(p.x), minimum.y.min(p.y), minimum.z.min(p.z));
+            maximum = Point3::new(maximum.x.max(p.x), maximum.y.max(p.y), maximum.z.max(p.z));
+        }
+
+        Some(Aabb::new(minimum, maximum))
+    }
+}
+
+//随时间运动的实例：按光线的时间对关键帧插值得到变换，可以为任意物体产生运动模糊
+pub struct AnimatedInstance {
+    object: Arc<dyn Hittable>,
+    animation: AnimatedTransform,
+}
+
+impl AnimatedInstance {
+    pub fn new(object: Arc<dyn Hittable>, animation: AnimatedTransform) -> Self {
+        AnimatedInstance { object, animation }
+    }
+
+    pub fn animation(&self) -> &AnimatedTransform {
+        &self.animation
+    }
+}
+
+impl Hittable for AnimatedInstance {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        let transform = self.animation.at(r.time);
+        let hit_info = self.object.hit(&object_ray(&transform, r), t_min, t_max, sampler)?;
+        Some(to_world(hit_info, &transform))
+    }
+
+    fn surface_hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        let transform = self.animation.at(r.time);
+        let hit_info = self.object.surface_hit(&object_ray(&transform, r), t_min, t_max, sampler)?;
+        Some(to_world(hit_info, &transform))
+    }
+
+    fn transmittance(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> f64 {
+        self.object.transmittance(&object_ray(&self.animation.at(r.time), r), t_min, t_max, sampler)
+    }
+
+    //包围盒覆盖快门时间内物体经过的整个范围
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        let bbox = self.object.bounding_box(time0, time1)?;
+        Some(self.animation.bounding_box(&bbox, time0, time1))
+    }
+}
+
+//把光线变换到transform给出的物体空间；方向不归一化，这样两个空间里同一个交点的t相同
+fn object_ray(transform: &Transform, r: &Ray) -> Ray {
+    let to_object = transform.inverse();
+    Ray::new(to_object.point(&r.orig), to_object.vector(&r.dir), r.time)
+}
+
+//把物体空间里的交点和法线变换回世界空间
+fn to_world<'a>(mut hit_info: HitInfo<'a>, transform: &Transform) -> HitInfo<'a> {
+    //法线已经与物体空间的光线方向相反，逆转置变换保持点积的符号，所以front_face不变
+    hit_info.pos = transform.point(&hit_info.pos);
+    hit_info.normal = transform.normal(&hit_info.normal).unit();
+    hit_info
+}
@@ -0,0 +1,45 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::material::{Material, ScatterInfo};
+use crate::ray::Ray;
+use crate::hittable::HitInfo;
+use crate::vector::*;
+use crate::utils::Sampler;
+use crate::texture::{SolidColor, Texture};
+
+//各向同性的相函数：参与介质中的散射方向在单位球面上均匀分布，与入射方向无关
+pub struct Isotropic {
+    pub albedo: Arc<dyn Texture>
+}
+
+impl Isotropic {
+    pub fn new(a: &Color) -> Self {
+        Isotropic::with_texture(Arc::new(SolidColor::new(a)))
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>) -> Self {
+        Isotropic {
+            albedo
+        }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(&self, r_in: &Ray, hit_info: &HitInfo, sampler: &mut Sampler) -> Option<ScatterInfo> {
+        let scattered = Ray::new(hit_info.pos, Vector3::random_unit_vector(sampler), r_in.time);
+        let attenuation = self.albedo.value_at(hit_info);
+
+        Some(ScatterInfo::sampled(attenuation, scattered, 1.0 / (4.0 * PI)))
+    }
+
+    //相函数为1 / 4π，没有cosθ项
+    fn eval(&self, _r_in: &Ray, hit_info: &HitInfo, _direction: &Vector3) -> Color {
+        self.albedo.value_at(hit_info) / (4.0 * PI)
+    }
+
+    fn pdf(&self, _r_in: &Ray, _hit_info: &HitInfo, _direction: &Vector3) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+}
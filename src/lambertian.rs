@@ -1,31 +1,52 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
 use crate::color::Color;
 use crate::material::{Material, ScatterInfo};
 use crate::ray::Ray;
 use crate::hittable::HitInfo;
+use crate::onb::Onb;
 use crate::vector::*;
+use crate::utils::Sampler;
+use crate::texture::{SolidColor, Texture};
 
 pub struct Lambertian {
-    pub albedo: Color
+    pub albedo: Arc<dyn Texture>
 }
 
 impl Lambertian {
     pub fn new(a: &Color) ->Self {
+        Lambertian::with_texture(Arc::new(SolidColor::new(a)))
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>) -> Self {
         Lambertian {
-            albedo: *a
+            albedo
         }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _r_in: &Ray, hit_info: &HitInfo) -> Option<ScatterInfo> {
-        let mut scatter_direction = hit_info.normal + Vector3::random_unit_vector();
+    //按cosθ加权在法线所在的半球上采样，BSDF为albedo / π，eval / pdf正好等于albedo
+    fn scatter(&self, r_in: &Ray, hit_info: &HitInfo, sampler: &mut Sampler) -> Option<ScatterInfo> {
+        let onb = Onb::build_from_w(&hit_info.normal);
+        let direction = Vector3::random_cosine_direction(sampler);
+        let scatter_direction = onb.local(direction.x, direction.y, direction.z);
+
+        let attenuation = self.albedo.value_at(hit_info);
+        Some(ScatterInfo::sampled(attenuation, Ray::new(hit_info.pos, scatter_direction, r_in.time), direction.z / PI))
+    }
 
-        if scatter_direction.near_zero(){
-            scatter_direction = hit_info.normal;
+    fn eval(&self, _r_in: &Ray, hit_info: &HitInfo, direction: &Vector3) -> Color {
+        let cosine = hit_info.normal.dot(&direction.unit());
+        if cosine <= 0.0 {
+            return Color::black();
         }
 
-        let scatter_info = ScatterInfo::new(self.albedo, Ray::new(hit_info.pos, scatter_direction, _r_in.time));
-        
-        Some(scatter_info)
+        self.albedo.value_at(hit_info) * (cosine / PI)
+    }
+
+    fn pdf(&self, _r_in: &Ray, hit_info: &HitInfo, direction: &Vector3) -> f64 {
+        hit_info.normal.dot(&direction.unit()).max(0.0) / PI
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,48 @@
+// 光线追踪渲染器的库，render可执行程序只是它的一个使用者
+
+pub mod aabb;
+pub mod aarect;
+pub mod animation;
+pub mod background;
+pub mod builtin_scenes;
+pub mod bvh;
+pub mod camera;
+pub mod checker_texture;
+pub mod color;
+pub mod constant_medium;
+pub mod cuboid;
+pub mod dielectric;
+pub mod diffuse_light;
+pub mod directional_light;
+pub mod environment_map;
+pub mod grid_volume;
+pub mod hittable;
+pub mod image_texture;
+pub mod import;
+pub mod instance;
+pub mod isotropic;
+pub mod lambertian;
+pub mod light;
+pub mod material;
+pub mod matrix;
+pub mod metal;
+pub mod noise_texture;
+pub mod onb;
+pub mod output;
+pub mod perlin;
+pub mod point_light;
+pub mod postprocess;
+pub mod preetham_sky;
+pub mod quaternion;
+pub mod ray;
+pub mod scene;
+pub mod sphere;
+pub mod spot_light;
+pub mod texture;
+pub mod triangle;
+pub mod triangle_mesh;
+pub mod utils;
+pub mod vector;
+pub mod vertex_color_texture;
+pub mod voxel_grid;
+pub mod world;
@@ -0,0 +1,14 @@
+use crate::color::Color;
+use crate::vector::{Point3, Vector3};
+
+//没有几何形状的光源（点光源、聚光灯、平行光）：光线不会击中它们，只能在散射点上通过阴影射线直接采样
+pub trait Light: Send + Sync {
+    //从p处看向光源，光源照不到p时返回None
+    fn sample(&self, p: &Point3) -> Option<LightSample>;
+}
+
+pub struct LightSample {
+    pub direction: Vector3, //从p指向光源的单位向量
+    pub distance: f64, //p到光源的距离，平行光为无穷大
+    pub irradiance: Color, //垂直于direction的平面上接收到的光，已经计入距离的平方衰减
+}
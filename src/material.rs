@@ -1,20 +1,56 @@
 use crate::ray::Ray;
 use crate::color::Color;
 use crate::hittable::HitInfo;
-pub trait Material {//不同的材质对入射光线的处理不同（镜面反射、漫反射、折射等，颜色衰减）
-    fn scatter(&self, r_in: &Ray, hit_info: &HitInfo) -> Option<ScatterInfo>;
+use crate::utils::Sampler;
+use crate::vector::Vector3;
+pub trait Material: Send + Sync {//不同的材质对入射光线的处理不同（镜面反射、漫反射、折射等，颜色衰减）
+    fn scatter(&self, r_in: &Ray, hit_info: &HitInfo, sampler: &mut Sampler) -> Option<ScatterInfo>;
+
+    //光线r_in击中后向direction方向散射的BSDF值乘以cosθ（参与介质为相函数的值），direction不必是单位向量
+    //镜面反射、折射这类只向一个方向散射的材质无法对任意方向求值，返回黑色
+    fn eval(&self, _r_in: &Ray, _hit_info: &HitInfo, _direction: &Vector3) -> Color {
+        Color::black()
+    }
+
+    //scatter采样到direction方向的概率密度（立体角），与eval配对使用
+    fn pdf(&self, _r_in: &Ray, _hit_info: &HitInfo, _direction: &Vector3) -> f64 {
+        0.0
+    }
+
+    //材质自身发出的光，默认不发光
+    fn emitted(&self, _r_in: &Ray, _hit_info: &HitInfo) -> Color {
+        Color::black()
+    }
+
+    //是否是发光材质，场景文件用它决定哪些物体登记为光源
+    fn is_emissive(&self) -> bool {
+        false
+    }
 }
 
 pub struct ScatterInfo {
-    pub attenuation: Color,
+    pub attenuation: Color, //eval / pdf，散射光线带回的光要乘上的权重
     pub scattered: Ray,
+    //按BSDF采样时scattered方向的概率密度；镜面反射、折射只有一个方向，没有概率密度，为None
+    //有概率密度的材质同时实现了eval和pdf，积分器在它上面对光源采样并用多重重要性采样合并两种策略
+    pub pdf: Option<f64>,
 }
 
 impl ScatterInfo {
+    //镜面散射
     pub fn new(attenuation: Color, scattered: Ray) -> ScatterInfo {
         ScatterInfo {
             attenuation,
-            scattered
+            scattered,
+            pdf: None,
+        }
+    }
+
+    //按概率密度pdf采样得到的散射
+    pub fn sampled(attenuation: Color, scattered: Ray, pdf: f64) -> ScatterInfo {
+        ScatterInfo {
+            pdf: Some(pdf),
+            ..ScatterInfo::new(attenuation, scattered)
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,205 @@
+use std::ops::Mul;
+
+use crate::utils::degrees_to_radians;
+use crate::vector::{Point3, Vector3};
+
+//4x4矩阵，按行存储，作用于列向量：点(x, y, z, 1)，方向(x, y, z, 0)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Matrix4 {
+    pub m: [[f64; 4]; 4],
+}
+
+impl Matrix4 {
+    pub fn new(m: [[f64; 4]; 4]) -> Self {
+        Matrix4 { m }
+    }
+
+    pub fn identity() -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        Matrix4 { m }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, x) in row.iter_mut().enumerate() {
+                *x = self.m[j][i];
+            }
+        }
+        Matrix4 { m }
+    }
+
+    //高斯-约当消元（列主元）求逆矩阵，矩阵奇异时返回None
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m;
+        let mut inv = Matrix4::identity().m;
+
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs())).unwrap();
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let p = a[col][col];
+            for k in 0..4 {
+                a[col][k] /= p;
+                inv[col][k] /= p;
+            }
+
+            for row in 0..4 {
+                if row != col {
+                    let f = a[row][col];
+                    for k in 0..4 {
+                        a[row][k] -= f * a[col][k];
+                        inv[row][k] -= f * inv[col][k];
+                    }
+                }
+            }
+        }
+
+        Some(Matrix4 { m: inv })
+    }
+
+    //变换点，包含平移；仿射变换的最后一行是(0, 0, 0, 1)，不需要做透视除法
+    pub fn transform_point(&self, p: &Point3) -> Point3 {
+        let m = &self.m;
+        Point3::new(
+            m[0][0] * p.x + m[0][1] * p.y + m[0][2] * p.z + m[0][3],
+            m[1][0] * p.x + m[1][1] * p.y + m[1][2] * p.z + m[1][3],
+            m[2][0] * p.x + m[2][1] * p.y + m[2][2] * p.z + m[2][3],
+        )
+    }
+
+    //变换方向，不受平移影响
+    pub fn transform_vector(&self, v: &Vector3) -> Vector3 {
+        let m = &self.m;
+        Vector3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, x) in row.iter_mut().enumerate() {
+                *x = (0..4).map(|k| self.m[i][k] * other.m[k][j]).sum();
+            }
+        }
+        Matrix4 { m }
+    }
+}
+
+//仿射变换，同时保存矩阵和它的逆矩阵
+//法线不能直接用矩阵变换（非均匀缩放会让法线不再垂直于表面），要用逆矩阵的转置
+#[derive(Debug, Copy, Clone)]
+pub struct Transform {
+    matrix: Matrix4,
+    inverse: Matrix4,
+}
+
+impl Transform {
+    //矩阵不可逆时返回None
+    pub fn new(matrix: Matrix4) -> Option<Self> {
+        Some(Transform { matrix, inverse: matrix.inverse()? })
+    }
+
+    pub fn identity() -> Self {
+        Transform { matrix: Matrix4::identity(), inverse: Matrix4::identity() }
+    }
+
+    pub fn translate(offset: &Vector3) -> Self {
+        let mut matrix = Matrix4::identity();
+        let mut inverse = Matrix4::identity();
+        for axis in 0..3 {
+            matrix.m[axis][3] = offset[axis];
+            inverse.m[axis][3] = -offset[axis];
+        }
+        Transform { matrix, inverse }
+    }
+
+    //各轴的缩放系数都不能为0
+    pub fn scale(factors: &Vector3) -> Self {
+        assert!(factors.x != 0.0 && factors.y != 0.0 && factors.z != 0.0, "scale factors must be non-zero");
+
+        let mut matrix = Matrix4::identity();
+        let mut inverse = Matrix4::identity();
+        for axis in 0..3 {
+            matrix.m[axis][axis] = factors[axis];
+            inverse.m[axis][axis] = 1.0 / factors[axis];
+        }
+        Transform { matrix, inverse }
+    }
+
+    //绕过原点的axis轴按右手定则旋转degrees度（罗德里格斯旋转公式）
+    pub fn rotate(axis: &Vector3, degrees: f64) -> Self {
+        let a = axis.unit();
+        let theta = degrees_to_radians(degrees);
+        let (sin, cos) = theta.sin_cos();
+        let t = 1.0 - cos;
+
+        let matrix = Matrix4::new([
+            [t * a.x * a.x + cos, t * a.x * a.y - sin * a.z, t * a.x * a.z + sin * a.y, 0.0],
+            [t * a.x * a.y + sin * a.z, t * a.y * a.y + cos, t * a.y * a.z - sin * a.x, 0.0],
+            [t * a.x * a.z - sin * a.y, t * a.y * a.z + sin * a.x, t * a.z * a.z + cos, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        Transform::rotation(matrix)
+    }
+
+    //由旋转矩阵构造变换；旋转矩阵是正交矩阵，逆矩阵就是转置
+    pub fn rotation(matrix: Matrix4) -> Self {
+        Transform { matrix, inverse: matrix.transpose() }
+    }
+
+    pub fn rotate_x(degrees: f64) -> Self {
+        Transform::rotate(&Vector3::new(1.0, 0.0, 0.0), degrees)
+    }
+
+    pub fn rotate_y(degrees: f64) -> Self {
+        Transform::rotate(&Vector3::new(0.0, 1.0, 0.0), degrees)
+    }
+
+    pub fn rotate_z(degrees: f64) -> Self {
+        Transform::rotate(&Vector3::new(0.0, 0.0, 1.0), degrees)
+    }
+
+    //先做self再做next的组合变换
+    pub fn then(&self, next: &Transform) -> Self {
+        Transform {
+            matrix: next.matrix * self.matrix,
+            inverse: self.inverse * next.inverse,
+        }
+    }
+
+    pub fn matrix(&self) -> &Matrix4 {
+        &self.matrix
+    }
+
+    pub fn inverse(&self) -> Transform {
+        Transform { matrix: self.inverse, inverse: self.matrix }
+    }
+
+    pub fn point(&self, p: &Point3) -> Point3 {
+        self.matrix.transform_point(p)
+    }
+
+    pub fn vector(&self, v: &Vector3) -> Vector3 {
+        self.matrix.transform_vector(v)
+    }
+
+    //变换法线：乘以逆矩阵的转置，结果没有归一化
+    pub fn normal(&self, n: &Vector3) -> Vector3 {
+        self.inverse.transpose().transform_vector(n)
+    }
+}
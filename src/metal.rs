@@ -1,31 +1,47 @@
+use std::sync::Arc;
+
 use crate::color::Color;
 use crate::material::{Material, ScatterInfo};
 use crate::ray::Ray;
 use crate::hittable::HitInfo;
 use crate::vector::*;
+use crate::utils::Sampler;
+use crate::texture::{SolidColor, Texture};
 
 pub struct Metal {
-    pub albedo: Color,
-    pub fuzz: f64
+    pub albedo: Arc<dyn Texture>,
+    pub fuzz: Arc<dyn Texture> //模糊程度，取纹理三个分量的平均值
 }
 
 impl Metal {
     pub fn new(a: &Color, f: f64) ->Self {
+        Metal::with_texture(Arc::new(SolidColor::new(a)), f)
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>, f: f64) -> Self {
+        Metal::with_textures(albedo, Arc::new(SolidColor::new(&Color::new(f, f, f))))
+    }
+
+    //用纹理控制表面各处的模糊程度，例如用噪声纹理做出磨砂不均匀的金属
+    pub fn with_textures(albedo: Arc<dyn Texture>, fuzz: Arc<dyn Texture>) -> Self {
         Metal {
-            albedo: *a,
-            fuzz: f
+            albedo,
+            fuzz
         }
     }
 }
 
 impl Material for Metal {
-    fn scatter(&self, r_in: &Ray, hit_info: &HitInfo) -> Option<ScatterInfo> {
+    fn scatter(&self, r_in: &Ray, hit_info: &HitInfo, sampler: &mut Sampler) -> Option<ScatterInfo> {
         let reflected = Vector3::reflect(&r_in.dir.unit(), &hit_info.normal);
-        
-        let scattered_ray= Ray::new(hit_info.pos, reflected + Vector3::random_in_unit_sphere()*self.fuzz, r_in.time);
+
+        let f = self.fuzz.value_at(hit_info);
+        let fuzz = (f.f64_r() + f.f64_g() + f.f64_b()) / 3.0;
+        let scattered_ray= Ray::new(hit_info.pos, reflected + Vector3::random_in_unit_sphere(sampler)*fuzz, r_in.time);
 
         if scattered_ray.dir.dot(&hit_info.normal) > 0.0 {
-            Some(ScatterInfo::new(self.albedo, scattered_ray))
+            let attenuation = self.albedo.value_at(hit_info);
+            Some(ScatterInfo::new(attenuation, scattered_ray))
         } else {
             None
         }
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::perlin::Perlin;
+use crate::texture::Texture;
+use crate::vector::Point3;
+
+//湍流默认叠加的倍频数
+pub const DEFAULT_OCTAVES: u32 = 7;
+
+//按a:b = (1-t):t混合两种颜色
+#[inline]
+fn mix(a: &Color, b: &Color, t: f64) -> Color {
+    *a * (1.0 - t) + *b * t
+}
+
+//缩放后的Perlin噪声，噪声值从[-1, 1]映射到[0, 1]的灰度；scale越大花纹越密
+pub struct NoiseTexture {
+    noise: Arc<Perlin>,
+    scale: f64,
+}
+
+impl NoiseTexture {
+    pub fn new(noise: Arc<Perlin>, scale: f64) -> Self {
+        NoiseTexture { noise, scale }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, p: &Point3) -> Color {
+        let n = 0.5 * (1.0 + self.noise.noise(&(*p * self.scale)));
+        Color::new(n, n, n)
+    }
+}
+
+//多倍频湍流的灰度，大致在[0, 1]之间
+pub struct TurbulenceTexture {
+    noise: Arc<Perlin>,
+    scale: f64,
+    octaves: u32,
+}
+
+impl TurbulenceTexture {
+    pub fn new(noise: Arc<Perlin>, scale: f64, octaves: u32) -> Self {
+        TurbulenceTexture { noise, scale, octaves }
+    }
+}
+
+impl Texture for TurbulenceTexture {
+    fn value(&self, _u: f64, _v: f64, p: &Point3) -> Color {
+        let t = self.noise.turbulence(&(*p * self.scale), self.octaves).min(1.0);
+        Color::new(t, t, t)
+    }
+}
+
+//大理石纹：沿z方向的正弦条纹，用湍流扰动相位
+pub struct MarbleTexture {
+    noise: Arc<Perlin>,
+    scale: f64,
+    turbulence: f64, //湍流对条纹的扰动强度
+    vein: Color, //条纹颜色
+    base: Color, //底色
+}
+
+impl MarbleTexture {
+    pub fn new(noise: Arc<Perlin>, scale: f64, turbulence: f64, vein: &Color, base: &Color) -> Self {
+        MarbleTexture { noise, scale, turbulence, vein: *vein, base: *base }
+    }
+}
+
+impl Texture for MarbleTexture {
+    fn value(&self, _u: f64, _v: f64, p: &Point3) -> Color {
+        let phase = self.scale * p.z + self.turbulence * self.noise.turbulence(p, DEFAULT_OCTAVES);
+        mix(&self.vein, &self.base, 0.5 * (1.0 + phase.sin()))
+    }
+}
+
+//木纹：以y轴为中心的同心年轮，用噪声扰动年轮半径
+pub struct WoodTexture {
+    noise: Arc<Perlin>,
+    scale: f64, //单位长度内的年轮数
+    turbulence: f64,
+    light: Color, //年轮之间的浅色木质
+    dark: Color, //年轮的深色
+}
+
+impl WoodTexture {
+    pub fn new(noise: Arc<Perlin>, scale: f64, turbulence: f64, light: &Color, dark: &Color) -> Self {
+        WoodTexture { noise, scale, turbulence, light: *light, dark: *dark }
+    }
+}
+
+impl Texture for WoodTexture {
+    fn value(&self, _u: f64, _v: f64, p: &Point3) -> Color {
+        let r = (p.x * p.x + p.z * p.z).sqrt();
+        let rings = self.scale * r + self.turbulence * self.noise.noise(p);
+        //年轮内部由浅到深渐变，t在[0, 1)之间
+        let t = rings - rings.floor();
+        mix(&self.light, &self.dark, t * t)
+    }
+}
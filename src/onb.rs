@@ -0,0 +1,25 @@
+use crate::vector::Vector3;
+
+//以w为第三个轴的正交归一基（orthonormal basis），用于把在局部坐标系中采样的方向变换到世界坐标系
+pub struct Onb {
+    pub u: Vector3,
+    pub v: Vector3,
+    pub w: Vector3,
+}
+
+impl Onb {
+    pub fn build_from_w(w: &Vector3) -> Self {
+        let w = w.unit();
+        //选一个与w不平行的坐标轴来构造另外两个轴
+        let a = if w.x.abs() > 0.9 { Vector3::new(0.0, 1.0, 0.0) } else { Vector3::new(1.0, 0.0, 0.0) };
+        let v = w.cross(&a).unit();
+        let u = w.cross(&v);
+
+        Onb { u, v, w }
+    }
+
+    //局部坐标(a, b, c)对应的世界坐标系中的向量
+    pub fn local(&self, a: f64, b: f64, c: f64) -> Vector3 {
+        self.u * a + self.v * b + self.w * c
+    }
+}
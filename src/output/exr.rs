@@ -0,0 +1,308 @@
+use std::io::{self, Read, Write};
+
+use crate::color::Color;
+use super::{Image, ImageWriter};
+
+const EXR_MAGIC: u32 = 20000630;
+const PIXEL_TYPE_UINT: i32 = 0;
+const PIXEL_TYPE_HALF: i32 = 1;
+const PIXEL_TYPE_FLOAT: i32 = 2;
+
+//OpenEXR格式，单part扫描线图片，不压缩，保存线性颜色值
+//通道按名称的字母顺序（B、G、R）排列，这是EXR规范的要求
+pub struct ExrWriter {
+    half: bool, //true为16位半精度浮点，false为32位浮点
+}
+
+impl ExrWriter {
+    pub fn half() -> Self {
+        ExrWriter { half: true }
+    }
+
+    pub fn float() -> Self {
+        ExrWriter { half: false }
+    }
+}
+
+impl ImageWriter for ExrWriter {
+    fn write_image(&self, out: &mut dyn Write, image: &[Color], width: usize, height: usize) -> io::Result<()> {
+        let pixel_type = if self.half { PIXEL_TYPE_HALF } else { PIXEL_TYPE_FLOAT };
+        let bytes_per_sample = if self.half { 2 } else { 4 };
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&EXR_MAGIC.to_le_bytes());
+        header.extend_from_slice(&2u32.to_le_bytes()); //版本号2，没有任何标志位
+
+        let mut channels = Vec::new();
+        for name in ["B", "G", "R"] {
+            channels.extend_from_slice(name.as_bytes());
+            channels.push(0);
+            channels.extend_from_slice(&pixel_type.to_le_bytes());
+            channels.extend_from_slice(&[0, 0, 0, 0]); //pLinear和3个保留字节
+            channels.extend_from_slice(&1i32.to_le_bytes()); //xSampling
+            channels.extend_from_slice(&1i32.to_le_bytes()); //ySampling
+        }
+        channels.push(0);
+
+        let mut window = Vec::new();
+        for v in [0, 0, width as i32 - 1, height as i32 - 1] {
+            window.extend_from_slice(&v.to_le_bytes());
+        }
+
+        write_attribute(&mut header, "channels", "chlist", &channels);
+        write_attribute(&mut header, "compression", "compression", &[0]);
+        write_attribute(&mut header, "dataWindow", "box2i", &window);
+        write_attribute(&mut header, "displayWindow", "box2i", &window);
+        write_attribute(&mut header, "lineOrder", "lineOrder", &[0]);
+        write_attribute(&mut header, "pixelAspectRatio", "float", &1f32.to_le_bytes());
+        write_attribute(&mut header, "screenWindowCenter", "v2f", &[0; 8]);
+        write_attribute(&mut header, "screenWindowWidth", "float", &1f32.to_le_bytes());
+        header.push(0);
+        out.write_all(&header)?;
+
+        //不压缩时每个数据块只有一行，偏移表记录每行数据块在文件中的位置
+        let line_size = width * 3 * bytes_per_sample;
+        let chunk_size = (8 + line_size) as u64;
+        let first_chunk = (header.len() + height * 8) as u64;
+        for y in 0..height as u64 {
+            out.write_all(&(first_chunk + y * chunk_size).to_le_bytes())?;
+        }
+
+        let mut line = Vec::with_capacity(line_size);
+        for (y, row) in image.chunks(width).enumerate() {
+            line.clear();
+            for channel in [Color::f64_b, Color::f64_g, Color::f64_r] {
+                for c in row {
+                    let value = channel(c) as f32;
+                    if self.half {
+                        line.extend_from_slice(&f32_to_half(value).to_le_bytes());
+                    } else {
+                        line.extend_from_slice(&value.to_le_bytes());
+                    }
+                }
+            }
+
+            out.write_all(&(y as i32).to_le_bytes())?;
+            out.write_all(&(line_size as i32).to_le_bytes())?;
+            out.write_all(&line)?;
+        }
+
+        Ok(())
+    }
+
+    fn is_hdr(&self) -> bool {
+        true
+    }
+}
+
+fn write_attribute(header: &mut Vec<u8>, name: &str, type_name: &str, value: &[u8]) {
+    header.extend_from_slice(name.as_bytes());
+    header.push(0);
+    header.extend_from_slice(type_name.as_bytes());
+    header.push(0);
+    header.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    header.extend_from_slice(value);
+}
+
+//读取OpenEXR文件，只支持不压缩的单part扫描线图片（即ExrWriter写出的格式），需要包含R、G、B三个通道
+pub fn read_exr(input: &mut dyn Read) -> io::Result<Image> {
+    let mut data = Vec::new();
+    input.read_to_end(&mut data)?;
+    let mut reader = ByteReader { data: &data, pos: 0 };
+
+    if reader.u32()? != EXR_MAGIC {
+        return Err(invalid_data("not an OpenEXR file"));
+    }
+    if reader.u32()? & !0xff != 0 {
+        return Err(invalid_data("only single-part scanline OpenEXR files are supported"));
+    }
+
+    let mut channels: Vec<(String, i32)> = Vec::new();
+    let mut compression = None;
+    let mut data_window = None;
+
+    loop {
+        let name = reader.string()?;
+        if name.is_empty() {
+            break;
+        }
+        let _type_name = reader.string()?;
+        let size = reader.i32()? as usize;
+        let value = reader.bytes(size)?;
+        let mut value_reader = ByteReader { data: value, pos: 0 };
+
+        match name.as_str() {
+            "channels" => loop {
+                let channel = value_reader.string()?;
+                if channel.is_empty() {
+                    break;
+                }
+                let pixel_type = value_reader.i32()?;
+                value_reader.bytes(4)?;
+                if value_reader.i32()? != 1 || value_reader.i32()? != 1 {
+                    return Err(invalid_data("subsampled OpenEXR channels are not supported"));
+                }
+                channels.push((channel, pixel_type));
+            },
+            "compression" => compression = value.first().copied(),
+            "dataWindow" => {
+                let mut window = [0i32; 4];
+                for v in &mut window {
+                    *v = value_reader.i32()?;
+                }
+                data_window = Some(window);
+            }
+            _ => {}
+        }
+    }
+
+    if compression != Some(0) {
+        return Err(invalid_data("compressed OpenEXR files are not supported"));
+    }
+    let [x_min, y_min, x_max, y_max] = data_window.ok_or_else(|| invalid_data("missing OpenEXR dataWindow"))?;
+    let width = (x_max as i64 - x_min as i64 + 1).max(0) as usize;
+    let height = (y_max as i64 - y_min as i64 + 1).max(0) as usize;
+
+    let channel_index = |name: &str| {
+        channels.iter().position(|(n, _)| n == name)
+            .ok_or_else(|| invalid_data(&format!("OpenEXR file has no {} channel", name)))
+    };
+    let (r_index, g_index, b_index) = (channel_index("R")?, channel_index("G")?, channel_index("B")?);
+
+    //每个像素的每个通道至少占2个字节，数据窗口超出文件大小时不按它分配内存
+    let min_size = width.checked_mul(height).and_then(|n| n.checked_mul(channels.len() * 2));
+    if min_size.is_none_or(|size| size > data.len()) {
+        return Err(invalid_data(&format!("OpenEXR data window {}x{} does not fit in the file", width, height)));
+    }
+
+    let offsets = (0..height).map(|_| reader.u64()).collect::<io::Result<Vec<u64>>>()?;
+
+    let mut pixels = vec![Color::black(); width * height];
+    let mut values = vec![vec![0f32; width]; channels.len()];
+    for offset in offsets {
+        reader.pos = offset as usize;
+        let y = reader.i32()? - y_min;
+        let _size = reader.i32()?;
+        if y < 0 || y as usize >= height {
+            return Err(invalid_data("OpenEXR scanline out of range"));
+        }
+
+        for (channel, (_, pixel_type)) in values.iter_mut().zip(&channels) {
+            for v in channel.iter_mut() {
+                *v = match *pixel_type {
+                    PIXEL_TYPE_HALF => half_to_f32(reader.u16()?),
+                    PIXEL_TYPE_FLOAT => f32::from_bits(reader.u32()?),
+                    PIXEL_TYPE_UINT => reader.u32()? as f32,
+                    _ => return Err(invalid_data("unknown OpenEXR pixel type")),
+                };
+            }
+        }
+
+        let row = &mut pixels[y as usize * width..(y as usize + 1) * width];
+        for (x, pixel) in row.iter_mut().enumerate() {
+            *pixel = Color::new(values[r_index][x] as f64, values[g_index][x] as f64, values[b_index][x] as f64);
+        }
+    }
+
+    Ok(Image { width, height, pixels })
+}
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn bytes(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        let end = self.pos.checked_add(n).filter(|&end| end <= self.data.len())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "truncated OpenEXR file"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> io::Result<i32> {
+        Ok(i32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    //以0结尾的字符串
+    fn string(&mut self) -> io::Result<String> {
+        let len = self.data[self.pos.min(self.data.len())..].iter().position(|&b| b == 0)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "truncated OpenEXR file"))?;
+        let s = String::from_utf8_lossy(self.bytes(len)?).into_owned();
+        self.pos += 1;
+        Ok(s)
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+//32位浮点转16位半精度浮点，按就近舍入到偶数，超出范围的值变为无穷大
+fn f32_to_half(x: f32) -> u16 {
+    let bits = x.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mant = bits & 0x7f_ffff;
+
+    if exp == 0xff {
+        //无穷大或NaN
+        return sign | 0x7c00 | if mant != 0 { 0x200 } else { 0 };
+    }
+
+    let e = exp - 127 + 15;
+    if e >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    let (value, shift) = if e <= 0 {
+        //半精度的非规格化数
+        if e < -10 {
+            return sign;
+        }
+        (mant | 0x80_0000, (14 - e) as u32)
+    } else {
+        ((e as u32) << 23 | mant, 13)
+    };
+
+    let mut half = value >> shift;
+    let rem = value & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    if rem > halfway || (rem == halfway && half & 1 == 1) {
+        half += 1; //进位可能溢出到指数位，结果仍然正确
+    }
+
+    sign | half as u16
+}
+
+fn half_to_f32(h: u16) -> f32 {
+    let sign = ((h & 0x8000) as u32) << 16;
+    let exp = ((h >> 10) & 0x1f) as u32;
+    let mant = (h & 0x3ff) as u32;
+
+    if exp == 0 {
+        //零或非规格化数：mant * 2^-24
+        let value = mant as f32 / (1 << 24) as f32;
+        return if sign != 0 { -value } else { value };
+    }
+
+    let bits = if exp == 0x1f {
+        sign | 0x7f80_0000 | (mant << 13)
+    } else {
+        sign | ((exp + 112) << 23) | (mant << 13)
+    };
+    f32::from_bits(bits)
+}
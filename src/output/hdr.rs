@@ -0,0 +1,222 @@
+use std::io::{self, BufRead, Write};
+
+use crate::color::Color;
+use super::{Image, ImageWriter};
+
+//Radiance HDR格式（RGBE），每个像素用3个字节的尾数加1个字节的公共指数保存线性颜色值，不做gamma校正和截断
+//扫描线采用新式游程编码（RLE）压缩
+pub struct HdrWriter;
+
+impl ImageWriter for HdrWriter {
+    fn write_image(&self, out: &mut dyn Write, image: &[Color], width: usize, height: usize) -> io::Result<()> {
+        write!(out, "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n", height, width)?;
+
+        for row in image.chunks(width) {
+            let rgbe: Vec<[u8; 4]> = row.iter().map(color_to_rgbe).collect();
+
+            //宽度不在[8, 32767]范围内的扫描线不能使用RLE
+            if !(8..=0x7fff).contains(&width) {
+                for pixel in &rgbe {
+                    out.write_all(pixel)?;
+                }
+                continue;
+            }
+
+            out.write_all(&[2, 2, (width >> 8) as u8, (width & 0xff) as u8])?;
+            for channel in 0..4 {
+                let data: Vec<u8> = rgbe.iter().map(|pixel| pixel[channel]).collect();
+                write_rle_channel(out, &data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_hdr(&self) -> bool {
+        true
+    }
+}
+
+//把一个通道的数据写成若干段：游程段（首字节128+n，随后1个重复的字节）或原样段（首字节n，随后n个字节）
+//这里沿用了Radiance参考实现的做法：只有长度不小于4的重复才单独编码成游程段
+fn write_rle_channel(out: &mut dyn Write, data: &[u8]) -> io::Result<()> {
+    const MIN_RUN: usize = 4;
+    let n = data.len();
+    let mut cur = 0;
+
+    while cur < n {
+        //找到下一个足够长的游程的起点
+        let mut beg_run = cur;
+        let mut run_count = 0;
+        let mut old_run_count = 0;
+        while run_count < MIN_RUN && beg_run < n {
+            beg_run += run_count;
+            old_run_count = run_count;
+            run_count = 1;
+            while beg_run + run_count < n && run_count < 127 && data[beg_run] == data[beg_run + run_count] {
+                run_count += 1;
+            }
+        }
+
+        //游程前面紧挨着一小段重复时也按游程编码
+        if old_run_count > 1 && old_run_count == beg_run - cur {
+            out.write_all(&[128 + old_run_count as u8, data[cur]])?;
+            cur = beg_run;
+        }
+
+        while cur < beg_run {
+            let count = (beg_run - cur).min(128);
+            out.write_all(&[count as u8])?;
+            out.write_all(&data[cur..cur + count])?;
+            cur += count;
+        }
+
+        if run_count >= MIN_RUN {
+            out.write_all(&[128 + run_count as u8, data[beg_run]])?;
+            cur += run_count;
+        }
+    }
+
+    Ok(())
+}
+
+//读取Radiance HDR文件，支持未压缩和新式RLE压缩的扫描线，只支持标准的“-Y h +X w”方向
+pub fn read_hdr(input: &mut dyn BufRead) -> io::Result<Image> {
+    let magic = read_line(input)?;
+    if !magic.starts_with("#?") {
+        return Err(invalid_data("not a Radiance HDR file"));
+    }
+
+    loop {
+        let line = read_line(input)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(invalid_data(&format!("unsupported HDR pixel format: {}", format)));
+            }
+        }
+    }
+
+    let resolution = read_line(input)?;
+    let fields: Vec<&str> = resolution.split_whitespace().collect();
+    let (height, width) = match fields.as_slice() {
+        ["-Y", h, "+X", w] => (
+            h.parse::<usize>().map_err(|_| invalid_data("bad HDR image height"))?,
+            w.parse::<usize>().map_err(|_| invalid_data("bad HDR image width"))?,
+        ),
+        _ => return Err(invalid_data(&format!("unsupported HDR resolution line: {}", resolution))),
+    };
+    if width == 0 || height == 0 {
+        return Err(invalid_data("empty HDR image"));
+    }
+
+    //宽高来自文件头，不按它们预先分配内存，像素随着实际读到的扫描线增加
+    let mut pixels = Vec::new();
+    let mut scanline = Vec::new();
+    for _ in 0..height {
+        read_scanline(input, width, &mut scanline)?;
+        pixels.extend(scanline.iter().map(rgbe_to_color));
+    }
+
+    Ok(Image { width, height, pixels })
+}
+
+fn read_scanline(input: &mut dyn BufRead, width: usize, scanline: &mut Vec<[u8; 4]>) -> io::Result<()> {
+    let mut first = [0u8; 4];
+    input.read_exact(&mut first)?;
+
+    let is_rle = (8..=0x7fff).contains(&width) && first[0] == 2 && first[1] == 2 && first[2] & 0x80 == 0;
+    if !is_rle {
+        //未压缩的扫描线，刚才读到的4个字节就是第一个像素；宽度没有上限，逐个像素读入
+        scanline.clear();
+        scanline.push(first);
+        for _ in 1..width {
+            let mut pixel = [0u8; 4];
+            input.read_exact(&mut pixel)?;
+            scanline.push(pixel);
+        }
+        return Ok(());
+    }
+    scanline.resize(width, [0u8; 4]);
+
+    if ((first[2] as usize) << 8 | first[3] as usize) != width {
+        return Err(invalid_data("HDR scanline width mismatch"));
+    }
+
+    for channel in 0..4 {
+        let mut x = 0;
+        while x < width {
+            let mut count = [0u8; 1];
+            input.read_exact(&mut count)?;
+
+            if count[0] > 128 {
+                let run = (count[0] - 128) as usize;
+                let mut value = [0u8; 1];
+                input.read_exact(&mut value)?;
+                if x + run > width {
+                    return Err(invalid_data("HDR run overflows scanline"));
+                }
+                for pixel in &mut scanline[x..x + run] {
+                    pixel[channel] = value[0];
+                }
+                x += run;
+            } else {
+                let run = count[0] as usize;
+                if run == 0 || x + run > width {
+                    return Err(invalid_data("bad HDR literal run"));
+                }
+                let mut values = [0u8; 128];
+                input.read_exact(&mut values[..run])?;
+                for (pixel, value) in scanline[x..x + run].iter_mut().zip(&values[..run]) {
+                    pixel[channel] = *value;
+                }
+                x += run;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_line(input: &mut dyn BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of HDR header"));
+    }
+    Ok(line.trim_end_matches(['\n', '\r']).to_string())
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+//RGB三个分量共用最大分量的二进制指数，尾数各占一个字节
+fn color_to_rgbe(c: &Color) -> [u8; 4] {
+    let r = c.f64_r().max(0.0);
+    let g = c.f64_g().max(0.0);
+    let b = c.f64_b().max(0.0);
+    let v = r.max(g).max(b);
+
+    if v.is_nan() || v < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+
+    //v = m * 2^e，m在[0.5, 1)之间
+    let e = v.log2().floor() as i32 + 1;
+    let scale = 256.0 / 2f64.powi(e);
+
+    let channel = |x: f64| (x * scale).min(255.0) as u8;
+    [channel(r), channel(g), channel(b), (e + 128).clamp(0, 255) as u8]
+}
+
+fn rgbe_to_color(rgbe: &[u8; 4]) -> Color {
+    if rgbe[3] == 0 {
+        return Color::black();
+    }
+
+    //与Radiance一致，解码时加0.5以抵消编码时截断带来的偏差
+    let f = 2f64.powi(rgbe[3] as i32 - (128 + 8));
+    Color::new((rgbe[0] as f64 + 0.5) * f, (rgbe[1] as f64 + 0.5) * f, (rgbe[2] as f64 + 0.5) * f)
+}
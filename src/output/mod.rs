@@ -0,0 +1,110 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::color::Color;
+use crate::postprocess::PostProcess;
+use crate::utils::clamp;
+
+mod exr;
+mod hdr;
+mod pfm;
+mod png;
+mod ppm;
+
+pub use self::exr::{read_exr, ExrWriter};
+pub use self::hdr::{read_hdr, HdrWriter};
+pub use self::pfm::PfmWriter;
+pub use self::png::{read_png, PngWriter};
+pub use self::ppm::{read_ppm, PpmWriter};
+
+//图片编码器，不同的图片格式各自实现
+pub trait ImageWriter {
+    //把宽width、高height的图片写入out，image按行从上到下、每行从左到右存储
+    fn write_image(&self, out: &mut dyn Write, image: &[Color], width: usize, height: usize) -> io::Result<()>;
+
+    //高动态范围格式直接保存线性颜色值，低动态范围格式写入的是经过后期处理、位于[0, 1]之间的显示值
+    fn is_hdr(&self) -> bool {
+        false
+    }
+}
+
+//读入内存的图片，pixels按行从上到下、每行从左到右存储
+#[derive(Clone)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Color>,
+}
+
+//根据格式名称选择编码器：ppm（二进制P6）、ppm-ascii（P3）、png（8位）、png16（16位）、
+//pfm（32位浮点）、hdr（Radiance RGBE）、exr（OpenEXR半精度浮点）、exr32（OpenEXR 32位浮点）
+//pfm、hdr、exr保存的是线性颜色值，不做gamma校正和截断
+pub fn writer_for_format(format: &str) -> Option<Box<dyn ImageWriter>> {
+    match format.to_ascii_lowercase().as_str() {
+        "ppm" => Some(Box::new(PpmWriter::binary())),
+        "ppm-ascii" => Some(Box::new(PpmWriter::ascii())),
+        "png" => Some(Box::new(PngWriter::new(8))),
+        "png16" => Some(Box::new(PngWriter::new(16))),
+        "pfm" => Some(Box::new(PfmWriter)),
+        "hdr" => Some(Box::new(HdrWriter)),
+        "exr" => Some(Box::new(ExrWriter::half())),
+        "exr32" => Some(Box::new(ExrWriter::float())),
+        _ => None,
+    }
+}
+
+//根据文件扩展名选择编码器
+pub fn writer_for_path(path: &Path) -> Option<Box<dyn ImageWriter>> {
+    writer_for_format(path.extension()?.to_str()?)
+}
+
+//用writer把图片保存到path
+//低动态范围格式在写入之前先经过post_process处理，高动态范围格式保存原始的线性颜色值
+pub fn save_image(writer: &dyn ImageWriter, path: &Path, image: &[Color], width: usize, height: usize, post_process: &PostProcess) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    if writer.is_hdr() {
+        writer.write_image(&mut out, image, width, height)?;
+    } else {
+        let mut display = image.to_vec();
+        post_process.apply(&mut display);
+        writer.write_image(&mut out, &display, width, height)?;
+    }
+    out.flush()
+}
+
+//按文件扩展名读取图片：hdr、exr保存的是线性颜色值，ppm、png的像素值按sRGB解码为线性颜色值
+pub fn load_image(path: &Path) -> io::Result<Image> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    let mut input = BufReader::new(File::open(path)?);
+
+    match extension.as_str() {
+        "hdr" => read_hdr(&mut input),
+        "exr" => read_exr(&mut input),
+        "ppm" => read_ppm(&mut input),
+        "png" => read_png(&mut input),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("unsupported image format: {}", path.display()))),
+    }
+}
+
+//把[0, 1]之间的显示值量化为8位整数
+#[inline]
+fn to_u8(x: f64) -> u8 {
+    (256.0 * clamp(x, 0.0, 0.999)) as u8
+}
+
+//把[0, 1]之间的显示值量化为16位整数
+#[inline]
+fn to_u16(x: f64) -> u16 {
+    (65536.0 * clamp(x, 0.0, 0.99999)) as u16
+}
+
+//sRGB编码的显示值解码为线性值
+#[inline]
+pub(crate) fn srgb_to_linear(x: f64) -> f64 {
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
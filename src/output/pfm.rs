@@ -0,0 +1,28 @@
+use std::io::{self, Write};
+
+use crate::color::Color;
+use super::ImageWriter;
+
+//PFM格式，每个通道一个32位浮点数，保存未经gamma校正的线性颜色值
+//比例因子为负数表示小端序，像素按行从下到上存储
+pub struct PfmWriter;
+
+impl ImageWriter for PfmWriter {
+    fn write_image(&self, out: &mut dyn Write, image: &[Color], width: usize, height: usize) -> io::Result<()> {
+        write!(out, "PF\n{} {}\n-1.0\n", width, height)?;
+
+        for row in image.chunks(width).rev() {
+            for c in row {
+                out.write_all(&(c.f64_r() as f32).to_le_bytes())?;
+                out.write_all(&(c.f64_g() as f32).to_le_bytes())?;
+                out.write_all(&(c.f64_b() as f32).to_le_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_hdr(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,74 @@
+use std::io::{self, Read, Write};
+
+use crate::color::Color;
+use super::{srgb_to_linear, to_u16, to_u8, Image, ImageWriter};
+
+//PNG格式，支持8位和16位的RGB
+pub struct PngWriter {
+    bit_depth: u8,
+}
+
+impl PngWriter {
+    //bit_depth只能是8或16
+    pub fn new(bit_depth: u8) -> Self {
+        assert!(bit_depth == 8 || bit_depth == 16, "PNG bit depth must be 8 or 16");
+        PngWriter { bit_depth }
+    }
+}
+
+impl ImageWriter for PngWriter {
+    fn write_image(&self, out: &mut dyn Write, image: &[Color], width: usize, height: usize) -> io::Result<()> {
+        let mut encoder = ::png::Encoder::new(out, width as u32, height as u32);
+        encoder.set_color(::png::ColorType::Rgb);
+
+        let data: Vec<u8> = if self.bit_depth == 8 {
+            encoder.set_depth(::png::BitDepth::Eight);
+            image.iter()
+                .flat_map(|c| [to_u8(c.f64_r()), to_u8(c.f64_g()), to_u8(c.f64_b())])
+                .collect()
+        } else {
+            //16位PNG按大端序存储
+            encoder.set_depth(::png::BitDepth::Sixteen);
+            image.iter()
+                .flat_map(|c| [to_u16(c.f64_r()), to_u16(c.f64_g()), to_u16(c.f64_b())])
+                .flat_map(|x| x.to_be_bytes())
+                .collect()
+        };
+
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer.write_image_data(&data).map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)
+    }
+}
+
+//读取PNG文件，调色板和低位深的图片会被展开为8位，像素值按sRGB解码为线性颜色值，alpha通道被忽略
+pub fn read_png(input: &mut dyn Read) -> io::Result<Image> {
+    let mut decoder = ::png::Decoder::new(input);
+    decoder.set_transformations(::png::Transformations::EXPAND);
+    let mut reader = decoder.read_info().map_err(io::Error::other)?;
+
+    let mut data = vec![0u8; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).map_err(io::Error::other)?;
+    let (width, height) = (info.width as usize, info.height as usize);
+
+    let channels = info.color_type.samples();
+    let samples: Vec<f64> = match info.bit_depth {
+        ::png::BitDepth::Sixteen => data[..info.buffer_size()].chunks_exact(2)
+            .map(|x| u16::from_be_bytes([x[0], x[1]]) as f64 / 65535.0)
+            .collect(),
+        _ => data[..info.buffer_size()].iter().map(|&x| x as f64 / 255.0).collect(),
+    };
+
+    //灰度图片的三个分量相同
+    let pixels = samples.chunks_exact(channels)
+        .map(|c| match c.len() {
+            1 | 2 => {
+                let l = srgb_to_linear(c[0]);
+                Color::new(l, l, l)
+            }
+            _ => Color::new(srgb_to_linear(c[0]), srgb_to_linear(c[1]), srgb_to_linear(c[2])),
+        })
+        .collect();
+
+    Ok(Image { width, height, pixels })
+}
@@ -0,0 +1,130 @@
+use std::io::{self, BufRead, Read, Write};
+
+use crate::color::Color;
+use super::{srgb_to_linear, to_u8, Image, ImageWriter};
+
+//PPM格式，P6为二进制格式，P3为文本格式（每个像素一行）
+pub struct PpmWriter {
+    binary: bool,
+}
+
+impl PpmWriter {
+    pub fn binary() -> Self {
+        PpmWriter { binary: true }
+    }
+
+    pub fn ascii() -> Self {
+        PpmWriter { binary: false }
+    }
+}
+
+impl ImageWriter for PpmWriter {
+    fn write_image(&self, out: &mut dyn Write, image: &[Color], width: usize, height: usize) -> io::Result<()> {
+        let magic = if self.binary { "P6" } else { "P3" };
+        write!(out, "{}\n{} {}\n255\n", magic, width, height)?;
+
+        for c in image {
+            let (r, g, b) = (to_u8(c.f64_r()), to_u8(c.f64_g()), to_u8(c.f64_b()));
+
+            if self.binary {
+                out.write_all(&[r, g, b])?;
+            } else {
+                writeln!(out, "{} {} {}", r, g, b)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//读取PPM文件（P3或P6），最大值可以是1到65535，像素值按sRGB解码为线性颜色值
+pub fn read_ppm(input: &mut dyn BufRead) -> io::Result<Image> {
+    let magic = read_token(input)?;
+    let binary = match magic.as_str() {
+        "P6" => true,
+        "P3" => false,
+        _ => return Err(invalid_data("not a PPM file")),
+    };
+
+    let width = read_number(input, "width")?;
+    let height = read_number(input, "height")?;
+    let max_value = read_number(input, "maximum value")?;
+    if max_value == 0 || max_value > 65535 {
+        return Err(invalid_data(&format!("bad PPM maximum value: {}", max_value)));
+    }
+
+    //宽高来自文件头，不能按它们预先分配内存：二进制数据最多读入头部声明的字节数，不足时报错
+    let bytes_per_sample = if max_value > 255 { 2 } else { 1 };
+    let count = width.checked_mul(height).and_then(|n| n.checked_mul(3))
+        .filter(|n| n.checked_mul(bytes_per_sample).is_some())
+        .ok_or_else(|| invalid_data("PPM image is too large"))?;
+    let mut samples = Vec::new();
+    if binary {
+        //P6的头部之后紧跟一个空白字符，read_token已经把它读掉了；最大值超过255时每个分量占2个字节（大端序）
+        let expected = count * bytes_per_sample;
+        let mut data = Vec::new();
+        Read::take(&mut *input, expected as u64).read_to_end(&mut data)?;
+        if data.len() < expected {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                format!("PPM image needs {} bytes of pixel data, got {}", expected, data.len())));
+        }
+        if bytes_per_sample == 1 {
+            samples.extend(data.iter().map(|&x| x as usize));
+        } else {
+            samples.extend(data.chunks_exact(2).map(|x| u16::from_be_bytes([x[0], x[1]]) as usize));
+        }
+    } else {
+        for _ in 0..count {
+            samples.push(read_number(input, "sample")?);
+        }
+    }
+
+    let scale = 1.0 / max_value as f64;
+    let pixels = samples.chunks_exact(3)
+        .map(|c| Color::new(
+            srgb_to_linear(c[0].min(max_value) as f64 * scale),
+            srgb_to_linear(c[1].min(max_value) as f64 * scale),
+            srgb_to_linear(c[2].min(max_value) as f64 * scale),
+        ))
+        .collect();
+
+    Ok(Image { width, height, pixels })
+}
+
+//读取一个以空白字符结尾的记号，跳过#开头的注释，结尾的那个空白字符也会被读掉
+fn read_token(input: &mut dyn BufRead) -> io::Result<String> {
+    let mut token = String::new();
+    let mut in_comment = false;
+    let mut byte = [0u8; 1];
+
+    loop {
+        if input.read(&mut byte)? == 0 {
+            if token.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of PPM file"));
+            }
+            return Ok(token);
+        }
+
+        let c = byte[0] as char;
+        if in_comment {
+            in_comment = c != '\n' && c != '\r';
+        } else if c == '#' && token.is_empty() {
+            in_comment = true;
+        } else if c.is_ascii_whitespace() {
+            if !token.is_empty() {
+                return Ok(token);
+            }
+        } else {
+            token.push(c);
+        }
+    }
+}
+
+fn read_number(input: &mut dyn BufRead, what: &str) -> io::Result<usize> {
+    let token = read_token(input)?;
+    token.parse().map_err(|_| invalid_data(&format!("bad PPM {}: {}", what, token)))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
@@ -0,0 +1,107 @@
+use crate::utils::{random_usize, Sampler};
+use crate::vector::{Point3, Vector3};
+
+const POINT_COUNT: usize = 256;
+
+//Perlin噪声：在整数格点上放置随机的单位梯度向量，格点之间做平滑插值
+//梯度向量和三个轴上的置换表都由sampler生成，相同的种子得到相同的噪声
+pub struct Perlin {
+    gradients: Vec<Vector3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new(sampler: &mut Sampler) -> Self {
+        let gradients = (0..POINT_COUNT)
+            .map(|_| Vector3::random(-1.0, 1.0, sampler).unit())
+            .collect();
+
+        Perlin {
+            gradients,
+            perm_x: Perlin::generate_perm(sampler),
+            perm_y: Perlin::generate_perm(sampler),
+            perm_z: Perlin::generate_perm(sampler),
+        }
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Perlin::new(&mut Sampler::new(seed))
+    }
+
+    //p处的噪声值，大致在[-1, 1]之间
+    pub fn noise(&self, p: &Point3) -> f64 {
+        let (fx, fy, fz) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let (u, v, w) = (p.x - fx, p.y - fy, p.z - fz);
+        let (i, j, k) = (fx as i64, fy as i64, fz as i64);
+
+        //所在格子8个顶点上的梯度向量
+        let mut c = [[[Vector3::new(0.0, 0.0, 0.0); 2]; 2]; 2];
+        for (di, plane) in c.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, g) in row.iter_mut().enumerate() {
+                    let index = self.perm_x[Perlin::wrap(i + di as i64)]
+                        ^ self.perm_y[Perlin::wrap(j + dj as i64)]
+                        ^ self.perm_z[Perlin::wrap(k + dk as i64)];
+                    *g = self.gradients[index];
+                }
+            }
+        }
+
+        Perlin::perlin_interp(&c, u, v, w)
+    }
+
+    //多个倍频的噪声加权求和后取绝对值，每一层频率加倍、权重减半，结果非负
+    pub fn turbulence(&self, p: &Point3, octaves: u32) -> f64 {
+        let mut accum = 0.0;
+        let mut temp_p = *p;
+        let mut weight = 1.0;
+
+        for _ in 0..octaves {
+            accum += weight * self.noise(&temp_p);
+            weight *= 0.5;
+            temp_p = temp_p * 2.0;
+        }
+
+        accum.abs()
+    }
+
+    //Fisher-Yates洗牌得到0..POINT_COUNT的随机排列
+    fn generate_perm(sampler: &mut Sampler) -> Vec<usize> {
+        let mut perm: Vec<usize> = (0..POINT_COUNT).collect();
+        for i in (1..POINT_COUNT).rev() {
+            let target = random_usize(i + 1, sampler);
+            perm.swap(i, target);
+        }
+        perm
+    }
+
+    #[inline]
+    fn wrap(i: i64) -> usize {
+        i.rem_euclid(POINT_COUNT as i64) as usize
+    }
+
+    //用Hermite三次曲线平滑插值权重，避免格子边界上出现明显的折痕
+    fn perlin_interp(c: &[[[Vector3; 2]; 2]; 2], u: f64, v: f64, w: f64) -> f64 {
+        let uu = u * u * (3.0 - 2.0 * u);
+        let vv = v * v * (3.0 - 2.0 * v);
+        let ww = w * w * (3.0 - 2.0 * w);
+
+        let mut accum = 0.0;
+        for (i, plane) in c.iter().enumerate() {
+            for (j, row) in plane.iter().enumerate() {
+                for (k, g) in row.iter().enumerate() {
+                    let (fi, fj, fk) = (i as f64, j as f64, k as f64);
+                    let weight = Vector3::new(u - fi, v - fj, w - fk);
+                    accum += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * g.dot(&weight);
+                }
+            }
+        }
+
+        accum
+    }
+}
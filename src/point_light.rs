@@ -0,0 +1,28 @@
+use crate::color::Color;
+use crate::light::{Light, LightSample};
+use crate::vector::Point3;
+
+//点光源：从position向各个方向均匀发光，强度为color · intensity，照度随距离的平方衰减
+pub struct PointLight {
+    pub position: Point3,
+    pub intensity: Color,
+}
+
+impl PointLight {
+    pub fn new(position: Point3, color: &Color, intensity: f64) -> Self {
+        PointLight { position, intensity: *color * intensity }
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let to_light = self.position - *p;
+        let distance_squared = to_light.length_squared();
+        if distance_squared == 0.0 {
+            return None;
+        }
+
+        let distance = distance_squared.sqrt();
+        Some(LightSample { direction: to_light / distance, distance, irradiance: self.intensity / distance_squared })
+    }
+}
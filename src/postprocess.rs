@@ -0,0 +1,154 @@
+use std::str::FromStr;
+
+use crate::color::Color;
+
+//色调映射算子，把[0, ∞)的线性颜色值压缩到[0, 1]的显示范围
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ToneMap {
+    //直接截断，超过1.0的部分全部丢失
+    Clamp,
+    //x / (1 + x)
+    Reinhard,
+    //带白点的Reinhard：亮度等于white的颜色映射为1.0
+    ReinhardExtended { white: f64 },
+    //ACES filmic曲线（Krzysztof Narkowicz的拟合版本）
+    Aces,
+    //Uncharted 2中John Hable的filmic曲线
+    Hable,
+}
+
+//传递函数（编码），把色调映射后的线性值转换为显示器使用的非线性值
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TransferFunction {
+    Linear,
+    Srgb,
+    Gamma(f64),
+}
+
+//后期处理：曝光 -> 色调映射 -> 编码，在低动态范围的图片格式（PPM、PNG）写入之前作用于整个画面
+pub struct PostProcess {
+    pub exposure: f64, //曝光补偿，单位为档（stop），每增加1档亮度翻倍
+    pub tone_map: ToneMap,
+    pub transfer: TransferFunction,
+}
+
+impl PostProcess {
+    pub fn new(exposure: f64, tone_map: ToneMap, transfer: TransferFunction) -> Self {
+        PostProcess { exposure, tone_map, transfer }
+    }
+
+    pub fn apply(&self, image: &mut [Color]) {
+        let scale = 2f64.powf(self.exposure);
+
+        for c in image.iter_mut() {
+            let process = |x: f64| self.transfer.encode(self.tone_map.map(x * scale));
+            *c = Color::new(process(c.f64_r()), process(c.f64_g()), process(c.f64_b()));
+        }
+    }
+}
+
+impl Default for PostProcess {
+    //与最初的输出效果一致：不调整曝光，直接截断，gamma=2.0
+    fn default() -> Self {
+        PostProcess::new(0.0, ToneMap::Clamp, TransferFunction::Gamma(2.0))
+    }
+}
+
+impl ToneMap {
+    pub fn map(&self, x: f64) -> f64 {
+        let x = x.max(0.0);
+
+        let y = match *self {
+            ToneMap::Clamp => x,
+            ToneMap::Reinhard => x / (1.0 + x),
+            ToneMap::ReinhardExtended { white } => x * (1.0 + x / (white * white)) / (1.0 + x),
+            ToneMap::Aces => (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14),
+            ToneMap::Hable => {
+                const WHITE_POINT: f64 = 11.2;
+                const EXPOSURE_BIAS: f64 = 2.0;
+                hable_curve(x * EXPOSURE_BIAS) / hable_curve(WHITE_POINT)
+            }
+        };
+
+        y.clamp(0.0, 1.0)
+    }
+}
+
+fn hable_curve(x: f64) -> f64 {
+    const A: f64 = 0.15; //肩部强度
+    const B: f64 = 0.50; //线性段强度
+    const C: f64 = 0.10; //线性段角度
+    const D: f64 = 0.20; //趾部强度
+    const E: f64 = 0.02; //趾部分子
+    const F: f64 = 0.30; //趾部分母
+
+    ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F
+}
+
+//按名称解析：clamp、reinhard、reinhard-extended[:白点]、aces、hable
+impl FromStr for ToneMap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, param) = split_param(s);
+
+        match (name.as_str(), param) {
+            ("clamp", None) => Ok(ToneMap::Clamp),
+            ("reinhard", None) => Ok(ToneMap::Reinhard),
+            ("reinhard-extended", None) => Ok(ToneMap::ReinhardExtended { white: 4.0 }),
+            ("reinhard-extended", Some(white)) => Ok(ToneMap::ReinhardExtended { white: parse_positive(white)? }),
+            ("aces", None) => Ok(ToneMap::Aces),
+            ("hable", None) => Ok(ToneMap::Hable),
+            _ => Err(format!("unknown tone map operator `{}` (expected clamp, reinhard, reinhard-extended[:WHITE], aces or hable)", s)),
+        }
+    }
+}
+
+//按名称解析：linear、srgb、gamma:指数
+impl FromStr for TransferFunction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, param) = split_param(s);
+
+        match (name.as_str(), param) {
+            ("linear", None) => Ok(TransferFunction::Linear),
+            ("srgb", None) => Ok(TransferFunction::Srgb),
+            ("gamma", Some(gamma)) => Ok(TransferFunction::Gamma(parse_positive(gamma)?)),
+            _ => Err(format!("unknown output encoding `{}` (expected linear, srgb or gamma:N)", s)),
+        }
+    }
+}
+
+//"name:param"拆分为名称和参数
+fn split_param(s: &str) -> (String, Option<&str>) {
+    match s.split_once(':') {
+        Some((name, param)) => (name.trim().to_ascii_lowercase(), Some(param.trim())),
+        None => (s.trim().to_ascii_lowercase(), None),
+    }
+}
+
+fn parse_positive(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(x) if x > 0.0 && x.is_finite() => Ok(x),
+        _ => Err(format!("expected a positive number, got `{}`", s)),
+    }
+}
+
+impl TransferFunction {
+    pub fn encode(&self, x: f64) -> f64 {
+        let x = x.max(0.0);
+
+        match *self {
+            TransferFunction::Linear => x,
+            TransferFunction::Srgb => {
+                if x <= 0.0031308 {
+                    12.92 * x
+                } else {
+                    1.055 * x.powf(1.0 / 2.4) - 0.055
+                }
+            }
+            TransferFunction::Gamma(gamma) => x.powf(1.0 / gamma),
+        }
+    }
+}
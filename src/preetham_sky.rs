@@ -0,0 +1,171 @@
+use std::f64::consts::PI;
+
+use crate::background::Background;
+use crate::color::Color;
+use crate::directional_light::DirectionalLight;
+use crate::ray::Ray;
+use crate::vector::Vector3;
+
+//sky和太阳的亮度单位：1.0对应20 kcd/m²（太阳的照度对应20 klux），这样晴天的天空大约在0.1到1之间
+const UNIT: f64 = 1.0 / 20.0;
+//大气层外的太阳照度（klux）
+const SOLAR_ILLUMINANCE: f64 = 128.0;
+//太阳照度的衰减按红、绿、蓝三个波长（微米）计算
+const WAVELENGTHS: [f64; 3] = [0.65, 0.55, 0.45];
+//天顶角接近90度时Perez公式中的1 / cosθ会发散，按这个下限截断
+const MIN_COS_THETA: f64 = 0.01;
+
+//Preetham等人的晴天天空解析模型（A Practical Analytic Model for Daylight, 1999）
+//
+//天空某个方向的亮度Y和色度x、y都写成天顶处的值乘以Perez分布F(θ, γ) / F(0, θs)，θ是该方向的天顶角，
+//γ是该方向与太阳的夹角，θs是太阳的天顶角；Perez分布的五个系数和天顶处的值都是浑浊度turbidity的函数。
+//浑浊度描述大气中的雾霾，2是非常晴朗的天空，10是很浑浊的天空。
+//
+//太阳本身不出现在背景中，而是由sun()给出的平行光照亮场景，它的颜色和照度按同样的浑浊度计算大气的瑞利散射
+//和气溶胶散射的衰减得到，与天空的颜色一致。地平线以下是反照率为ground_albedo的无限大漫反射地面，
+//被太阳和天空照亮。
+pub struct PreethamSky {
+    sun_direction: Vector3, //指向太阳的单位向量
+    turbidity: f64,
+    intensity: f64,
+    perez: [[f64; 5]; 3], //Y、x、y的Perez系数A到E
+    zenith: [f64; 3], //天顶处的Y、x、y
+    ground: Color, //地面的辐亮度
+}
+
+impl PreethamSky {
+    //sun_direction指向太阳，太阳必须在地平线以上；turbidity在2到10之间；intensity同时缩放天空和太阳
+    pub fn new(sun_direction: &Vector3, turbidity: f64, ground_albedo: &Color, intensity: f64) -> Self {
+        assert!(sun_direction.y > 0.0, "the sun must be above the horizon");
+        assert!((2.0..=10.0).contains(&turbidity), "turbidity must be between 2 and 10");
+
+        let sun_direction = sun_direction.unit();
+        let t = turbidity;
+        let perez = [
+            [0.1787 * t - 1.4630, -0.3554 * t + 0.4275, -0.0227 * t + 5.3251, 0.1206 * t - 2.5771, -0.0670 * t + 0.3703],
+            [-0.0193 * t - 0.2592, -0.0665 * t + 0.0008, -0.0004 * t + 0.2125, -0.0641 * t - 0.8989, -0.0033 * t + 0.0452],
+            [-0.0167 * t - 0.2608, -0.0950 * t + 0.0092, -0.0079 * t + 0.2102, -0.0441 * t - 1.6537, -0.0109 * t + 0.0529],
+        ];
+
+        let theta_s = sun_direction.y.clamp(-1.0, 1.0).acos();
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let zenith_y = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+        let chromaticity = |c: [[f64; 4]; 3]| {
+            let poly = |k: [f64; 4]| k[0] * theta_s.powi(3) + k[1] * theta_s.powi(2) + k[2] * theta_s + k[3];
+            t * t * poly(c[0]) + t * poly(c[1]) + poly(c[2])
+        };
+        let zenith_x = chromaticity([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886],
+        ]);
+        let zenith_chroma_y = chromaticity([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688],
+        ]);
+
+        let mut sky = PreethamSky {
+            sun_direction,
+            turbidity,
+            intensity,
+            perez,
+            zenith: [zenith_y, zenith_x, zenith_chroma_y],
+            ground: Color::black(),
+        };
+
+        //地面是被太阳和天空照亮的漫反射平面：辐亮度 = albedo / π · 照度
+        let irradiance = sky.sun_irradiance() * sun_direction.y + sky.sky_irradiance();
+        sky.ground = ground_albedo.mul_color(&irradiance) / PI;
+        sky
+    }
+
+    pub fn sun_direction(&self) -> Vector3 {
+        self.sun_direction
+    }
+
+    pub fn turbidity(&self) -> f64 {
+        self.turbidity
+    }
+
+    //与天空配套的太阳光，光沿-sun_direction方向传播
+    pub fn sun(&self) -> DirectionalLight {
+        let irradiance = self.sun_irradiance();
+        let intensity = irradiance.f64_r().max(irradiance.f64_g()).max(irradiance.f64_b());
+        if intensity <= 0.0 {
+            return DirectionalLight::new(&-self.sun_direction, &Color::black(), 0.0);
+        }
+        DirectionalLight::new(&-self.sun_direction, &(irradiance / intensity), intensity)
+    }
+
+    //垂直于太阳光的平面上的照度：大气层外的照度乘以大气的透射率，光学厚度按Kasten-Young的大气质量
+    //放大瑞利散射（∝ λ^-4.08）和气溶胶散射（Ångström公式，β由浑浊度给出）的天顶光学厚度
+    pub fn sun_irradiance(&self) -> Color {
+        let zenith_angle = self.sun_direction.y.clamp(-1.0, 1.0).acos();
+        let air_mass = 1.0 / (zenith_angle.cos() + 0.50572 * (96.07995 - zenith_angle.to_degrees()).powf(-1.6364));
+        let beta = 0.04608 * self.turbidity - 0.04586;
+
+        let transmittance = WAVELENGTHS.map(|lambda| {
+            let rayleigh = 0.008735 * lambda.powf(-4.08);
+            let aerosol = beta * lambda.powf(-1.3);
+            (-air_mass * (rayleigh + aerosol)).exp()
+        });
+
+        Color::new(transmittance[0], transmittance[1], transmittance[2]) * (SOLAR_ILLUMINANCE * UNIT * self.intensity)
+    }
+
+    //天空在水平面上产生的照度，对上半球做数值积分
+    fn sky_irradiance(&self) -> Color {
+        let (n_theta, n_phi) = (32, 64);
+        let d_theta = PI / 2.0 / n_theta as f64;
+        let d_phi = 2.0 * PI / n_phi as f64;
+
+        let mut sum = Color::black();
+        for i in 0..n_theta {
+            let theta = (i as f64 + 0.5) * d_theta;
+            for j in 0..n_phi {
+                let phi = (j as f64 + 0.5) * d_phi;
+                let direction = Vector3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+                sum = sum + self.sky_radiance(&direction) * (theta.cos() * theta.sin() * d_theta * d_phi);
+            }
+        }
+        sum
+    }
+
+    //Perez分布
+    fn perez(coefficients: &[f64; 5], cos_theta: f64, gamma: f64) -> f64 {
+        let [a, b, c, d, e] = *coefficients;
+        (1.0 + a * (b / cos_theta).exp()) * (1.0 + c * (d * gamma).exp() + e * gamma.cos() * gamma.cos())
+    }
+
+    //地平线以上单位向量direction方向的天空辐亮度
+    fn sky_radiance(&self, direction: &Vector3) -> Color {
+        let cos_theta = direction.y.max(MIN_COS_THETA);
+        let gamma = direction.dot(&self.sun_direction).clamp(-1.0, 1.0).acos();
+        let theta_s = self.sun_direction.y.clamp(-1.0, 1.0).acos();
+
+        let [luminance, x, y] = [0, 1, 2].map(|k| {
+            self.zenith[k] * PreethamSky::perez(&self.perez[k], cos_theta, gamma) / PreethamSky::perez(&self.perez[k], 1.0, theta_s)
+        });
+
+        //xyY -> XYZ -> 线性sRGB
+        let big_x = x * luminance / y;
+        let big_z = (1.0 - x - y) * luminance / y;
+        let r = 3.2406 * big_x - 1.5372 * luminance - 0.4986 * big_z;
+        let g = -0.9689 * big_x + 1.8758 * luminance + 0.0415 * big_z;
+        let b = 0.0557 * big_x - 0.2040 * luminance + 1.0570 * big_z;
+
+        Color::new(r.max(0.0), g.max(0.0), b.max(0.0)) * (UNIT * self.intensity)
+    }
+}
+
+impl Background for PreethamSky {
+    fn color(&self, ray: &Ray) -> Color {
+        let direction = ray.dir.unit();
+        if direction.y < 0.0 {
+            return self.ground;
+        }
+
+        self.sky_radiance(&direction)
+    }
+}
@@ -0,0 +1,112 @@
+use std::ops::Mul;
+
+use crate::matrix::{Matrix4, Transform};
+use crate::utils::degrees_to_radians;
+use crate::vector::Vector3;
+
+//单位四元数表示旋转：绕单位轴a旋转θ对应 (cos(θ/2), a·sin(θ/2))
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Quaternion {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Quaternion {
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Quaternion { w, x, y, z }
+    }
+
+    pub fn identity() -> Self {
+        Quaternion::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    //绕过原点的axis轴按右手定则旋转degrees度，与Transform::rotate一致
+    pub fn from_axis_angle(axis: &Vector3, degrees: f64) -> Self {
+        let a = axis.unit();
+        let (sin, cos) = (degrees_to_radians(degrees) / 2.0).sin_cos();
+        Quaternion::new(cos, a.x * sin, a.y * sin, a.z * sin)
+    }
+
+    //依次绕x、y、z轴旋转（角度），与场景文件中rotate字段的顺序相同
+    pub fn from_euler(degrees: &Vector3) -> Self {
+        let qx = Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), degrees.x);
+        let qy = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), degrees.y);
+        let qz = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), degrees.z);
+        qz * qy * qx
+    }
+
+    pub fn dot(&self, other: &Quaternion) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn normalize(&self) -> Self {
+        let len = self.dot(self).sqrt();
+        Quaternion::new(self.w / len, self.x / len, self.y / len, self.z / len)
+    }
+
+    //q和-q表示同一个旋转
+    fn negate(&self) -> Self {
+        Quaternion::new(-self.w, -self.x, -self.y, -self.z)
+    }
+
+    //两个旋转之间的夹角（弧度），范围[0, π]
+    pub fn angle_to(&self, other: &Quaternion) -> f64 {
+        2.0 * self.dot(other).abs().min(1.0).acos()
+    }
+
+    //球面线性插值，沿最短的路径从self(s=0)转到other(s=1)，角速度恒定
+    pub fn slerp(&self, other: &Quaternion, s: f64) -> Self {
+        let mut other = *other;
+        let mut cos = self.dot(&other);
+        if cos < 0.0 {
+            other = other.negate();
+            cos = -cos;
+        }
+
+        //夹角很小时sin接近0，退化为线性插值
+        let (a, b) = if cos > 0.9995 {
+            (1.0 - s, s)
+        } else {
+            let theta = cos.acos();
+            let sin = theta.sin();
+            (((1.0 - s) * theta).sin() / sin, (s * theta).sin() / sin)
+        };
+
+        Quaternion::new(
+            a * self.w + b * other.w,
+            a * self.x + b * other.x,
+            a * self.y + b * other.y,
+            a * self.z + b * other.z,
+        ).normalize()
+    }
+
+    //旋转矩阵，要求是单位四元数
+    pub fn to_matrix(&self) -> Matrix4 {
+        let Quaternion { w, x, y, z } = *self;
+        Matrix4::new([
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y), 0.0],
+            [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x), 0.0],
+            [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y), 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn to_transform(&self) -> Transform {
+        Transform::rotation(self.to_matrix())
+    }
+}
+
+//四元数乘法，a * b表示先做b的旋转再做a的旋转
+impl Mul for Quaternion {
+    type Output = Self;
+    fn mul(self, o: Self) -> Self {
+        Quaternion::new(
+            self.w * o.w - self.x * o.x - self.y * o.y - self.z * o.z,
+            self.w * o.x + self.x * o.w + self.y * o.z - self.z * o.y,
+            self.w * o.y - self.x * o.z + self.y * o.w + self.z * o.x,
+            self.w * o.z + self.x * o.y - self.y * o.x + self.z * o.w,
+        )
+    }
+}
@@ -0,0 +1,1131 @@
+// 场景描述文件的解析
+//
+// 场景文件是纯文本，每行一条指令，#之后为注释。指令由关键字、若干位置参数和若干key=value字段组成，
+// 包含空白字符的值需要用双引号括起来。数值可以写成分数形式（如16/9），向量和颜色写成逗号分隔的三个数（如0.5,0.5,0.5）。
+//
+//   render width=400 aspect=16/9 spp=100 max_depth=50 mis=power output=1.ppm
+//       渲染参数，各字段均可省略，默认值即为上面的值；mis是合并光源采样和BSDF采样的权重函数，可以是balance或power
+//
+//   camera lookfrom=13,2,3 lookat=0,0,0 vup=0,1,0 vfov=20 aperture=0.1 focus_dist=10 shutter=0,1
+//       相机，lookfrom和lookat必填；vup默认0,1,0，vfov默认90，aperture默认0，
+//       focus_dist默认为lookfrom到lookat的距离，shutter默认0,1
+//
+//   texture <名称> solid color=0.5,0.5,0.5
+//   texture <名称> checker even=0.2,0.3,0.1 odd=0.9,0.9,0.9 scale=0.32
+//   texture <名称> image file=earth.png
+//   texture <名称> noise scale=4 seed=0
+//   texture <名称> turbulence scale=4 octaves=7 seed=0
+//   texture <名称> marble scale=4 turbulence=10 vein=0,0,0 base=1,1,1 seed=0
+//   texture <名称> wood scale=8 turbulence=1 light=0.8,0.6,0.4 dark=0.45,0.3,0.15 seed=0
+//       定义有名字的纹理，名称不能重复；checker是按空间位置划分的三维棋盘格，scale为格子边长（默认1），
+//       even和odd可以是颜色或之前定义的纹理；image支持ppm、png、hdr、exr，相对路径相对于场景文件所在的目录
+//       noise、turbulence、marble、wood是基于Perlin噪声的程序纹理，seed决定噪声的随机置换表，
+//       scale越大花纹越密，各字段均可省略，默认值即为上面的值
+//
+//   material <名称> lambertian albedo=0.5,0.5,0.5
+//   material <名称> metal albedo=0.7,0.6,0.5 fuzz=0
+//   material <名称> dielectric ir=1.5
+//   material <名称> diffuse_light emit=4,4,4
+//   material <名称> isotropic albedo=1,1,1
+//       定义有名字的材质，名称不能重复；metal的fuzz默认为0；diffuse_light是自身发光的材质，emit可以大于1；
+//       isotropic是参与介质的相函数，向各个方向均匀散射
+//       albedo和emit既可以是颜色，也可以是之前定义的纹理名称；fuzz既可以是数值，也可以是纹理名称（取三个分量的平均值）
+//
+//   sphere center=0,-1000,0 radius=1000 material=ground [move_to=0,1,0 move_time=0,1]
+//       球体，material引用之前定义的材质；给出move_to时球体在move_time（默认0,1）时间段内从center移动到move_to
+//
+//   xy_rect x=0,555 y=0,555 z=555 material=white
+//   xz_rect x=0,555 z=0,555 y=0 material=white
+//   yz_rect y=0,555 z=0,555 x=0 material=green
+//       轴对齐的矩形，两个范围字段给出矩形在所在平面上的范围，第三个字段给出平面的位置；外法线指向坐标轴的正方向
+//
+//   box min=0,0,0 max=165,330,165 material=white [rotate=0,15,0 translate=265,0,295]
+//       长方体，min和max是两个相对的顶点
+//
+//   triangle v0=0,0,0 v1=1,0,0 v2=0,1,0 material=white
+//       三角形，顶点按逆时针顺序排列的一侧为正面
+//
+//   mesh file=model.obj [material=white] [scale=2 rotate=0,90,0 translate=0,1,0]
+//       从模型文件导入三角形网格，按扩展名支持Wavefront OBJ（及其引用的MTL材质）、PLY和STL，
+//       相对路径相对于场景文件所在的目录；没有指定材质的面（OBJ中MTL材质找不到的面、没有顶点颜色的PLY、STL）
+//       使用material，不给出material时为灰色的lambertian；带顶点颜色的PLY使用顶点颜色作为lambertian的颜色
+//
+//   volume file=smoke.vgrid min=0,0,0 max=1,1,1 [absorption=0 scattering=1 albedo=1,1,1 emission=0,0,0]
+//   volume file=smoke.raw resolution=64,64,64 min=0,0,0 max=1,1,1
+//       体素网格给出密度的非均匀参与介质，网格拉伸后充满min和max之间的长方体。vgrid是带文本文件头的格式，
+//       raw是没有文件头的小端序32位浮点数，需要用resolution给出分辨率，两种格式见src/import/voxels.rs的说明。
+//       吸收、散射和发光系数分别是absorption、scattering、emission乘以密度，默认值即为上面的值；
+//       albedo是散射的颜色；发光的介质absorption必须大于0
+//
+//   model <名称> file=bunny.obj [material=white]
+//   instance model=<名称> [scale=2 rotate=0,90,0 translate=0,1,0]
+//       model导入模型但不放入场景，instance把之前定义的模型按给定的变换放入场景，同一个模型的所有实例共用一份网格数据
+//
+//   keyframe <名称> time=0 [translate=0,0,0 rotate=0,0,0 scale=1]
+//       给名为<名称>的动画添加一个关键帧，同一个动画的多个关键帧写成多行，时间不能重复；
+//       关键帧之间平移和缩放线性插值，旋转按最短路径做球面插值，转过超过180度时需要插入中间的关键帧
+//
+//   各种物体都可以带变换字段：先按scale缩放（一个数或每个轴一个数），再依次绕x、y、z轴按rotate
+//   旋转（角度），最后按translate平移；animation=<名称>在静态变换之后再施加动画，随光线的时间运动，
+//   配合相机的shutter产生运动模糊
+//
+//   sphere、box和mesh可以带density字段（如density=0.01），这时物体不再是表面，而是以它为边界的均匀参与介质
+//   （雾、烟），density是单位长度上的散射概率密度，material作为相函数，一般是isotropic；边界必须是封闭的凸物体
+//
+//   发光材质（diffuse_light）的球体、矩形和三角形自动登记为光源，漫反射表面上会直接对它们采样，
+//   小光源照亮的场景因此收敛得快得多；运动的球体和带变换、动画或density的物体不登记，只靠随机游走照亮场景
+//
+//   point_light position=0,5,0 [color=1,1,1 intensity=1]
+//   spot_light position=0,5,0 direction=0,-1,0 [inner=30 outer=30 color=1,1,1 intensity=1]
+//   directional_light direction=-1,-1,0 [color=1,1,1 intensity=1]
+//       没有几何形状的光源，相机看不到它们，只照亮漫反射表面和参与介质。点光源和聚光灯的强度为color · intensity，
+//       照度随距离的平方衰减；聚光灯沿direction发光，与光轴的夹角小于inner（角度）时为全部强度，
+//       在inner和outer之间平滑衰减，超过outer时不发光，inner默认等于outer；
+//       平行光沿direction传播（如太阳光），垂直于光线的平面上的照度为color · intensity
+//
+//   background gradient
+//   background solid color=0,0,0
+//   background image file=sky.hdr [intensity=1 rotation=0]
+//   background sky sun_direction=1,1,-1 [turbidity=3 ground=0.3,0.3,0.3 intensity=1]
+//       光线没有击中任何物体时的颜色，默认为天空渐变色；纯黑背景下只有光源照亮场景。
+//       image是等距柱状投影的全景环境贴图（一般是hdr或exr），相对路径相对于场景文件所在的目录，
+//       intensity缩放亮度，rotation让环境绕竖直的y轴旋转（角度）；渲染时按贴图的亮度做重要性采样。
+//       sky是Preetham晴天天空模型，sun_direction指向太阳（必须在地平线以上），turbidity是2到10之间的
+//       大气浑浊度，ground是地平线以下地面的反照率；同时加入一个与天空一致的平行光作为太阳
+//
+// 场景文件必须包含一条camera指令，render和background最多只能出现一次。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::aarect::{XyRect, XzRect, YzRect};
+use crate::animation::{AnimatedTransform, Keyframe};
+use crate::background::{Background, GradientBackground, SolidBackground};
+use crate::bvh::BvhSplit;
+use crate::camera::{Camera, MisHeuristic, RenderSettings};
+use crate::checker_texture::CheckerTexture;
+use crate::color::Color;
+use crate::constant_medium::ConstantMedium;
+use crate::cuboid::Cuboid;
+use crate::dielectric::Dielectric;
+use crate::diffuse_light::DiffuseLight;
+use crate::directional_light::DirectionalLight;
+use crate::environment_map::EnvironmentMap;
+use crate::grid_volume::GridVolume;
+use crate::hittable::Hittable;
+use crate::image_texture::ImageTexture;
+use crate::import;
+use crate::instance::{AnimatedInstance, Instance};
+use crate::isotropic::Isotropic;
+use crate::lambertian::Lambertian;
+use crate::light::Light;
+use crate::material::Material;
+use crate::matrix::Transform;
+use crate::metal::Metal;
+use crate::noise_texture::{MarbleTexture, NoiseTexture, TurbulenceTexture, WoodTexture, DEFAULT_OCTAVES};
+use crate::perlin::Perlin;
+use crate::point_light::PointLight;
+use crate::preetham_sky::PreethamSky;
+use crate::quaternion::Quaternion;
+use crate::sphere::Sphere;
+use crate::spot_light::SpotLight;
+use crate::texture::{SolidColor, Texture};
+use crate::triangle::Triangle;
+use crate::vector::{Point3, Vector3};
+use crate::world::World;
+
+//从场景文件构建的场景
+pub struct Scene {
+    pub world: World,
+    pub camera: Camera,
+    pub settings: RenderSettings,
+    pub output: PathBuf, //输出图片的路径
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    Missing(&'static str), //缺少必需的指令
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(e) => write!(f, "cannot read scene file: {}", e),
+            SceneError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            SceneError::Missing(keyword) => write!(f, "missing {} directive", keyword),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<io::Error> for SceneError {
+    fn from(e: io::Error) -> Self {
+        SceneError::Io(e)
+    }
+}
+
+pub fn load_scene(path: &Path) -> Result<Scene, SceneError> {
+    let text = fs::read_to_string(path)?;
+    parse_scene_in(&text, path.parent().unwrap_or(Path::new("")))
+}
+
+//解析场景文本，其中引用的相对路径相对于当前目录
+pub fn parse_scene(text: &str) -> Result<Scene, SceneError> {
+    parse_scene_in(text, Path::new(""))
+}
+
+//解析场景文本，其中引用的相对路径相对于base_dir
+fn parse_scene_in(text: &str, base_dir: &Path) -> Result<Scene, SceneError> {
+    let mut parser = SceneParser::new(base_dir);
+
+    for (index, line) in text.lines().enumerate() {
+        let tokens = tokenize(line).map_err(|message| SceneError::Parse { line: index + 1, message })?;
+        if tokens.is_empty() {
+            continue;
+        }
+
+        let mut directive = Directive::new(index + 1, tokens)?;
+        parser.parse_directive(&mut directive)?;
+        directive.finish()?;
+    }
+
+    parser.finish()
+}
+
+struct SceneParser {
+    base_dir: PathBuf, //场景文件所在的目录
+    world: World,
+    textures: HashMap<String, Arc<dyn Texture>>,
+    perlins: HashMap<u64, Arc<Perlin>>, //相同种子的噪声纹理共用一个Perlin噪声
+    materials: HashMap<String, Arc<dyn Material>>,
+    models: HashMap<String, Arc<dyn Hittable>>,
+    animations: HashMap<String, AnimatedTransform>,
+    camera: Option<(usize, CameraArgs)>, //camera指令所在的行及其参数
+    render: Option<usize>, //render指令所在的行
+    background: Option<(usize, Arc<dyn Background>)>, //background指令所在的行及背景
+    image_width: usize,
+    aspect_ratio: f64,
+    samples_per_pixel: i32,
+    max_depth: i32,
+    mis: MisHeuristic,
+    output: PathBuf,
+}
+
+impl SceneParser {
+    fn new(base_dir: &Path) -> Self {
+        SceneParser {
+            base_dir: base_dir.to_path_buf(),
+            world: World::new(),
+            textures: HashMap::new(),
+            perlins: HashMap::new(),
+            materials: HashMap::new(),
+            models: HashMap::new(),
+            animations: HashMap::new(),
+            camera: None,
+            render: None,
+            background: None,
+            image_width: 400,
+            aspect_ratio: 16.0 / 9.0,
+            samples_per_pixel: 100,
+            max_depth: 50,
+            mis: MisHeuristic::Power,
+            output: PathBuf::from("1.ppm"),
+        }
+    }
+
+    fn parse_directive(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        match d.keyword.as_str() {
+            "render" => self.parse_render(d),
+            "camera" => self.parse_camera(d),
+            "texture" => self.parse_texture(d),
+            "material" => self.parse_material(d),
+            "sphere" => self.parse_sphere(d),
+            "xy_rect" | "xz_rect" | "yz_rect" => self.parse_rect(d),
+            "box" => self.parse_box(d),
+            "triangle" => self.parse_triangle(d),
+            "mesh" => self.parse_mesh(d),
+            "volume" => self.parse_volume(d),
+            "model" => self.parse_model(d),
+            "instance" => self.parse_instance(d),
+            "keyframe" => self.parse_keyframe(d),
+            "point_light" | "spot_light" | "directional_light" => self.parse_light(d),
+            "background" => self.parse_background(d),
+            _ => Err(d.error(format!("unknown directive `{}`", d.keyword))),
+        }
+    }
+
+    fn parse_render(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        if let Some(line) = self.render {
+            return Err(d.error(format!("duplicate render directive (first one on line {})", line)));
+        }
+        self.render = Some(d.line);
+        d.positional(0)?;
+
+        if let Some(width) = d.integer("width")? {
+            self.image_width = width;
+        }
+        if let Some(aspect) = d.number("aspect")? {
+            self.aspect_ratio = aspect;
+        }
+        if let Some(spp) = d.integer("spp")? {
+            self.samples_per_pixel = i32::try_from(spp)
+                .map_err(|_| d.field_error("spp", format!("must be at most {}", i32::MAX)))?;
+        }
+        if let Some(depth) = d.integer("max_depth")? {
+            self.max_depth = i32::try_from(depth)
+                .map_err(|_| d.field_error("max_depth", format!("must be at most {}", i32::MAX)))?;
+        }
+        if let Some(mis) = d.string("mis") {
+            self.mis = mis.parse().map_err(|e| d.field_error("mis", e))?;
+        }
+        if let Some(output) = d.string("output") {
+            self.output = PathBuf::from(output);
+        }
+
+        if self.image_width < 2 {
+            return Err(d.field_error("width", "must be at least 2".to_string()));
+        }
+        if self.aspect_ratio <= 0.0 || ((self.image_width as f64 / self.aspect_ratio) as usize) < 2 {
+            return Err(d.field_error("aspect", "image height must be at least 2 pixels".to_string()));
+        }
+        if self.samples_per_pixel < 1 {
+            return Err(d.field_error("spp", "must be at least 1".to_string()));
+        }
+        if self.max_depth < 1 {
+            return Err(d.field_error("max_depth", "must be at least 1".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn parse_camera(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        if let Some((line, _)) = self.camera {
+            return Err(d.error(format!("duplicate camera directive (first one on line {})", line)));
+        }
+        d.positional(0)?;
+
+        let lookfrom = d.required_vector("lookfrom")?;
+        let lookat = d.required_vector("lookat")?;
+        let vup = d.vector("vup")?.unwrap_or(Vector3::new(0.0, 1.0, 0.0));
+        let vfov = d.number("vfov")?.unwrap_or(90.0);
+        let aperture = d.number("aperture")?.unwrap_or(0.0);
+        let focus_dist = d.number("focus_dist")?.unwrap_or((lookfrom - lookat).length());
+        let (shutter_open, shutter_close) = d.pair("shutter")?.unwrap_or((0.0, 1.0));
+
+        if (lookfrom - lookat).near_zero() {
+            return Err(d.field_error("lookat", "must differ from lookfrom".to_string()));
+        }
+        //vup与视线方向平行时无法确定相机的上方
+        if vup.cross(&(lookfrom - lookat)).near_zero() {
+            return Err(d.field_error("vup", "must not be parallel to the view direction".to_string()));
+        }
+        if vfov <= 0.0 || vfov >= 180.0 {
+            return Err(d.field_error("vfov", "must be between 0 and 180 degrees".to_string()));
+        }
+        if shutter_open > shutter_close {
+            return Err(d.field_error("shutter", "open time must not be after close time".to_string()));
+        }
+
+        //宽高比由render指令决定，它可能写在camera之后，所以先记下参数，等整个文件读完再创建相机
+        self.camera = Some((d.line, CameraArgs { lookfrom, lookat, vup, vfov, aperture, focus_dist, shutter_open, shutter_close }));
+
+        Ok(())
+    }
+
+    fn parse_texture(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        let args = d.positional(2)?;
+        let (name, kind) = (args[0].clone(), args[1].clone());
+
+        if self.textures.contains_key(&name) {
+            return Err(d.error(format!("texture `{}` is already defined", name)));
+        }
+
+        let texture: Arc<dyn Texture> = match kind.as_str() {
+            "solid" => Arc::new(SolidColor::new(&d.required_color("color")?)),
+            "checker" => {
+                let even = self.texture(d, "even")?;
+                let odd = self.texture(d, "odd")?;
+                let scale = d.number("scale")?.unwrap_or(1.0);
+                if scale <= 0.0 {
+                    return Err(d.field_error("scale", "must be positive".to_string()));
+                }
+                Arc::new(CheckerTexture::new(scale, even, odd))
+            }
+            "image" => {
+                let file = d.required_string("file")?;
+                let image = ImageTexture::load(&self.base_dir.join(&file))
+                    .map_err(|e| d.field_error("file", format!("cannot load image `{}`: {}", file, e)))?;
+                Arc::new(image)
+            }
+            "noise" => {
+                let (noise, scale) = self.noise_args(d, 4.0)?;
+                Arc::new(NoiseTexture::new(noise, scale))
+            }
+            "turbulence" => {
+                let (noise, scale) = self.noise_args(d, 4.0)?;
+                let octaves = d.integer("octaves")?.unwrap_or(DEFAULT_OCTAVES as usize);
+                if octaves < 1 {
+                    return Err(d.field_error("octaves", "must be at least 1".to_string()));
+                }
+                Arc::new(TurbulenceTexture::new(noise, scale, octaves as u32))
+            }
+            "marble" => {
+                let (noise, scale) = self.noise_args(d, 4.0)?;
+                let turbulence = d.number("turbulence")?.unwrap_or(10.0);
+                let vein = d.color("vein")?.unwrap_or(Color::black());
+                let base = d.color("base")?.unwrap_or(Color::new(1.0, 1.0, 1.0));
+                Arc::new(MarbleTexture::new(noise, scale, turbulence, &vein, &base))
+            }
+            "wood" => {
+                let (noise, scale) = self.noise_args(d, 8.0)?;
+                let turbulence = d.number("turbulence")?.unwrap_or(1.0);
+                let light = d.color("light")?.unwrap_or(Color::new(0.8, 0.6, 0.4));
+                let dark = d.color("dark")?.unwrap_or(Color::new(0.45, 0.3, 0.15));
+                Arc::new(WoodTexture::new(noise, scale, turbulence, &light, &dark))
+            }
+            _ => return Err(d.error(format!("unknown texture type `{}` (expected solid, checker, image, noise, turbulence, marble or wood)", kind))),
+        };
+
+        self.textures.insert(name, texture);
+        Ok(())
+    }
+
+    fn parse_material(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        let args = d.positional(2)?;
+        let (name, kind) = (args[0].clone(), args[1].clone());
+
+        if self.materials.contains_key(&name) {
+            return Err(d.error(format!("material `{}` is already defined", name)));
+        }
+
+        let material: Arc<dyn Material> = match kind.as_str() {
+            "lambertian" => Arc::new(Lambertian::with_texture(self.texture(d, "albedo")?)),
+            "metal" => {
+                let albedo = self.texture(d, "albedo")?;
+                let fuzz: Arc<dyn Texture> = match d.string("fuzz") {
+                    None => Arc::new(SolidColor::new(&Color::black())),
+                    Some(value) => match parse_number(&value) {
+                        Ok(f) => Arc::new(SolidColor::new(&Color::new(f, f, f))),
+                        Err(_) => self.textures.get(&value).cloned()
+                            .ok_or_else(|| d.field_error("fuzz", format!("expected a number or a texture name, got `{}`", value)))?,
+                    },
+                };
+                Arc::new(Metal::with_textures(albedo, fuzz))
+            }
+            "dielectric" => Arc::new(Dielectric::new(d.required_number("ir")?)),
+            "diffuse_light" => Arc::new(DiffuseLight::with_texture(self.texture(d, "emit")?)),
+            "isotropic" => Arc::new(Isotropic::with_texture(self.texture(d, "albedo")?)),
+            _ => return Err(d.error(format!("unknown material type `{}` (expected lambertian, metal, dielectric, diffuse_light or isotropic)", kind))),
+        };
+
+        self.materials.insert(name, material);
+        Ok(())
+    }
+
+    fn parse_sphere(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        d.positional(0)?;
+
+        let center = d.required_vector("center")?;
+        let radius = d.required_number("radius")?;
+        let material = self.material(d)?;
+
+        if radius <= 0.0 {
+            return Err(d.field_error("radius", "must be positive".to_string()));
+        }
+
+        let mut sphere = Sphere::new(center, radius, material.clone());
+        if let Some(destination) = d.vector("move_to")? {
+            let (begin, end) = d.pair("move_time")?.unwrap_or((0.0, 1.0));
+            if end <= begin {
+                return Err(d.field_error("move_time", "end time must be greater than start time".to_string()));
+            }
+            sphere.move_to(&destination, begin, end - begin);
+        }
+
+        let placement = self.placement(d, Some(&material))?;
+        if sphere.is_moving() {
+            self.add_object(Box::new(sphere), &placement);
+        } else {
+            self.add_shape(Box::new(sphere), &material, &placement);
+        }
+        Ok(())
+    }
+
+    fn parse_rect(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        d.positional(0)?;
+
+        //两个范围字段和平面位置字段的名称
+        let (a, b, k) = match d.keyword.as_str() {
+            "xy_rect" => ("x", "y", "z"),
+            "xz_rect" => ("x", "z", "y"),
+            _ => ("y", "z", "x"),
+        };
+
+        let (a0, a1) = d.range(a)?;
+        let (b0, b1) = d.range(b)?;
+        let k = d.required_number(k)?;
+        let material = self.material(d)?;
+
+        let rect: Box<dyn Hittable> = match d.keyword.as_str() {
+            "xy_rect" => Box::new(XyRect::new(a0, a1, b0, b1, k, material.clone())),
+            "xz_rect" => Box::new(XzRect::new(a0, a1, b0, b1, k, material.clone())),
+            _ => Box::new(YzRect::new(a0, a1, b0, b1, k, material.clone())),
+        };
+
+        let placement = self.placement(d, None)?;
+        self.add_shape(rect, &material, &placement);
+        Ok(())
+    }
+
+    fn parse_box(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        d.positional(0)?;
+
+        let min = d.required_vector("min")?;
+        let max = d.required_vector("max")?;
+        let material = self.material(d)?;
+
+        if min.x >= max.x || min.y >= max.y || min.z >= max.z {
+            return Err(d.field_error("max", "must be greater than min on every axis".to_string()));
+        }
+
+        let placement = self.placement(d, Some(&material))?;
+        self.add_object(Box::new(Cuboid::new(&min, &max, material)), &placement);
+        Ok(())
+    }
+
+    fn parse_triangle(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        d.positional(0)?;
+
+        let v0 = d.required_vector("v0")?;
+        let v1 = d.required_vector("v1")?;
+        let v2 = d.required_vector("v2")?;
+        let material = self.material(d)?;
+
+        if (v1 - v0).cross(&(v2 - v0)).near_zero() {
+            return Err(d.error("degenerate triangle, the three vertices are collinear".to_string()));
+        }
+
+        let placement = self.placement(d, None)?;
+        self.add_shape(Box::new(Triangle::new(v0, v1, v2, material.clone())), &material, &placement);
+        Ok(())
+    }
+
+    fn parse_mesh(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        d.positional(0)?;
+
+        let material = self.model_material(d)?;
+        let objects = self.load_model(d, &material)?;
+        let placement = self.placement(d, Some(&material))?;
+        for object in objects {
+            self.add_object(object, &placement);
+        }
+
+        Ok(())
+    }
+
+    fn parse_volume(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        d.positional(0)?;
+
+        let file = d.required_string("file")?;
+        let min = d.required_vector("min")?;
+        let max = d.required_vector("max")?;
+        if min.x >= max.x || min.y >= max.y || min.z >= max.z {
+            return Err(d.field_error("max", "must be greater than min on every axis".to_string()));
+        }
+
+        let path = self.base_dir.join(&file);
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+        let grid = match extension.as_str() {
+            "vgrid" => import::load_vgrid(&path),
+            "raw" => {
+                let resolution = d.numbers("resolution", 3)?
+                    .ok_or_else(|| d.error("missing field `resolution` for a raw voxel file".to_string()))?;
+                if resolution.iter().any(|&n| n < 1.0 || n.fract() != 0.0) {
+                    return Err(d.field_error("resolution", "expected 3 positive integers".to_string()));
+                }
+                import::load_raw_voxels(&path, [resolution[0] as usize, resolution[1] as usize, resolution[2] as usize])
+            }
+            _ => return Err(d.field_error("file", format!("unsupported voxel format `{}` (expected .vgrid or .raw)", file))),
+        }.map_err(|e| d.field_error("file", e.to_string()))?;
+
+        let coefficient = |d: &mut Directive, key: &str, default: f64| -> Result<f64, SceneError> {
+            let value = d.number(key)?.unwrap_or(default);
+            if value < 0.0 {
+                return Err(d.field_error(key, "must not be negative".to_string()));
+            }
+            Ok(value)
+        };
+        let absorption = coefficient(d, "absorption", 0.0)?;
+        let scattering = coefficient(d, "scattering", 1.0)?;
+
+        let mut volume = GridVolume::new(Arc::new(grid), &min, &max, absorption, scattering);
+        if let Some(albedo) = d.color("albedo")? {
+            volume.set_albedo(&albedo);
+        }
+        if let Some(emission) = d.color("emission")? {
+            if absorption <= 0.0 {
+                return Err(d.field_error("emission", "an emissive volume needs a positive absorption".to_string()));
+            }
+            volume.set_emission(&emission);
+        }
+
+        let placement = self.placement(d, None)?;
+        self.add_object(Box::new(volume), &placement);
+        Ok(())
+    }
+
+    fn parse_model(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        let name = d.positional(1)?[0].clone();
+        if self.models.contains_key(&name) {
+            return Err(d.error(format!("model `{}` is already defined", name)));
+        }
+
+        //文件中有多个网格时合成一个整体，所有实例共用
+        let material = self.model_material(d)?;
+        let mut objects = self.load_model(d, &material)?;
+        let model: Arc<dyn Hittable> = if objects.len() == 1 {
+            Arc::from(objects.pop().unwrap())
+        } else {
+            let mut group = World::new();
+            for object in objects {
+                group.add(object);
+            }
+            group.build_bvh(0.0, 1.0, BvhSplit::Sah);
+            Arc::new(group)
+        };
+
+        self.models.insert(name, model);
+        Ok(())
+    }
+
+    fn parse_instance(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        d.positional(0)?;
+
+        let name = d.required_string("model")?;
+        let model = self.models.get(&name).cloned()
+            .ok_or_else(|| d.field_error("model", format!("undefined model `{}`", name)))?;
+        let placement = self.placement(d, None)?;
+
+        self.world.add(placement.place(model));
+        Ok(())
+    }
+
+    //按file字段的扩展名导入模型文件，material是没有指定材质的面使用的材质
+    fn load_model(&self, d: &mut Directive, material: &Arc<dyn Material>) -> Result<Vec<Box<dyn Hittable>>, SceneError> {
+        let file = d.required_string("file")?;
+
+        let path = self.base_dir.join(&file);
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+        let import_error = |e: import::ImportError| d.field_error("file", e.to_string());
+        let objects: Vec<Box<dyn Hittable>> = match extension.as_str() {
+            "obj" => import::load_obj(&path, material.clone()).map_err(import_error)?
+                .into_iter()
+                .map(|obj| Box::new(obj.mesh) as Box<dyn Hittable>)
+                .collect(),
+            "ply" => vec![Box::new(import::load_ply(&path, material.clone()).map_err(import_error)?)],
+            "stl" => vec![Box::new(import::load_stl(&path, material.clone()).map_err(import_error)?)],
+            _ => return Err(d.field_error("file", format!("unsupported model format `{}` (expected .obj, .ply or .stl)", file))),
+        };
+
+        Ok(objects)
+    }
+
+    //模型的material字段可以省略，默认为灰色的lambertian
+    fn model_material(&self, d: &mut Directive) -> Result<Arc<dyn Material>, SceneError> {
+        match d.string("material") {
+            Some(name) => self.materials.get(&name).cloned()
+                .ok_or_else(|| d.field_error("material", format!("undefined material `{}`", name))),
+            None => Ok(Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)))),
+        }
+    }
+
+    //可选的变换字段：先按scale缩放，再依次绕x、y、z轴按rotate旋转（角度），最后按translate平移
+    //三个字段都没有给出时返回None
+    fn transform(&self, d: &mut Directive) -> Result<Option<Transform>, SceneError> {
+        let scale = scale_factors(d)?;
+        let rotate = d.vector("rotate")?;
+        let translate = d.vector("translate")?;
+
+        if scale.is_none() && rotate.is_none() && translate.is_none() {
+            return Ok(None);
+        }
+
+        let mut transform = Transform::scale(&scale.unwrap_or(Vector3::new(1.0, 1.0, 1.0)));
+        if let Some(r) = rotate {
+            transform = transform.then(&Quaternion::from_euler(&r).to_transform());
+        }
+        if let Some(offset) = translate {
+            transform = transform.then(&Transform::translate(&offset));
+        }
+
+        Ok(Some(transform))
+    }
+
+    //物体在场景中的放置方式：静态的变换字段和animation字段引用的动画
+    //封闭的物体（medium_material为Some）还可以带density字段，这时物体成为以它为边界的均匀参与介质，
+    //medium_material作为介质的相函数
+    fn placement(&self, d: &mut Directive, medium_material: Option<&Arc<dyn Material>>) -> Result<Placement, SceneError> {
+        let medium = match medium_material {
+            Some(material) => match d.number("density")? {
+                Some(density) if density <= 0.0 => return Err(d.field_error("density", "must be positive".to_string())),
+                Some(density) => Some((density, material.clone())),
+                None => None,
+            },
+            None => None,
+        };
+        let transform = self.transform(d)?;
+        let animation = match d.string("animation") {
+            Some(name) => Some(self.animations.get(&name).cloned()
+                .ok_or_else(|| d.field_error("animation", format!("undefined animation `{}`", name)))?),
+            None => None,
+        };
+
+        Ok(Placement { medium, transform, animation })
+    }
+
+    //先在物体空间中充满介质，再施加静态变换和动画，然后加入场景
+    fn add_object(&mut self, object: Box<dyn Hittable>, placement: &Placement) {
+        if placement.is_empty() {
+            self.world.add(object);
+        } else {
+            let object = placement.place(Arc::from(object));
+            self.world.add(object);
+        }
+    }
+
+    //可以作为光源采样的物体（静止的球体、矩形、三角形）：材质发光并且没有变换、动画和介质时登记为光源
+    fn add_shape(&mut self, object: Box<dyn Hittable>, material: &Arc<dyn Material>, placement: &Placement) {
+        if material.is_emissive() && placement.is_empty() {
+            self.world.add_light(Arc::from(object));
+        } else {
+            self.add_object(object, placement);
+        }
+    }
+
+    fn parse_keyframe(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        let name = d.positional(1)?[0].clone();
+
+        let time = d.required_number("time")?;
+        let translation = d.vector("translate")?.unwrap_or(Vector3::new(0.0, 0.0, 0.0));
+        let rotation = Quaternion::from_euler(&d.vector("rotate")?.unwrap_or(Vector3::new(0.0, 0.0, 0.0)));
+        let scale = scale_factors(d)?.unwrap_or(Vector3::new(1.0, 1.0, 1.0));
+        if scale.x < 0.0 || scale.y < 0.0 || scale.z < 0.0 {
+            return Err(d.field_error("scale", "keyframe scale factors must be positive".to_string()));
+        }
+
+        let mut keyframes = self.animations.get(&name).map(|a| a.keyframes().to_vec()).unwrap_or_default();
+        if keyframes.iter().any(|key| key.time == time) {
+            return Err(d.field_error("time", format!("animation `{}` already has a keyframe at time {}", name, time)));
+        }
+        keyframes.push(Keyframe::new(time, translation, rotation, scale));
+
+        let animation = AnimatedTransform::new(keyframes).map_err(|e| d.error(e))?;
+        self.animations.insert(name, animation);
+        Ok(())
+    }
+
+    fn parse_light(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        d.positional(0)?;
+
+        let color = d.color("color")?.unwrap_or(Color::new(1.0, 1.0, 1.0));
+        let intensity = d.number("intensity")?.unwrap_or(1.0);
+        if color.f64_r() < 0.0 || color.f64_g() < 0.0 || color.f64_b() < 0.0 {
+            return Err(d.field_error("color", "must not be negative".to_string()));
+        }
+        if intensity < 0.0 {
+            return Err(d.field_error("intensity", "must not be negative".to_string()));
+        }
+
+        let light: Box<dyn Light> = match d.keyword.as_str() {
+            "point_light" => Box::new(PointLight::new(d.required_vector("position")?, &color, intensity)),
+            "spot_light" => {
+                let position = d.required_vector("position")?;
+                let direction = self.light_direction(d)?;
+                let outer = d.number("outer")?.unwrap_or(30.0);
+                let inner = d.number("inner")?.unwrap_or(outer);
+                if !(0.0..180.0).contains(&outer) {
+                    return Err(d.field_error("outer", "must be between 0 and 180 degrees".to_string()));
+                }
+                if !(0.0..=outer).contains(&inner) {
+                    return Err(d.field_error("inner", "must be between 0 and the outer angle".to_string()));
+                }
+                Box::new(SpotLight::new(position, &direction, &color, intensity, inner, outer))
+            }
+            _ => Box::new(DirectionalLight::new(&self.light_direction(d)?, &color, intensity)),
+        };
+
+        self.world.add_delta_light(light);
+        Ok(())
+    }
+
+    //聚光灯和平行光的direction字段：光传播的方向，不能是零向量
+    fn light_direction(&self, d: &mut Directive) -> Result<Vector3, SceneError> {
+        let direction = d.required_vector("direction")?;
+        if direction.near_zero() {
+            return Err(d.field_error("direction", "must not be a zero vector".to_string()));
+        }
+        Ok(direction)
+    }
+
+    fn parse_background(&mut self, d: &mut Directive) -> Result<(), SceneError> {
+        if let Some((line, _)) = &self.background {
+            return Err(d.error(format!("duplicate background directive (first one on line {})", line)));
+        }
+
+        let kind = d.positional(1)?[0].clone();
+        let background: Arc<dyn Background> = match kind.as_str() {
+            "gradient" => Arc::new(GradientBackground),
+            "solid" => Arc::new(SolidBackground::new(&d.required_color("color")?)),
+            "image" => {
+                let file = d.required_string("file")?;
+                let intensity = d.number("intensity")?.unwrap_or(1.0);
+                let rotation = d.number("rotation")?.unwrap_or(0.0);
+                if intensity < 0.0 {
+                    return Err(d.field_error("intensity", "must not be negative".to_string()));
+                }
+                let map = EnvironmentMap::load(&self.base_dir.join(&file), intensity, rotation)
+                    .map_err(|e| d.field_error("file", format!("cannot load image `{}`: {}", file, e)))?;
+                Arc::new(map)
+            }
+            "sky" => {
+                let sun_direction = d.required_vector("sun_direction")?;
+                let turbidity = d.number("turbidity")?.unwrap_or(3.0);
+                let ground = d.color("ground")?.unwrap_or(Color::new(0.3, 0.3, 0.3));
+                let intensity = d.number("intensity")?.unwrap_or(1.0);
+                if sun_direction.y <= 0.0 {
+                    return Err(d.field_error("sun_direction", "the sun must be above the horizon".to_string()));
+                }
+                if !(2.0..=10.0).contains(&turbidity) {
+                    return Err(d.field_error("turbidity", "must be between 2 and 10".to_string()));
+                }
+                if !(0.0..=1.0).contains(&ground.f64_r()) || !(0.0..=1.0).contains(&ground.f64_g()) || !(0.0..=1.0).contains(&ground.f64_b()) {
+                    return Err(d.field_error("ground", "must be between 0 and 1".to_string()));
+                }
+                if intensity < 0.0 {
+                    return Err(d.field_error("intensity", "must not be negative".to_string()));
+                }
+
+                let sky = PreethamSky::new(&sun_direction, turbidity, &ground, intensity);
+                self.world.add_delta_light(Box::new(sky.sun()));
+                Arc::new(sky)
+            }
+            _ => return Err(d.error(format!("unknown background type `{}` (expected gradient, solid, image or sky)", kind))),
+        };
+
+        self.background = Some((d.line, background));
+        Ok(())
+    }
+
+    //key字段给出的纹理：逗号分隔的三个数是纯色，否则是之前定义的纹理名称
+    fn texture(&self, d: &mut Directive, key: &str) -> Result<Arc<dyn Texture>, SceneError> {
+        let value = d.required_string(key)?;
+        if value.contains(',') {
+            let color = parse_numbers(&value, 3).map_err(|message| d.field_error(key, message))?;
+            return Ok(Arc::new(SolidColor::new(&Color::new(color[0], color[1], color[2]))));
+        }
+
+        self.textures.get(&value).cloned()
+            .ok_or_else(|| d.field_error(key, format!("undefined texture `{}`", value)))
+    }
+
+    //噪声纹理共同的seed和scale字段，相同种子的纹理共用一个Perlin噪声
+    fn noise_args(&mut self, d: &mut Directive, default_scale: f64) -> Result<(Arc<Perlin>, f64), SceneError> {
+        let seed = d.integer("seed")?.unwrap_or(0) as u64;
+        let scale = d.number("scale")?.unwrap_or(default_scale);
+        if scale <= 0.0 {
+            return Err(d.field_error("scale", "must be positive".to_string()));
+        }
+
+        let noise = self.perlins.entry(seed).or_insert_with(|| Arc::new(Perlin::with_seed(seed))).clone();
+        Ok((noise, scale))
+    }
+
+    //material字段引用的材质
+    fn material(&self, d: &mut Directive) -> Result<Arc<dyn Material>, SceneError> {
+        let name = d.required_string("material")?;
+        self.materials.get(&name).cloned()
+            .ok_or_else(|| d.field_error("material", format!("undefined material `{}`", name)))
+    }
+
+    fn finish(self) -> Result<Scene, SceneError> {
+        let (_, args) = self.camera.ok_or(SceneError::Missing("camera"))?;
+
+        let mut camera = Camera::new(args.lookfrom, args.lookat, args.vup, args.vfov, self.aspect_ratio,
+            args.aperture, args.focus_dist, args.shutter_open, args.shutter_close);
+        if let Some((_, background)) = self.background {
+            camera.set_background(background);
+        }
+
+        let settings = RenderSettings {
+            image_width: self.image_width,
+            image_height: (self.image_width as f64 / self.aspect_ratio) as usize,
+            samples_per_pixel: self.samples_per_pixel,
+            max_depth: self.max_depth,
+            threads: 0,
+            seed: 0,
+            mis: self.mis,
+        };
+
+        Ok(Scene { world: self.world, camera, settings, output: self.output })
+    }
+}
+
+struct CameraArgs {
+    lookfrom: Point3,
+    lookat: Point3,
+    vup: Vector3,
+    vfov: f64,
+    aperture: f64,
+    focus_dist: f64,
+    shutter_open: f64,
+    shutter_close: f64,
+}
+
+//物体的介质、静态变换和动画
+struct Placement {
+    medium: Option<(f64, Arc<dyn Material>)>, //介质的密度和相函数
+    transform: Option<Transform>,
+    animation: Option<AnimatedTransform>,
+}
+
+impl Placement {
+    fn is_empty(&self) -> bool {
+        self.medium.is_none() && self.transform.is_none() && self.animation.is_none()
+    }
+
+    fn place(&self, object: Arc<dyn Hittable>) -> Box<dyn Hittable> {
+        let object: Arc<dyn Hittable> = match &self.medium {
+            Some((density, material)) => Arc::new(ConstantMedium::new(object, *density, material.clone())),
+            None => object,
+        };
+
+        match (self.transform, &self.animation) {
+            (Some(transform), Some(animation)) => {
+                Box::new(AnimatedInstance::new(Arc::new(Instance::new(object, transform)), animation.clone()))
+            }
+            (None, Some(animation)) => Box::new(AnimatedInstance::new(object, animation.clone())),
+            (Some(transform), None) => Box::new(Instance::new(object, transform)),
+            (None, None) => Box::new(Instance::new(object, Transform::identity())),
+        }
+    }
+}
+
+//一行指令：关键字、位置参数和key=value字段
+struct Directive {
+    line: usize,
+    keyword: String,
+    args: Vec<String>,
+    fields: Vec<(String, String, bool)>, //字段名、字段值、是否已被读取
+}
+
+impl Directive {
+    fn new(line: usize, tokens: Vec<String>) -> Result<Self, SceneError> {
+        let mut tokens = tokens.into_iter();
+        let keyword = tokens.next().unwrap();
+        let mut args = Vec::new();
+        let mut fields: Vec<(String, String, bool)> = Vec::new();
+
+        for token in tokens {
+            match token.split_once('=') {
+                Some((key, value)) => {
+                    if fields.iter().any(|(k, _, _)| k == key) {
+                        return Err(SceneError::Parse { line, message: format!("{}: duplicate field `{}`", keyword, key) });
+                    }
+                    fields.push((key.to_string(), value.to_string(), false));
+                }
+                None if fields.is_empty() => args.push(token),
+                None => {
+                    return Err(SceneError::Parse { line, message: format!("{}: unexpected `{}` after fields", keyword, token) });
+                }
+            }
+        }
+
+        Ok(Directive { line, keyword, args, fields })
+    }
+
+    fn error(&self, message: String) -> SceneError {
+        SceneError::Parse { line: self.line, message: format!("{}: {}", self.keyword, message) }
+    }
+
+    fn field_error(&self, key: &str, message: String) -> SceneError {
+        SceneError::Parse { line: self.line, message: format!("{}: field `{}`: {}", self.keyword, key, message) }
+    }
+
+    //要求正好有count个位置参数
+    fn positional(&self, count: usize) -> Result<&[String], SceneError> {
+        if self.args.len() != count {
+            return Err(self.error(format!("expected {} positional argument(s), got {}", count, self.args.len())));
+        }
+        Ok(&self.args)
+    }
+
+    fn string(&mut self, key: &str) -> Option<String> {
+        self.fields.iter_mut()
+            .find(|(k, _, _)| k == key)
+            .map(|(_, value, used)| {
+                *used = true;
+                value.clone()
+            })
+    }
+
+    fn required_string(&mut self, key: &str) -> Result<String, SceneError> {
+        self.string(key).ok_or_else(|| self.error(format!("missing field `{}`", key)))
+    }
+
+    fn number(&mut self, key: &str) -> Result<Option<f64>, SceneError> {
+        match self.string(key) {
+            Some(value) => parse_number(&value).map(Some).map_err(|message| self.field_error(key, message)),
+            None => Ok(None),
+        }
+    }
+
+    fn required_number(&mut self, key: &str) -> Result<f64, SceneError> {
+        self.number(key)?.ok_or_else(|| self.error(format!("missing field `{}`", key)))
+    }
+
+    fn integer(&mut self, key: &str) -> Result<Option<usize>, SceneError> {
+        match self.string(key) {
+            Some(value) => value.parse::<usize>().map(Some)
+                .map_err(|_| self.field_error(key, format!("expected a non-negative integer, got `{}`", value))),
+            None => Ok(None),
+        }
+    }
+
+    //逗号分隔的若干个数
+    fn numbers(&mut self, key: &str, count: usize) -> Result<Option<Vec<f64>>, SceneError> {
+        match self.string(key) {
+            Some(value) => parse_numbers(&value, count).map(Some).map_err(|message| self.field_error(key, message)),
+            None => Ok(None),
+        }
+    }
+
+    fn pair(&mut self, key: &str) -> Result<Option<(f64, f64)>, SceneError> {
+        Ok(self.numbers(key, 2)?.map(|n| (n[0], n[1])))
+    }
+
+    //必填的范围a,b，要求a < b
+    fn range(&mut self, key: &str) -> Result<(f64, f64), SceneError> {
+        let (a, b) = self.pair(key)?.ok_or_else(|| self.error(format!("missing field `{}`", key)))?;
+        if a >= b {
+            return Err(self.field_error(key, format!("range start must be less than its end, got `{},{}`", a, b)));
+        }
+        Ok((a, b))
+    }
+
+    fn vector(&mut self, key: &str) -> Result<Option<Vector3>, SceneError> {
+        Ok(self.numbers(key, 3)?.map(|n| Vector3::new(n[0], n[1], n[2])))
+    }
+
+    fn required_vector(&mut self, key: &str) -> Result<Point3, SceneError> {
+        self.vector(key)?.ok_or_else(|| self.error(format!("missing field `{}`", key)))
+    }
+
+    fn color(&mut self, key: &str) -> Result<Option<Color>, SceneError> {
+        Ok(self.vector(key)?.map(|v| Color::new(v.x, v.y, v.z)))
+    }
+
+    fn required_color(&mut self, key: &str) -> Result<Color, SceneError> {
+        let v = self.required_vector(key)?;
+        Ok(Color::new(v.x, v.y, v.z))
+    }
+
+    //所有字段都必须被读取过，否则说明有拼写错误或不支持的字段
+    fn finish(&self) -> Result<(), SceneError> {
+        match self.fields.iter().find(|(_, _, used)| !used) {
+            Some((key, _, _)) => Err(self.error(format!("unknown field `{}`", key))),
+            None => Ok(()),
+        }
+    }
+}
+
+//数值，支持a/b形式的分数
+fn parse_number(s: &str) -> Result<f64, String> {
+    let parse = |x: &str| x.trim().parse::<f64>().ok().filter(|v| v.is_finite());
+
+    let value = match s.split_once('/') {
+        Some((a, b)) => parse(a).zip(parse(b)).filter(|&(_, b)| b != 0.0).map(|(a, b)| a / b),
+        None => parse(s),
+    };
+
+    value.ok_or_else(|| format!("expected a number, got `{}`", s))
+}
+
+//正好count个逗号分隔的数
+fn parse_numbers(s: &str, count: usize) -> Result<Vec<f64>, String> {
+    let numbers = s.split(',')
+        .map(parse_number)
+        .collect::<Result<Vec<f64>, String>>()?;
+
+    if numbers.len() != count {
+        return Err(format!("expected {} comma-separated numbers, got `{}`", count, s));
+    }
+
+    Ok(numbers)
+}
+
+//scale字段：一个数表示各轴相同的缩放，三个数分别给出各轴的缩放，缩放系数不能为0
+fn scale_factors(d: &mut Directive) -> Result<Option<Vector3>, SceneError> {
+    let value = match d.string("scale") {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    let factors = match parse_numbers(&value, 3) {
+        Ok(v) => Vector3::new(v[0], v[1], v[2]),
+        Err(_) => {
+            let s = parse_number(&value).map_err(|_| {
+                d.field_error("scale", format!("expected a number or 3 comma-separated numbers, got `{}`", value))
+            })?;
+            Vector3::new(s, s, s)
+        }
+    };
+    if factors.x == 0.0 || factors.y == 0.0 || factors.z == 0.0 {
+        return Err(d.field_error("scale", "scale factors must be non-zero".to_string()));
+    }
+
+    Ok(Some(factors))
+}
+
+//按空白字符切分一行，双引号内的空白不切分，#之后为注释
+fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut in_quotes = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                in_token = true;
+            }
+            '#' if !in_quotes => break,
+            c if c.is_whitespace() && !in_quotes => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err("unterminated quoted string".to_string());
+    }
+    if in_token {
+        tokens.push(current);
+    }
+
+    Ok(tokens)
+}
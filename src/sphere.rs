@@ -1,14 +1,18 @@
-use std::rc::Rc;
+use std::f64::consts::PI;
+use std::sync::Arc;
 
-use crate::Ray;
-use crate::vector::Point3; 
+use crate::ray::Ray;
+use crate::onb::Onb;
+use crate::utils::{random_f64, Sampler};
+use crate::vector::{Point3, Vector3};
+use crate::aabb::Aabb;
 use crate::hittable::{HitInfo, Hittable};
 use crate::material::Material;
 pub struct Sphere {
     pub center: Point3, // 球心坐标
     pub radius: f64, // 半径
 
-    pub material: Rc<dyn Material>, //材质
+    pub material: Arc<dyn Material>, //材质
 
     // 模拟球体的运动
     is_moving: bool,
@@ -18,7 +22,7 @@ pub struct Sphere {
 }
 
 impl Sphere {
-    pub fn new(center: Point3, radius: f64, material: Rc<dyn Material>) -> Self {
+    pub fn new(center: Point3, radius: f64, material: Arc<dyn Material>) -> Self {
         Sphere {
             center,
             radius,
@@ -39,6 +43,10 @@ impl Sphere {
         self.is_moving = true;
     }
 
+    pub fn is_moving(&self) -> bool {
+        self.is_moving
+    }
+
     // 根据当前时间获取球体中心的位置
     fn get_current_center(&self, current_time: f64) -> Point3 {
         if !self.is_moving {
@@ -47,11 +55,21 @@ impl Sphere {
             self.center + (self.move_destination - self.center) * ((current_time - self.move_begin_time) / self.move_duration)
         }
     }
+
+    // 单位球面上点p的球面坐标(u, v)，都在[0, 1]之间
+    // theta是与-y轴的夹角，phi是绕y轴从-x轴开始经过+z、+x、-z的角度
+    // u = phi / 2π，v = theta / π
+    fn get_sphere_uv(p: &Point3) -> (f64, f64) {
+        let theta = (-p.y).clamp(-1.0, 1.0).acos();
+        let phi = (-p.z).atan2(p.x) + PI;
+
+        (phi / (2.0 * PI), theta / PI)
+    }
 }
 
 impl Hittable for Sphere {
     //判断光线r是否击中以center为球心半径为r的圆球
-    fn hit(&self, r: &Ray, t_min: f64, t_max: f64) -> Option<HitInfo> {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, _sampler: &mut Sampler) -> Option<HitInfo<'_>> {
         let current_center = self.get_current_center(r.time);
         //光线起点到球心的向量
         let oc = r.orig - current_center;
@@ -86,8 +104,56 @@ impl Hittable for Sphere {
         }
 
         let pos = r.at(t);
-        let info = HitInfo::new(r, pos, &((pos - current_center) / self.radius), t, &*self.material);
+        let outward_normal = (pos - current_center) / self.radius;
+        let (u, v) = Sphere::get_sphere_uv(&outward_normal);
+        let info = HitInfo::new(r, pos, &outward_normal, t, u, v, &*self.material);
 
         Some(info)
     }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        let r = Vector3::new(self.radius, self.radius, self.radius);
+        let box0 = Aabb::new(self.get_current_center(time0) - r, self.get_current_center(time0) + r);
+
+        if !self.is_moving {
+            return Some(box0);
+        }
+
+        //球体做直线运动，开始和结束时刻的包围盒合起来就能覆盖整个运动过程
+        let box1 = Aabb::new(self.get_current_center(time1) - r, self.get_current_center(time1) + r);
+        Some(box0.surrounding(&box1))
+    }
+
+    //作为光源采样时只考虑球心位于center的静止球体
+    //从球外看，球体张成一个圆锥，在圆锥内的立体角上均匀采样；origin在球内时在整个球面方向上均匀采样
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3, sampler: &mut Sampler) -> f64 {
+        if self.hit(&Ray::new(*origin, *direction, 0.0), 0.001, f64::INFINITY, sampler).is_none() {
+            return 0.0;
+        }
+
+        let distance_squared = (self.center - *origin).length_squared();
+        if distance_squared <= self.radius * self.radius {
+            return 1.0 / (4.0 * PI);
+        }
+
+        let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+        1.0 / solid_angle
+    }
+
+    fn random(&self, origin: &Point3, sampler: &mut Sampler) -> Vector3 {
+        let direction = self.center - *origin;
+        let distance_squared = direction.length_squared();
+        if distance_squared <= self.radius * self.radius {
+            return Vector3::random_unit_vector(sampler);
+        }
+
+        //圆锥内均匀分布的方向：cosθ在[cos_theta_max, 1]之间均匀分布，绕圆锥轴的角度在[0, 2π)之间均匀分布
+        let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+        let z = 1.0 + random_f64(sampler) * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * random_f64(sampler);
+        let sin_theta = (1.0 - z * z).sqrt();
+
+        Onb::build_from_w(&direction).local(phi.cos() * sin_theta, phi.sin() * sin_theta, z)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,65 @@
+use crate::color::Color;
+use crate::light::{Light, LightSample};
+use crate::utils::degrees_to_radians;
+use crate::vector::{Point3, Vector3};
+
+//聚光灯：从position沿direction发出一个圆锥形的光束，与光轴的夹角小于inner时为全部强度，
+//在inner和outer之间平滑地衰减到0，超过outer时不发光；强度和距离衰减与点光源相同
+pub struct SpotLight {
+    pub position: Point3,
+    direction: Vector3,
+    pub intensity: Color,
+    cos_inner: f64,
+    cos_outer: f64,
+}
+
+impl SpotLight {
+    //inner和outer是光束的半角（角度），必须满足0 <= inner <= outer < 180
+    pub fn new(position: Point3, direction: &Vector3, color: &Color, intensity: f64, inner: f64, outer: f64) -> Self {
+        assert!(0.0 <= inner && inner <= outer && outer < 180.0, "spot light angles must satisfy 0 <= inner <= outer < 180");
+
+        SpotLight {
+            position,
+            direction: direction.unit(),
+            intensity: *color * intensity,
+            cos_inner: degrees_to_radians(inner).cos(),
+            cos_outer: degrees_to_radians(outer).cos(),
+        }
+    }
+
+    pub fn direction(&self) -> Vector3 {
+        self.direction
+    }
+
+    //从光源看向光轴夹角余弦为cos_theta的方向时的强度比例，在内外圆锥之间用smoothstep过渡
+    pub fn falloff(&self, cos_theta: f64) -> f64 {
+        if cos_theta >= self.cos_inner {
+            return 1.0;
+        }
+        if cos_theta <= self.cos_outer {
+            return 0.0;
+        }
+
+        let x = (cos_theta - self.cos_outer) / (self.cos_inner - self.cos_outer);
+        x * x * (3.0 - 2.0 * x)
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, p: &Point3) -> Option<LightSample> {
+        let to_light = self.position - *p;
+        let distance_squared = to_light.length_squared();
+        if distance_squared == 0.0 {
+            return None;
+        }
+
+        let distance = distance_squared.sqrt();
+        let direction = to_light / distance;
+        let falloff = self.falloff(-direction.dot(&self.direction));
+        if falloff == 0.0 {
+            return None;
+        }
+
+        Some(LightSample { direction, distance, irradiance: self.intensity * (falloff / distance_squared) })
+    }
+}
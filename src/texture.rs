@@ -0,0 +1,32 @@
+use crate::color::Color;
+use crate::hittable::HitInfo;
+use crate::vector::Point3;
+
+//纹理：根据表面坐标(u, v)和击中点在物体自身空间中的位置p给出颜色
+pub trait Texture: Send + Sync {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color;
+
+    //击中点处的颜色，材质通过它取纹理；默认按表面坐标和物体空间中的位置取值，需要其它击中信息（如顶点颜色）的纹理重写它
+    fn value_at(&self, hit_info: &HitInfo) -> Color {
+        self.value(hit_info.u, hit_info.v, &hit_info.local_pos)
+    }
+}
+
+//纯色纹理
+pub struct SolidColor {
+    pub color: Color
+}
+
+impl SolidColor {
+    pub fn new(color: &Color) -> Self {
+        SolidColor {
+            color: *color
+        }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f64, _v: f64, _p: &Point3) -> Color {
+        self.color
+    }
+}
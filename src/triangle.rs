@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::hittable::{area_pdf_value, HitInfo, Hittable};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::utils::{random_f64, Sampler};
+use crate::vector::{Point3, Vector3};
+
+//单独的三角形，顶点按逆时针顺序排列时外法线朝向观察者
+//表面坐标(u, v)是击中点相对于v1、v2的重心坐标
+pub struct Triangle {
+    pub v0: Point3,
+    pub v1: Point3,
+    pub v2: Point3,
+    pub material: Arc<dyn Material>,
+}
+
+impl Triangle {
+    pub fn new(v0: Point3, v1: Point3, v2: Point3, material: Arc<dyn Material>) -> Self {
+        Triangle { v0, v1, v2, material }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, _sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        let (t, b1, b2) = intersect(r, t_min, t_max, &self.v0, &self.v1, &self.v2)?;
+        let outward_normal = (self.v1 - self.v0).cross(&(self.v2 - self.v0)).unit();
+
+        Some(HitInfo::new(r, r.at(t), &outward_normal, t, b1, b2, &*self.material))
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(bounding_box(&self.v0, &self.v1, &self.v2))
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vector3, sampler: &mut Sampler) -> f64 {
+        let area = (self.v1 - self.v0).cross(&(self.v2 - self.v0)).length() / 2.0;
+        area_pdf_value(self, area, origin, direction, sampler)
+    }
+
+    //在三角形上均匀取点：第一个随机数开平方后，重心坐标在三角形上均匀分布
+    fn random(&self, origin: &Point3, sampler: &mut Sampler) -> Vector3 {
+        let s = random_f64(sampler).sqrt();
+        let r = random_f64(sampler);
+        let point = self.v0 * (1.0 - s) + self.v1 * (s * (1.0 - r)) + self.v2 * (s * r);
+        point - *origin
+    }
+}
+
+//Möller–Trumbore算法：把击中点写成重心坐标形式v0 + b1*(v1-v0) + b2*(v2-v0)，与光线方程联立后用克莱姆法则求解，
+//不需要先求出三角形所在的平面。返回(t, b1, b2)
+pub(crate) fn intersect(r: &Ray, t_min: f64, t_max: f64, v0: &Point3, v1: &Point3, v2: &Point3) -> Option<(f64, f64, f64)> {
+    let edge1 = *v1 - *v0;
+    let edge2 = *v2 - *v0;
+
+    let p = r.dir.cross(&edge2);
+    let det = edge1.dot(&p);
+    //行列式接近0说明光线与三角形所在平面平行，或三角形退化成了线段
+    //按边长的平方缩放阈值，避免很小或很大的三角形被误判
+    if det.abs() <= 1e-12 * edge1.length_squared().max(edge2.length_squared()) * r.dir.length() {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+
+    let s = r.orig - *v0;
+    let b1 = s.dot(&p) * inv_det;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let q = s.cross(&edge1);
+    let b2 = r.dir.dot(&q) * inv_det;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(&q) * inv_det;
+    if t < t_min || t > t_max {
+        return None;
+    }
+
+    Some((t, b1, b2))
+}
+
+pub(crate) fn bounding_box(v0: &Point3, v1: &Point3, v2: &Point3) -> Aabb {
+    let minimum = Point3::new(v0.x.min(v1.x).min(v2.x), v0.y.min(v1.y).min(v2.y), v0.z.min(v1.z).min(v2.z));
+    let maximum = Point3::new(v0.x.max(v1.x).max(v2.x), v0.y.max(v1.y).max(v2.y), v0.z.max(v1.z).max(v2.z));
+    Aabb::new(minimum, maximum).pad()
+}
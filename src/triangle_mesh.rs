@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::bvh::{BvhNode, BvhSplit};
+use crate::color::Color;
+use crate::hittable::{HitInfo, Hittable};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::triangle;
+use crate::utils::Sampler;
+use crate::vector::{Point3, Vector3};
+
+//网格中的一个三角形面，三个顶点分别引用位置、法线和纹理坐标缓冲区中的元素
+#[derive(Debug, Copy, Clone)]
+pub struct MeshFace {
+    pub positions: [usize; 3],
+    pub normals: Option<[usize; 3]>, //没有顶点法线时使用三角形自身的法线
+    pub uvs: Option<[usize; 3]>, //没有纹理坐标时(u, v)为重心坐标
+}
+
+impl MeshFace {
+    pub fn new(positions: [usize; 3]) -> Self {
+        MeshFace { positions, normals: None, uvs: None }
+    }
+}
+
+//网格的顶点数据，所有三角形共用同一份位置、法线和纹理坐标缓冲区
+#[derive(Debug, Default, Clone)]
+pub struct MeshData {
+    pub positions: Vec<Point3>,
+    pub normals: Vec<Vector3>,
+    pub uvs: Vec<(f64, f64)>,
+    pub colors: Vec<Color>, //顶点颜色，与positions一一对应；为空时网格没有顶点颜色
+    pub faces: Vec<MeshFace>,
+}
+
+impl MeshData {
+    pub fn new() -> Self {
+        MeshData::default()
+    }
+
+    //用相邻三角形法线的面积加权平均作为每个顶点的法线，替换原有的顶点法线
+    pub fn compute_vertex_normals(&mut self) {
+        let mut normals = vec![Vector3::new(0.0, 0.0, 0.0); self.positions.len()];
+
+        for face in &self.faces {
+            let [i0, i1, i2] = face.positions;
+            //叉积的长度是三角形面积的两倍，直接累加就是按面积加权
+            let n = (self.positions[i1] - self.positions[i0]).cross(&(self.positions[i2] - self.positions[i0]));
+            for i in face.positions {
+                normals[i] = normals[i] + n;
+            }
+        }
+
+        self.normals = normals.into_iter()
+            .map(|n| if n.near_zero() { n } else { n.unit() })
+            .collect();
+        for face in &mut self.faces {
+            face.normals = Some(face.positions);
+        }
+    }
+
+    //检查所有面引用的下标都在缓冲区范围之内
+    fn validate(&self) -> Result<(), String> {
+        if !self.colors.is_empty() && self.colors.len() != self.positions.len() {
+            return Err(format!("mesh has {} vertex colors for {} positions", self.colors.len(), self.positions.len()));
+        }
+
+        for (index, face) in self.faces.iter().enumerate() {
+            let check = |indices: Option<[usize; 3]>, len: usize, what: &str| -> Result<(), String> {
+                match indices {
+                    Some(indices) if indices.iter().any(|&i| i >= len) => {
+                        Err(format!("face {} references {} {:?}, but there are only {}", index, what, indices, len))
+                    }
+                    _ => Ok(()),
+                }
+            };
+
+            check(Some(face.positions), self.positions.len(), "positions")?;
+            check(face.normals, self.normals.len(), "normals")?;
+            check(face.uvs, self.uvs.len(), "uvs")?;
+        }
+
+        Ok(())
+    }
+}
+
+//三角形网格：顶点数据只保存一份，所有三角形共用一个材质，内部用BVH加速求交
+pub struct TriangleMesh {
+    data: Arc<MeshData>,
+    bvh: Option<BvhNode>, //没有三角形时为None
+}
+
+impl TriangleMesh {
+    //smooth为true时在三角形内插值顶点法线（平滑着色），否则使用三角形自身的法线（平面着色）
+    //面引用的下标超出缓冲区范围时返回错误
+    pub fn new(data: MeshData, material: Arc<dyn Material>, smooth: bool) -> Result<Self, String> {
+        data.validate()?;
+
+        let data = Arc::new(data);
+        let triangles: Vec<Box<dyn Hittable>> = (0..data.faces.len())
+            .map(|face| Box::new(MeshTriangle { data: data.clone(), material: material.clone(), face, smooth }) as Box<dyn Hittable>)
+            .collect();
+
+        //网格是静止的，包围盒与时间无关
+        let bvh = if triangles.is_empty() { None } else { Some(BvhNode::new(triangles, 0.0, 1.0, BvhSplit::Sah)) };
+
+        Ok(TriangleMesh { data, bvh })
+    }
+
+    pub fn data(&self) -> &MeshData {
+        &self.data
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        self.bvh.as_ref()?.hit(r, t_min, t_max, sampler)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        self.bvh.as_ref()?.bounding_box(time0, time1)
+    }
+}
+
+//网格中的一个三角形，只保存网格数据的引用和面的下标
+struct MeshTriangle {
+    data: Arc<MeshData>,
+    material: Arc<dyn Material>,
+    face: usize,
+    smooth: bool,
+}
+
+impl MeshTriangle {
+    fn vertices(&self) -> (&Point3, &Point3, &Point3) {
+        let [i0, i1, i2] = self.data.faces[self.face].positions;
+        (&self.data.positions[i0], &self.data.positions[i1], &self.data.positions[i2])
+    }
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, _sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        let (v0, v1, v2) = self.vertices();
+        let (t, b1, b2) = triangle::intersect(r, t_min, t_max, v0, v1, v2)?;
+        let b0 = 1.0 - b1 - b2;
+        let face = &self.data.faces[self.face];
+
+        let geometric_normal = (*v1 - *v0).cross(&(*v2 - *v0)).unit();
+        let shading_normal = match face.normals {
+            Some([n0, n1, n2]) if self.smooth => {
+                let n = self.data.normals[n0] * b0 + self.data.normals[n1] * b1 + self.data.normals[n2] * b2;
+                if n.near_zero() { None } else { Some(n.unit()) }
+            }
+            _ => None,
+        };
+
+        let (u, v) = match face.uvs {
+            Some([t0, t1, t2]) => {
+                let (uv0, uv1, uv2) = (self.data.uvs[t0], self.data.uvs[t1], self.data.uvs[t2]);
+                (uv0.0 * b0 + uv1.0 * b1 + uv2.0 * b2, uv0.1 * b0 + uv1.1 * b1 + uv2.1 * b2)
+            }
+            None => (b1, b2),
+        };
+
+        //正反面由几何法线决定：轮廓附近插值法线可能与几何法线对光线在哪一侧的判断不一致，
+        //这时按插值法线会让电介质把折射率取反、漫反射向真实表面以下散射
+        let mut hit = HitInfo::new(r, r.at(t), &geometric_normal, t, u, v, &*self.material);
+        if let Some(n) = shading_normal {
+            hit.normal = if n.dot(&hit.normal) < 0.0 { -n } else { n };
+        }
+        if !self.data.colors.is_empty() {
+            let [i0, i1, i2] = face.positions;
+            hit.vertex_color = Some(self.data.colors[i0] * b0 + self.data.colors[i1] * b1 + self.data.colors[i2] * b2);
+        }
+        Some(hit)
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        let (v0, v1, v2) = self.vertices();
+        Some(triangle::bounding_box(v0, v1, v2))
+    }
+}
@@ -1,7 +1,34 @@
 extern crate rand ;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f64::consts::PI;
 
-const PI:f64 = 3.1415926535897932385;
+//可设定种子的随机数发生器，所有的随机采样都通过它进行，相同的种子得到相同的随机序列
+pub struct Sampler {
+    rng: StdRng,
+}
+
+impl Sampler {
+    pub fn new(seed: u64) -> Self {
+        Sampler {
+            rng: StdRng::seed_from_u64(seed)
+        }
+    }
+
+    //为第index个像素派生一个独立的随机序列，这样渲染结果与线程数和各行的渲染顺序无关
+    pub fn for_pixel(seed: u64, index: u64) -> Self {
+        Sampler::new(splitmix64(seed ^ splitmix64(index)))
+    }
+}
+
+//SplitMix64哈希，把相近的整数打散成互不相关的种子
+#[inline]
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
 
 #[inline]
 pub fn degrees_to_radians(degrees: f64) ->f64 {
@@ -9,17 +36,19 @@ pub fn degrees_to_radians(degrees: f64) ->f64 {
 }
 
 #[inline]
-pub fn random_f64_range(min: f64, max: f64) ->f64 {
-    let mut rng = rand::thread_rng(); 
-
-    rng.gen_range(min..=max)
+pub fn random_f64_range(min: f64, max: f64, sampler: &mut Sampler) ->f64 {
+    sampler.rng.gen_range(min..=max)
 }
 
 #[inline]
-pub fn random_f64() ->f64 {
-    let mut rng = rand::thread_rng(); 
+pub fn random_f64(sampler: &mut Sampler) ->f64 {
+    sampler.rng.gen()
+}
 
-    rng.gen()
+//[0, n)之间的随机整数
+#[inline]
+pub fn random_usize(n: usize, sampler: &mut Sampler) -> usize {
+    sampler.rng.gen_range(0..n)
 }
 
 #[inline]
@@ -31,4 +60,4 @@ pub fn clamp(x: f64, min: f64, max: f64) -> f64{
     } else {
         x
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,7 @@
-use std::ops::{Add, Sub, Mul, Div, Neg};
+use std::f64::consts::PI;
+use std::ops::{Add, Sub, Mul, Div, Neg, Index};
 
-use crate::utils::random_f64_range;
+use crate::utils::{random_f64, random_f64_range, Sampler};
 
 #[derive(Debug, Copy, Clone)]
 pub struct Vector3 {
@@ -44,13 +45,13 @@ impl Vector3 {
     }
 
     #[inline]
-    pub fn random(min:f64, max:f64) ->Vector3 {
-        return Vector3::new(random_f64_range(min,max), random_f64_range(min,max), random_f64_range(min,max));
+    pub fn random(min:f64, max:f64, sampler: &mut Sampler) ->Vector3 {
+        Vector3::new(random_f64_range(min,max,sampler), random_f64_range(min,max,sampler), random_f64_range(min,max,sampler))
     }
 
-    pub fn random_in_unit_sphere() ->Vector3 {
+    pub fn random_in_unit_sphere(sampler: &mut Sampler) ->Vector3 {
         loop {
-            let p = Vector3::random(-1.0,1.0);
+            let p = Vector3::random(-1.0,1.0,sampler);
             if p.length_squared() >= 1.0 {
                 continue;
             }
@@ -59,8 +60,8 @@ impl Vector3 {
         }
     }
 
-    pub fn random_unit_vector() ->Vector3 {
-        Vector3::random_in_unit_sphere().unit()
+    pub fn random_unit_vector(sampler: &mut Sampler) ->Vector3 {
+        Vector3::random_in_unit_sphere(sampler).unit()
     }
 
     pub fn near_zero(&self) ->bool {
@@ -89,7 +90,7 @@ impl Vector3 {
     //参数v: 入射光线方向向量，n: 法线单位向量，返回反射光线的方向向量
     #[inline]
     pub fn reflect(v: &Vector3, n: &Vector3) ->Vector3 {
-        *v - *n * ((v.dot(n) * 2.0))
+        *v - *n * (v.dot(n) * 2.0)
     }
 
     //折射，遵循光线折射定律。至于为什么这么求折射光线可以利用几何光学自行推导或查阅相关资料
@@ -107,9 +108,19 @@ impl Vector3 {
         r_out_perp + r_out_parallel
     }
 
-    pub fn random_in_unit_disk() -> Vector3 {
+    //单位半球上按cosθ加权分布的方向（局部坐标系，z轴为半球的轴），概率密度为cosθ / π
+    pub fn random_cosine_direction(sampler: &mut Sampler) -> Vector3 {
+        let r1 = random_f64(sampler);
+        let r2 = random_f64(sampler);
+
+        let phi = 2.0 * PI * r1;
+        let r = r2.sqrt();
+        Vector3::new(phi.cos() * r, phi.sin() * r, (1.0 - r2).sqrt())
+    }
+
+    pub fn random_in_unit_disk(sampler: &mut Sampler) -> Vector3 {
         loop {
-            let p = Vector3::new(random_f64_range(-1.0,1.0), random_f64_range(-1.0,1.0), 0.0);
+            let p = Vector3::new(random_f64_range(-1.0,1.0,sampler), random_f64_range(-1.0,1.0,sampler), 0.0);
             if p.length_squared() >= 1.0 {
                 continue;
             }
@@ -150,4 +161,17 @@ impl Neg for Vector3 {
     }
 }
 
+impl Index<usize> for Vector3 {
+    type Output = f64;
+    //按坐标轴取分量，0、1、2分别对应x、y、z
+    fn index(&self, axis: usize) -> &f64 {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vector3 axis index out of range: {}", axis),
+        }
+    }
+}
+
 pub type Point3 = Vector3;
\ No newline at end of file
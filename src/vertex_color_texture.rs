@@ -0,0 +1,25 @@
+use crate::color::Color;
+use crate::hittable::HitInfo;
+use crate::texture::Texture;
+use crate::vector::Point3;
+
+//顶点颜色纹理：取网格在击中点插值得到的顶点颜色（HitInfo.vertex_color），没有顶点颜色的物体为黑色
+#[derive(Debug, Default, Clone, Copy)]
+pub struct VertexColorTexture;
+
+impl VertexColorTexture {
+    pub fn new() -> Self {
+        VertexColorTexture
+    }
+}
+
+impl Texture for VertexColorTexture {
+    //只有表面坐标时拿不到顶点颜色
+    fn value(&self, _u: f64, _v: f64, _p: &Point3) -> Color {
+        Color::black()
+    }
+
+    fn value_at(&self, hit_info: &HitInfo) -> Color {
+        hit_info.vertex_color.unwrap_or_else(Color::black)
+    }
+}
@@ -0,0 +1,70 @@
+use crate::vector::Point3;
+
+//规则的三维标量网格（如烟雾模拟得到的密度场），数据按x变化最快、其次y、最后z的顺序存储
+//网格覆盖单位立方体[0, 1]^3，体素的值位于体素中心
+#[derive(Debug, Clone)]
+pub struct VoxelGrid {
+    resolution: [usize; 3],
+    data: Vec<f32>,
+    max: f64,
+}
+
+impl VoxelGrid {
+    //各方向至少一个体素，数据个数与分辨率一致，值必须是非负的有限数
+    pub fn new(resolution: [usize; 3], data: Vec<f32>) -> Result<Self, String> {
+        let [nx, ny, nz] = resolution;
+        if nx == 0 || ny == 0 || nz == 0 {
+            return Err(format!("invalid grid resolution {}x{}x{}", nx, ny, nz));
+        }
+        let count = nx.checked_mul(ny).and_then(|n| n.checked_mul(nz))
+            .ok_or_else(|| format!("grid resolution {}x{}x{} is too large", nx, ny, nz))?;
+        if data.len() != count {
+            return Err(format!("a {}x{}x{} grid needs {} values, got {}", nx, ny, nz, count, data.len()));
+        }
+        if let Some(index) = data.iter().position(|v| !v.is_finite() || *v < 0.0) {
+            return Err(format!("voxel {} has invalid value {}", index, data[index]));
+        }
+
+        let max = data.iter().fold(0.0f32, |m, &v| m.max(v)) as f64;
+        Ok(VoxelGrid { resolution, data, max })
+    }
+
+    pub fn resolution(&self) -> [usize; 3] {
+        self.resolution
+    }
+
+    //所有体素的最大值，三线性插值的结果不会超过它
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    pub fn voxel(&self, x: usize, y: usize, z: usize) -> f64 {
+        let [nx, ny, _] = self.resolution;
+        self.data[(z * ny + y) * nx + x] as f64
+    }
+
+    //单位立方体内p处的值，对相邻8个体素中心做三线性插值；边界上的半个体素取最近的体素值，立方体之外为0
+    pub fn sample(&self, p: &Point3) -> f64 {
+        if !(0.0..=1.0).contains(&p.x) || !(0.0..=1.0).contains(&p.y) || !(0.0..=1.0).contains(&p.z) {
+            return 0.0;
+        }
+
+        //每个轴上相邻两个体素的下标和插值权重
+        let axis = |x: f64, n: usize| {
+            let g = (x * n as f64 - 0.5).clamp(0.0, (n - 1) as f64);
+            let i0 = (g.floor() as usize).min(n - 1);
+            let i1 = (i0 + 1).min(n - 1);
+            (i0, i1, g - i0 as f64)
+        };
+        let (x0, x1, fx) = axis(p.x, self.resolution[0]);
+        let (y0, y1, fy) = axis(p.y, self.resolution[1]);
+        let (z0, z1, fz) = axis(p.z, self.resolution[2]);
+
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+        let along_x = |y, z| lerp(self.voxel(x0, y, z), self.voxel(x1, y, z), fx);
+        let near = lerp(along_x(y0, z0), along_x(y1, z0), fy);
+        let far = lerp(along_x(y0, z1), along_x(y1, z1), fy);
+
+        lerp(near, far, fz)
+    }
+}
@@ -1,26 +1,99 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::bvh::{BvhNode, BvhSplit};
 use crate::hittable::{HitInfo, Hittable};
+use crate::light::Light;
 use crate::ray::Ray;
+use crate::utils::{random_usize, Sampler};
+use crate::vector::{Point3, Vector3};
 
 pub struct World  {
-    objects: Vec<Box<dyn Hittable>>
+    objects: Vec<Box<dyn Hittable>>,
+    lights: Vec<Arc<dyn Hittable>>, //做直接光照采样的光源，同时也在objects中
+    delta_lights: Vec<Box<dyn Light>>, //没有几何形状的点光源、聚光灯和平行光
 }
 
 impl World {
     pub fn new() ->Self {
         World {
-            objects: Vec::new()
+            objects: Vec::new(),
+            lights: Vec::new(),
+            delta_lights: Vec::new(),
         }
     }
     pub fn add(&mut self, object: Box<dyn Hittable>) {
         self.objects.push(object);
     }
 
-    pub fn hit(&self, r: &Ray, t_min: f64, t_max: f64) -> Option<HitInfo> {
+    //加入一个发光物体并把它登记为光源，渲染时在漫反射表面上直接对它采样
+    //光源必须实现Hittable的pdf_value和random，目前支持静止的球体、矩形和三角形
+    pub fn add_light(&mut self, light: Arc<dyn Hittable>) {
+        self.lights.push(light.clone());
+        self.objects.push(Box::new(LightObject { object: light }));
+    }
+
+    pub fn lights(&self) -> &[Arc<dyn Hittable>] {
+        &self.lights
+    }
+
+    //加入没有几何形状的光源，它们不参与求交，只在散射点上通过阴影射线照亮场景
+    pub fn add_delta_light(&mut self, light: Box<dyn Light>) {
+        self.delta_lights.push(light);
+    }
+
+    pub fn delta_lights(&self) -> &[Box<dyn Light>] {
+        &self.delta_lights
+    }
+
+    //从所有光源中等概率地选一个，返回从origin指向它上面随机一点的方向；没有光源时返回None
+    pub fn sample_light(&self, origin: &Point3, sampler: &mut Sampler) -> Option<Vector3> {
+        if self.lights.is_empty() {
+            return None;
+        }
+
+        let light = &self.lights[random_usize(self.lights.len(), sampler)];
+        Some(light.random(origin, sampler))
+    }
+
+    //sample_light采样到direction方向的概率密度（立体角），是各光源概率密度的平均值
+    pub fn light_pdf(&self, origin: &Point3, direction: &Vector3, sampler: &mut Sampler) -> f64 {
+        if self.lights.is_empty() {
+            return 0.0;
+        }
+
+        let sum: f64 = self.lights.iter().map(|light| light.pdf_value(origin, direction, sampler)).sum();
+        sum / self.lights.len() as f64
+    }
+
+    //把所有物体组织成一棵BVH树，之后的求交从逐个遍历变为按树查找
+    //没有包围盒的物体无法放入树中，仍然逐个求交
+    pub fn build_bvh(&mut self, time0: f64, time1: f64, split: BvhSplit) {
+        let (bounded, mut unbounded): (Vec<_>, Vec<_>) = self.objects
+            .drain(..)
+            .partition(|object| object.bounding_box(time0, time1).is_some());
+
+        if !bounded.is_empty() {
+            unbounded.push(Box::new(BvhNode::new(bounded, time0, time1, split)));
+        }
+
+        self.objects = unbounded;
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        World::new()
+    }
+}
+
+impl Hittable for World {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
         let mut hit_info: Option<HitInfo> = None;
         let mut closest_so_far = t_max;
     
         for object in &self.objects {
-            if let Some(tmp_info) = object.hit(r, t_min, closest_so_far) {
+            if let Some(tmp_info) = object.hit(r, t_min, closest_so_far, sampler) {
                 closest_so_far = tmp_info.t;
                 hit_info = Some(tmp_info);
             }
@@ -28,5 +101,72 @@ impl World {
     
         hit_info
     }
+
+    fn surface_hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        let mut hit_info: Option<HitInfo> = None;
+        let mut closest_so_far = t_max;
+
+        for object in &self.objects {
+            if let Some(tmp_info) = object.surface_hit(r, t_min, closest_so_far, sampler) {
+                closest_so_far = tmp_info.t;
+                hit_info = Some(tmp_info);
+            }
+        }
+
+        hit_info
+    }
+
+    fn transmittance(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> f64 {
+        let mut transmittance = 1.0;
+
+        for object in &self.objects {
+            transmittance *= object.transmittance(r, t_min, t_max, sampler);
+            if transmittance <= 0.0 {
+                break;
+            }
+        }
+
+        transmittance
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        let mut result: Option<Aabb> = None;
+
+        for object in &self.objects {
+            let bbox = object.bounding_box(time0, time1)?;
+            result = Some(match result {
+                Some(b) => b.surrounding(&bbox),
+                None => bbox,
+            });
+        }
+
+        result
+    }
+}
+
+//光源在objects中的代表：击中时标记HitInfo.is_light，让积分器知道这份发光已经由直接光照采样计入
+struct LightObject {
+    object: Arc<dyn Hittable>,
 }
 
+impl Hittable for LightObject {
+    fn hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        let mut hit_info = self.object.hit(r, t_min, t_max, sampler)?;
+        hit_info.is_light = true;
+        Some(hit_info)
+    }
+
+    fn surface_hit(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> Option<HitInfo<'_>> {
+        let mut hit_info = self.object.surface_hit(r, t_min, t_max, sampler)?;
+        hit_info.is_light = true;
+        Some(hit_info)
+    }
+
+    fn transmittance(&self, r: &Ray, t_min: f64, t_max: f64, sampler: &mut Sampler) -> f64 {
+        self.object.transmittance(r, t_min, t_max, sampler)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        self.object.bounding_box(time0, time1)
+    }
+}
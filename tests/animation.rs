@@ -0,0 +1,148 @@
+mod common;
+
+use std::sync::Arc;
+
+use render::animation::{AnimatedTransform, Keyframe};
+use render::cuboid::Cuboid;
+use render::hittable::Hittable;
+use render::instance::AnimatedInstance;
+use render::matrix::Transform;
+use render::quaternion::Quaternion;
+use render::ray::Ray;
+use render::sphere::Sphere;
+use render::utils::Sampler;
+use render::vector::{Point3, Vector3};
+
+use common::{gray, near};
+
+fn key(time: f64, translation: Vector3, degrees_y: f64) -> Keyframe {
+    let rotation = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), degrees_y);
+    Keyframe::new(time, translation, rotation, Vector3::new(1.0, 1.0, 1.0))
+}
+
+#[test]
+fn quaternion_matches_matrix_rotation() {
+    let axis = Vector3::new(1.0, 2.0, -0.5);
+    let q = Quaternion::from_axis_angle(&axis, 73.0);
+    let p = Point3::new(0.3, -1.2, 2.0);
+    assert!(near(&q.to_transform().point(&p), &Transform::rotate(&axis, 73.0).point(&p)));
+
+    //欧拉角依次绕x、y、z轴旋转
+    let euler = Quaternion::from_euler(&Vector3::new(30.0, 45.0, 60.0));
+    let expected = Transform::rotate_x(30.0).then(&Transform::rotate_y(45.0)).then(&Transform::rotate_z(60.0));
+    assert!(near(&euler.to_transform().point(&p), &expected.point(&p)));
+}
+
+#[test]
+fn slerp_rotates_at_constant_speed_along_shortest_path() {
+    let a = Quaternion::identity();
+    let b = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), 90.0);
+
+    for &s in &[0.0, 0.25, 0.5, 1.0] {
+        let q = a.slerp(&b, s);
+        let expected = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), 90.0 * s);
+        assert!((q.angle_to(&expected)).abs() < 1e-6);
+    }
+
+    //-b与b是同一个旋转，插值仍然走90度的短路径
+    let neg_b = Quaternion::new(-b.w, -b.x, -b.y, -b.z);
+    let q = a.slerp(&neg_b, 0.5);
+    let expected = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), 45.0);
+    assert!(q.angle_to(&expected) < 1e-6);
+}
+
+#[test]
+fn animated_transform_interpolates_between_keyframes() {
+    //关键帧不需要按时间顺序给出
+    let animation = AnimatedTransform::new(vec![
+        key(1.0, Vector3::new(10.0, 0.0, 0.0), 90.0),
+        key(0.0, Vector3::new(0.0, 0.0, 0.0), 0.0),
+    ]).unwrap();
+
+    let p = Point3::new(1.0, 0.0, 0.0);
+    assert!(near(&animation.at(0.0).point(&p), &Point3::new(1.0, 0.0, 0.0)));
+    assert!(near(&animation.at(1.0).point(&p), &Point3::new(10.0, 0.0, -1.0)));
+
+    let half = 0.5f64.sqrt();
+    assert!(near(&animation.at(0.5).point(&p), &Point3::new(5.0 + half, 0.0, -half)));
+
+    //关键帧范围之外保持不动
+    assert!(near(&animation.at(-3.0).point(&p), &animation.at(0.0).point(&p)));
+    assert!(near(&animation.at(7.0).point(&p), &animation.at(1.0).point(&p)));
+
+    //缩放线性插值
+    let animation = AnimatedTransform::new(vec![
+        Keyframe::new(0.0, Vector3::new(0.0, 0.0, 0.0), Quaternion::identity(), Vector3::new(1.0, 1.0, 1.0)),
+        Keyframe::new(2.0, Vector3::new(0.0, 0.0, 0.0), Quaternion::identity(), Vector3::new(3.0, 1.0, 1.0)),
+    ]).unwrap();
+    assert!(near(&animation.at(1.0).point(&Point3::new(1.0, 1.0, 1.0)), &Point3::new(2.0, 1.0, 1.0)));
+}
+
+#[test]
+fn rejects_invalid_keyframes() {
+    assert!(AnimatedTransform::new(vec![]).is_err());
+
+    let duplicate = AnimatedTransform::new(vec![key(0.5, Vector3::new(0.0, 0.0, 0.0), 0.0), key(0.5, Vector3::new(1.0, 0.0, 0.0), 0.0)]);
+    assert!(duplicate.unwrap_err().contains("more than one keyframe"));
+
+    let negative = Keyframe::new(0.0, Vector3::new(0.0, 0.0, 0.0), Quaternion::identity(), Vector3::new(1.0, -1.0, 1.0));
+    assert!(AnimatedTransform::new(vec![negative]).unwrap_err().contains("positive"));
+}
+
+#[test]
+fn animated_instance_moves_with_ray_time() {
+    let material = gray();
+    let sphere: Arc<dyn Hittable> = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, material));
+    let animation = AnimatedTransform::new(vec![
+        key(0.0, Vector3::new(0.0, 0.0, 0.0), 0.0),
+        key(1.0, Vector3::new(4.0, 0.0, 0.0), 0.0),
+    ]).unwrap();
+    let instance = AnimatedInstance::new(sphere, animation);
+
+    //从z方向看x=4处，只有快门结束时球才在那里
+    let ray_at = |time: f64| Ray::new(Point3::new(4.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0), time);
+    assert!(instance.hit(&ray_at(0.0), 0.001, f64::INFINITY, &mut Sampler::new(0)).is_none());
+    let hit = instance.hit(&ray_at(1.0), 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!((hit.t - 4.0).abs() < 1e-9);
+    assert!(near(&hit.normal, &Vector3::new(0.0, 0.0, 1.0)));
+
+    let bbox = instance.bounding_box(0.0, 1.0).unwrap();
+    assert!(near(&bbox.minimum, &Point3::new(-1.0, -1.0, -1.0)));
+    assert!(near(&bbox.maximum, &Point3::new(5.0, 1.0, 1.0)));
+}
+
+#[test]
+fn bounding_box_covers_rotation_over_shutter() {
+    let material = gray();
+    let cuboid: Arc<dyn Hittable> = Arc::new(Cuboid::new(&Point3::new(1.0, -0.5, -0.5), &Point3::new(3.0, 0.5, 0.5), material));
+
+    //一个长条绕y轴转过170度再回来，中间的位置远远超出两端时刻的包围盒
+    let animation = AnimatedTransform::new(vec![
+        key(0.0, Vector3::new(0.0, 0.0, 0.0), 0.0),
+        key(0.5, Vector3::new(0.0, 0.0, 0.0), 170.0),
+        key(1.0, Vector3::new(0.0, 0.0, 0.0), 0.0),
+    ]).unwrap();
+    let instance = AnimatedInstance::new(cuboid.clone(), animation.clone());
+    let bbox = instance.bounding_box(0.0, 1.0).unwrap();
+
+    let object_box = cuboid.bounding_box(0.0, 1.0).unwrap();
+    for i in 0..=1000 {
+        let transform = animation.at(i as f64 / 1000.0);
+        for c in 0..8 {
+            let corner = Point3::new(
+                if c & 1 == 0 { object_box.minimum.x } else { object_box.maximum.x },
+                if c & 2 == 0 { object_box.minimum.y } else { object_box.maximum.y },
+                if c & 4 == 0 { object_box.minimum.z } else { object_box.maximum.z },
+            );
+            let p = transform.point(&corner);
+            for axis in 0..3 {
+                assert!(p[axis] >= bbox.minimum[axis] && p[axis] <= bbox.maximum[axis], "{:?} outside {:?}", p, bbox);
+            }
+        }
+    }
+
+    //只覆盖快门时间内的运动
+    let early = instance.bounding_box(0.0, 0.05).unwrap();
+    assert!(early.minimum.x > 0.0);
+    assert!(bbox.minimum.x < -2.9);
+}
@@ -0,0 +1,201 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use render::aarect::XzRect;
+use render::background::{Background, GradientBackground, SolidBackground};
+use render::camera::{Camera, MisHeuristic, RenderSettings};
+use render::color::Color;
+use render::environment_map::EnvironmentMap;
+use render::lambertian::Lambertian;
+use render::light::Light;
+use render::output::Image;
+use render::preetham_sky::PreethamSky;
+use render::ray::Ray;
+use render::utils::Sampler;
+use render::vector::{Point3, Vector3};
+use render::world::World;
+
+//width x height的环境贴图，第row行第column列的像素为bright，其余为dim
+fn map_with_spot(width: usize, height: usize, (column, row): (usize, usize), bright: f64, dim: f64) -> Image {
+    let mut pixels = vec![Color::new(dim, dim, dim); width * height];
+    pixels[row * width + column] = Color::new(bright, bright, bright);
+    Image { width, height, pixels }
+}
+
+fn look(background: &dyn Background, direction: Vector3) -> Color {
+    background.color(&Ray::new(Point3::new(0.0, 0.0, 0.0), direction, 0.0))
+}
+
+#[test]
+fn solid_and_gradient_backgrounds() {
+    let solid = SolidBackground::new(&Color::new(0.1, 0.2, 0.3));
+    assert_eq!(look(&solid, Vector3::new(0.0, 1.0, 0.0)).f64_b(), 0.3);
+    assert!(solid.sample(&mut Sampler::new(0)).is_none());
+
+    let up = look(&GradientBackground, Vector3::new(0.0, 1.0, 0.0));
+    let down = look(&GradientBackground, Vector3::new(0.0, -1.0, 0.0));
+    assert_eq!((up.f64_r(), up.f64_g(), up.f64_b()), (0.5, 0.7, 1.0));
+    assert_eq!((down.f64_r(), down.f64_g(), down.f64_b()), (1.0, 1.0, 1.0));
+}
+
+#[test]
+fn environment_map_orientation_rotation_and_intensity() {
+    //中间一列朝向-z，第一行是正上方
+    let image = map_with_spot(4, 2, (2, 0), 8.0, 1.0);
+    let map = EnvironmentMap::new(image.clone(), 0.5, 0.0);
+    assert_eq!(look(&map, Vector3::new(0.1, 0.5, -1.0)).f64_r(), 4.0);
+    assert_eq!(look(&map, Vector3::new(0.1, -0.5, -1.0)).f64_r(), 0.5);
+    assert_eq!(look(&map, Vector3::new(0.1, 0.5, 1.0)).f64_r(), 0.5);
+
+    //绕y轴旋转90度后，原来朝向-z的像素朝向-x
+    let rotated = EnvironmentMap::new(image, 1.0, 90.0);
+    assert_eq!(look(&rotated, Vector3::new(-1.0, 0.5, -0.1)).f64_r(), 8.0);
+    assert_eq!(look(&rotated, Vector3::new(0.1, 0.5, -1.0)).f64_r(), 1.0);
+}
+
+#[test]
+fn environment_sampling_follows_luminance() {
+    for rotation in [0.0, 37.0] {
+        let mut sampler = Sampler::new(2);
+
+        //pdf在整个球面上的积分为1
+        let map = EnvironmentMap::new(map_with_spot(32, 16, (9, 4), 20.0, 1.0), 1.0, rotation);
+        let count = 400000;
+        let integral: f64 = (0..count)
+            .map(|_| map.pdf(&Vector3::random_unit_vector(&mut sampler)))
+            .sum::<f64>() * 4.0 * PI / count as f64;
+        assert!((integral - 1.0).abs() < 0.02, "pdf integrates to {}", integral);
+
+        let map = EnvironmentMap::new(map_with_spot(32, 16, (9, 4), 5000.0, 1.0), 1.0, rotation);
+
+        //大多数样本落在最亮的像素上
+        let samples = 10000;
+        let mut bright = 0;
+        for _ in 0..samples {
+            let direction = map.sample(&mut sampler).unwrap();
+            assert!(map.pdf(&direction) > 0.0);
+            if look(&map, direction).f64_r() > 1.0 {
+                bright += 1;
+            }
+        }
+        assert!(bright as f64 / samples as f64 > 0.9, "{}", bright);
+    }
+
+    let black = EnvironmentMap::new(map_with_spot(4, 2, (0, 0), 0.0, 0.0), 1.0, 0.0);
+    assert!(black.sample(&mut Sampler::new(0)).is_none());
+    assert_eq!(black.pdf(&Vector3::new(0.0, 1.0, 0.0)), 0.0);
+}
+
+//不做重要性采样的同一张环境贴图，只能靠BSDF采样照到
+struct Unsampled(EnvironmentMap);
+
+impl Background for Unsampled {
+    fn color(&self, ray: &Ray) -> Color {
+        self.0.color(ray)
+    }
+}
+
+//漫反射地面上一点的辐亮度，返回所有像素的均值和与expected的平均误差
+fn render_floor(background: Arc<dyn Background>, lights: Vec<Box<dyn Light>>, mis: MisHeuristic, expected: f64) -> (f64, f64) {
+    let mut world = World::new();
+    for light in lights {
+        world.add_delta_light(light);
+    }
+    world.add(Box::new(XzRect::new(-50.0, 50.0, -50.0, 50.0, 0.0, Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5))))));
+
+    let mut camera = Camera::new(Point3::new(3.0, 1.0, 0.0), Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0),
+        1.0, 1.0, 0.0, 1.0, 0.0, 1.0);
+    camera.set_background(background);
+    let settings = RenderSettings { image_width: 8, image_height: 8, samples_per_pixel: 32, max_depth: 2, threads: 1, seed: 4, mis };
+
+    let image = camera.take_photo(&world, &settings);
+    let values: Vec<f64> = image.iter().map(|c| c.f64_g()).collect();
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let error = values.iter().map(|v| (v - expected).abs()).sum::<f64>() / values.len() as f64;
+    (mean, error)
+}
+
+#[test]
+fn environment_sampling_removes_fireflies_from_small_suns() {
+    //16x8的贴图中只有一个很亮的像素，它覆盖θ在[π/4, 3π/8]之间、宽2π/16的区域，
+    //地面的照度为 L · Δφ · (sin²θ1 - sin²θ0) / 2，辐亮度为albedo / π乘以照度
+    let image = map_with_spot(16, 8, (5, 2), 1000.0, 0.0);
+    let (theta0, theta1) = (PI / 4.0, 3.0 * PI / 8.0);
+    let irradiance = 1000.0 * (2.0 * PI / 16.0) * (theta1.sin().powi(2) - theta0.sin().powi(2)) / 2.0;
+    let expected = 0.5 / PI * irradiance;
+
+    for mis in [MisHeuristic::Balance, MisHeuristic::Power] {
+        let (mean, sampled_error) = render_floor(Arc::new(EnvironmentMap::new(image.clone(), 1.0, 0.0)), vec![], mis, expected);
+        assert!((mean - expected).abs() < 0.02 * expected, "{:?}: {} vs {}", mis, mean, expected);
+
+        let (_, unsampled_error) = render_floor(Arc::new(Unsampled(EnvironmentMap::new(image.clone(), 1.0, 0.0))), vec![], mis, expected);
+        assert!(unsampled_error > 10.0 * sampled_error, "{} vs {}", unsampled_error, sampled_error);
+    }
+}
+
+#[test]
+fn uniform_environment_lights_a_floor_by_its_albedo() {
+    //各个方向亮度都为1的环境，上半球的照度为π，地面的辐亮度等于albedo
+    let image = map_with_spot(8, 4, (0, 0), 1.0, 1.0);
+    let (mean, error) = render_floor(Arc::new(EnvironmentMap::new(image, 1.0, 0.0)), vec![], MisHeuristic::Power, 0.5);
+    assert!((mean - 0.5).abs() < 0.01, "{}", mean);
+    assert!(error < 0.05, "{}", error);
+}
+
+#[test]
+fn preetham_sky_looks_like_a_clear_day() {
+    let sun = Vector3::new(0.0, 1.0, -1.0);
+    let sky = PreethamSky::new(&sun, 3.0, &Color::new(0.3, 0.3, 0.3), 1.0);
+
+    //天顶是蓝色的，太阳附近比背对太阳的一侧亮，背对太阳的地平线比天顶亮而且更白
+    let zenith = look(&sky, Vector3::new(0.0, 1.0, 0.0));
+    assert!(zenith.f64_b() > zenith.f64_g() && zenith.f64_g() > zenith.f64_r(), "{:?}", zenith);
+    let near_sun = look(&sky, Vector3::new(0.0, 1.0, -1.2));
+    let away = look(&sky, Vector3::new(0.0, 1.0, 1.2));
+    assert!(near_sun.luminance() > 2.0 * away.luminance(), "{:?} vs {:?}", near_sun, away);
+    let horizon = look(&sky, Vector3::new(0.0, 0.05, 1.0));
+    assert!(horizon.luminance() > zenith.luminance(), "{:?} vs {:?}", horizon, zenith);
+    assert!(horizon.f64_r() / horizon.f64_b() > zenith.f64_r() / zenith.f64_b());
+
+    //地平线以下是亮度均匀的地面
+    let ground = look(&sky, Vector3::new(0.3, -1.0, 0.0));
+    assert!(ground.luminance() > 0.0);
+    assert_eq!(look(&sky, Vector3::new(-0.5, -0.2, 0.7)).f64_b(), ground.f64_b());
+
+    //intensity同时缩放天空和太阳
+    let bright = PreethamSky::new(&sun, 3.0, &Color::new(0.3, 0.3, 0.3), 2.0);
+    assert!((look(&bright, Vector3::new(0.0, 1.0, 0.0)).f64_b() - 2.0 * zenith.f64_b()).abs() < 1e-12);
+    assert!((bright.sun_irradiance().f64_g() - 2.0 * sky.sun_irradiance().f64_g()).abs() < 1e-12);
+}
+
+#[test]
+fn sun_dims_and_reddens_with_haze_and_low_elevation() {
+    let ground = Color::new(0.3, 0.3, 0.3);
+    let redness = |c: Color| c.f64_r() / c.f64_b();
+
+    let high = PreethamSky::new(&Vector3::new(0.0, 3.0, -1.0), 2.0, &ground, 1.0).sun_irradiance();
+    let low = PreethamSky::new(&Vector3::new(0.0, 0.1, -1.0), 2.0, &ground, 1.0).sun_irradiance();
+    let hazy = PreethamSky::new(&Vector3::new(0.0, 3.0, -1.0), 8.0, &ground, 1.0).sun_irradiance();
+    assert!(low.luminance() < 0.5 * high.luminance(), "{:?} vs {:?}", low, high);
+    assert!(redness(low) > 1.5 * redness(high), "{:?} vs {:?}", low, high);
+    assert!(hazy.luminance() < high.luminance() && redness(hazy) > redness(high));
+
+    //配套的太阳光沿指向太阳的反方向传播，照度与sun_irradiance一致
+    let sky = PreethamSky::new(&Vector3::new(1.0, 2.0, 0.0), 4.0, &ground, 1.0);
+    let sun = sky.sun();
+    assert!((sun.direction() + Vector3::new(1.0, 2.0, 0.0).unit()).length() < 1e-12);
+    let sample = sun.sample(&Point3::new(0.0, 0.0, 0.0)).unwrap();
+    assert!((sample.irradiance.f64_r() - sky.sun_irradiance().f64_r()).abs() < 1e-12);
+    assert!((sample.irradiance.f64_b() - sky.sun_irradiance().f64_b()).abs() < 1e-12);
+}
+
+#[test]
+fn sky_ground_matches_a_floor_lit_by_sky_and_sun() {
+    //天空背景中地平线以下的地面与真正被天空和太阳照亮的同样反照率的地面亮度一致
+    let sky = PreethamSky::new(&Vector3::new(1.0, 1.5, -0.5), 3.0, &Color::new(0.5, 0.5, 0.5), 1.0);
+    let expected = look(&sky, Vector3::new(0.0, -1.0, 0.0)).f64_g();
+    let sun: Box<dyn Light> = Box::new(sky.sun());
+
+    let (mean, _) = render_floor(Arc::new(sky), vec![sun], MisHeuristic::Power, expected);
+    assert!((mean - expected).abs() < 0.02 * expected, "{} vs {}", mean, expected);
+}
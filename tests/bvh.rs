@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use render::bvh::{BvhNode, BvhSplit};
+use render::color::Color;
+use render::hittable::Hittable;
+use render::lambertian::Lambertian;
+use render::ray::Ray;
+use render::sphere::Sphere;
+use render::utils::{random_f64_range, Sampler};
+use render::vector::{Point3, Vector3};
+use render::world::World;
+
+fn random_world(sampler: &mut Sampler, count: usize) -> World {
+    let material = Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)));
+    let mut world = World::new();
+
+    for i in 0..count {
+        let center = Vector3::random(-10.0, 10.0, sampler);
+        let mut sphere = Sphere::new(center, random_f64_range(0.1, 1.0, sampler), material.clone());
+        if i % 3 == 0 {
+            sphere.move_to(&(center + Vector3::new(0.0, 1.0, 0.0)), 0.0, 1.0);
+        }
+        world.add(Box::new(sphere));
+    }
+
+    world
+}
+
+#[test]
+fn bvh_finds_the_same_closest_hits_as_linear_scan() {
+    for split in [BvhSplit::Median, BvhSplit::Sah] {
+        let linear = random_world(&mut Sampler::new(11), 300);
+        let mut bvh = random_world(&mut Sampler::new(11), 300);
+        bvh.build_bvh(0.0, 1.0, split);
+
+        let mut sampler = Sampler::new(12);
+        for _ in 0..2000 {
+            let origin = Vector3::random(-15.0, 15.0, &mut sampler);
+            let ray = Ray::new(origin, Vector3::random_unit_vector(&mut sampler), random_f64_range(0.0, 1.0, &mut sampler));
+
+            let expected = linear.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).map(|info| info.t);
+            let actual = bvh.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).map(|info| info.t);
+            assert_eq!(expected, actual, "{:?}", split);
+        }
+    }
+}
+
+#[test]
+fn bounding_box_covers_moving_sphere_over_shutter() {
+    let material = Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)));
+    let mut sphere = Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, material);
+    sphere.move_to(&Point3::new(0.0, 4.0, 0.0), 0.0, 1.0);
+
+    let bbox = sphere.bounding_box(0.0, 1.0).unwrap();
+    assert_eq!((bbox.minimum.y, bbox.maximum.y), (-1.0, 5.0));
+
+    let node = BvhNode::new(vec![Box::new(sphere)], 0.0, 1.0, BvhSplit::Median);
+    assert_eq!(node.bounding_box(0.0, 1.0).unwrap().maximum.y, 5.0);
+}
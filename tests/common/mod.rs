@@ -0,0 +1,17 @@
+// 几个集成测试共用的辅助函数；每个测试文件只用到其中一部分
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use render::color::Color;
+use render::lambertian::Lambertian;
+use render::material::Material;
+use render::vector::Vector3;
+
+pub fn gray() -> Arc<dyn Material> {
+    Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)))
+}
+
+pub fn near(a: &Vector3, b: &Vector3) -> bool {
+    (*a - *b).length() < 1e-9
+}
@@ -0,0 +1,325 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use render::hittable::Hittable;
+use render::import::{load_obj, parse_ply, parse_stl, ImportError};
+use render::ray::Ray;
+use render::utils::Sampler;
+use render::vector::{Point3, Vector3};
+
+use common::gray;
+
+//在临时目录下为每个测试建一个单独的目录，写入files并返回目录路径
+fn write_files(test: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("render-import-{}-{}", test, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for (name, content) in files {
+        fs::write(dir.join(name), content).unwrap();
+    }
+    dir
+}
+
+fn parse_error(result: Result<Vec<render::import::ObjMesh>, ImportError>) -> (PathBuf, usize, String) {
+    match result {
+        Err(ImportError::Parse { path, line, message }) => (path, line, message),
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("file should not load"),
+    }
+}
+
+#[test]
+fn obj_polygons_are_triangulated_and_split_by_group_and_material() {
+    let dir = write_files("groups", &[
+        ("quad.obj", "
+            mtllib quad.mtl
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 1 0
+            vt 0 0
+            vt 1 0
+            vt 1 1
+            vt 0 1
+            vn 0 0 1
+            g front
+            usemtl red
+            f 1/1/1 2/2/1 3/3/1 4/4/1
+            g back
+            usemtl lamp
+            # 负数下标从最后一个顶点往回数
+            f -1 -2 -3
+        "),
+        ("quad.mtl", "
+            newmtl red
+            Kd 0.8 0.1 0.1
+            newmtl lamp
+            Ke 4 4 4
+        "),
+    ]);
+
+    let meshes = load_obj(&dir.join("quad.obj"), gray()).unwrap();
+    assert_eq!(meshes.len(), 2);
+    assert_eq!((meshes[0].group.as_str(), meshes[0].material.as_str()), ("front", "red"));
+    assert_eq!((meshes[1].group.as_str(), meshes[1].material.as_str()), ("back", "lamp"));
+
+    //四边形剖分为两个三角形，只保留用到的顶点
+    let front = meshes[0].mesh.data();
+    assert_eq!(front.faces.len(), 2);
+    assert_eq!(front.positions.len(), 4);
+
+    let ray = Ray::new(Point3::new(0.75, 0.5, 1.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+    let hit = meshes[0].mesh.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!((hit.u - 0.75).abs() < 1e-9 && (hit.v - 0.5).abs() < 1e-9);
+
+    //Ke不为0的材质是发光的
+    let ray = Ray::new(Point3::new(0.6, 0.8, -1.0), Vector3::new(0.0, 0.0, 1.0), 0.0);
+    let hit = meshes[1].mesh.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!(hit.material.emitted(&ray, &hit).f64_r() > 3.9);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn mtl_maps_specular_and_transparent_materials() {
+    let dir = write_files("mtl", &[
+        ("m.obj", "
+            mtllib m.mtl
+            v 0 0 0
+            v 1 0 0
+            v 0 1 0
+            usemtl glass
+            f 1 2 3
+            usemtl mirror
+            f 1 2 3
+            usemtl unknown
+            f 1 2 3
+        "),
+        ("m.mtl", "
+            newmtl glass
+            Ni 1.33
+            d 0.2
+            newmtl mirror
+            Kd 0.1 0.1 0.1
+            Ks 0.9 0.9 0.9
+            Ns 1000
+        "),
+    ]);
+
+    let meshes = load_obj(&dir.join("m.obj"), gray()).unwrap();
+    assert_eq!(meshes.len(), 3);
+
+    let ray = Ray::new(Point3::new(0.2, 0.2, 1.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+    let mut sampler = render::utils::Sampler::new(0);
+
+    //镜面材质几乎按镜面反射方向反射
+    let hit = meshes[1].mesh.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    let scatter = hit.material.scatter(&ray, &hit, &mut sampler).unwrap();
+    assert!(scatter.scattered.dir.unit().z > 0.9);
+
+    //玻璃的衰减为1，光线可能穿过表面
+    let hit = meshes[0].mesh.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    let through = (0..50)
+        .filter_map(|_| hit.material.scatter(&ray, &hit, &mut sampler))
+        .any(|s| s.scattered.dir.z < 0.0);
+    assert!(through);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn obj_errors_report_file_and_line() {
+    let dir = write_files("errors", &[
+        ("bad_index.obj", "v 0 0 0\nv 1 0 0\nv 0 1 0\n\nf 1 2 4\n"),
+        ("bad_number.obj", "v 0 0 0\nv 1 x 0\n"),
+        ("bad_mtl.obj", "mtllib bad.mtl\n"),
+        ("bad.mtl", "newmtl a\nKd 1 1\n"),
+    ]);
+
+    let (path, line, message) = parse_error(load_obj(&dir.join("bad_index.obj"), gray()));
+    assert!(path.ends_with("bad_index.obj"));
+    assert_eq!(line, 5);
+    assert!(message.contains("vertex index 4 out of range"), "{}", message);
+
+    let (_, line, message) = parse_error(load_obj(&dir.join("bad_number.obj"), gray()));
+    assert_eq!(line, 2);
+    assert!(message.contains("expected a number, got `x`"), "{}", message);
+
+    //MTL文件中的错误报告MTL文件的路径和行号
+    let (path, line, _) = parse_error(load_obj(&dir.join("bad_mtl.obj"), gray()));
+    assert!(path.ends_with("bad.mtl"));
+    assert_eq!(line, 2);
+
+    let error = load_obj(&dir.join("missing.obj"), gray()).err().unwrap();
+    assert!(matches!(error, ImportError::Io { .. }));
+    assert!(error.to_string().contains("missing.obj"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+//[0,1]x[0,1]的正方形，两个三角形，z = 0
+const PLY_HEADER: &str = "element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 1
+property list uchar int vertex_indices
+end_header
+";
+
+const SQUARE: [([f32; 3], [u8; 3]); 4] = [
+    ([0.0, 0.0, 0.0], [255, 0, 0]),
+    ([1.0, 0.0, 0.0], [255, 0, 0]),
+    ([1.0, 1.0, 0.0], [0, 0, 255]),
+    ([0.0, 1.0, 0.0], [0, 0, 255]),
+];
+
+fn binary_ply(big_endian: bool) -> Vec<u8> {
+    let format = if big_endian { "binary_big_endian" } else { "binary_little_endian" };
+    let mut data = format!("ply\nformat {} 1.0\n{}", format, PLY_HEADER).into_bytes();
+    let f32_bytes = |x: f32| if big_endian { x.to_be_bytes() } else { x.to_le_bytes() };
+    let i32_bytes = |x: i32| if big_endian { x.to_be_bytes() } else { x.to_le_bytes() };
+
+    for (position, color) in SQUARE {
+        for x in position {
+            data.extend(f32_bytes(x));
+        }
+        data.extend(color);
+    }
+    data.push(4);
+    for i in 0..4 {
+        data.extend(i32_bytes(i));
+    }
+    data
+}
+
+fn ascii_ply() -> Vec<u8> {
+    let mut text = format!("ply\nformat ascii 1.0\ncomment test square\n{}", PLY_HEADER);
+    for (p, c) in SQUARE {
+        text += &format!("{} {} {} {} {} {}\n", p[0], p[1], p[2], c[0], c[1], c[2]);
+    }
+    text += "4 0 1 2 3\n";
+    text.into_bytes()
+}
+
+#[test]
+fn ply_encodings_load_the_same_vertex_colored_mesh() {
+    for data in [ascii_ply(), binary_ply(false), binary_ply(true)] {
+        let mesh = parse_ply(&data, Path::new("square.ply"), gray()).unwrap();
+        assert_eq!(mesh.data().faces.len(), 2);
+        assert_eq!(mesh.data().positions.len(), 4);
+
+        //顶点颜色插值：下边红色，上边蓝色
+        let mut sampler = render::utils::Sampler::new(0);
+        for (y, red) in [(0.01, true), (0.99, false)] {
+            let ray = Ray::new(Point3::new(0.5, y, 1.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+            let hit = mesh.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+            let albedo = hit.material.scatter(&ray, &hit, &mut sampler).unwrap().attenuation;
+            assert_eq!(albedo.f64_r() > 0.9, red);
+            assert_eq!(albedo.f64_b() > 0.9, !red);
+        }
+    }
+}
+
+#[test]
+fn ply_vertex_colors_keep_uvs_and_face_edges() {
+    //带纹理坐标的顶点颜色网格：纹理坐标保持原样，颜色在两个三角形的公共边上也不会取到别的面
+    let mut text = "ply\nformat ascii 1.0\nelement vertex 4\nproperty float x\nproperty float y\nproperty float z\n\
+        property float u\nproperty float v\nproperty uchar red\nproperty uchar green\nproperty uchar blue\n\
+        element face 2\nproperty list uchar int vertex_indices\nend_header\n".to_string();
+    for (p, c) in SQUARE {
+        text += &format!("{} {} {} {} {} {} {} {}\n", p[0], p[1], p[2], p[0] * 0.5, p[1] * 0.5, c[0], c[1], c[2]);
+    }
+    text += "3 0 1 2\n3 0 2 3\n";
+    let mesh = parse_ply(text.as_bytes(), Path::new("square.ply"), gray()).unwrap();
+
+    let mut sampler = Sampler::new(0);
+    for i in 0..=100 {
+        let x = i as f64 / 100.0;
+        let ray = Ray::new(Point3::new(x, x, 1.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+        let Some(hit) = mesh.hit(&ray, 0.001, f64::INFINITY, &mut sampler) else {
+            continue;
+        };
+        assert!((hit.u - 0.5 * x).abs() < 1e-6 && (hit.v - 0.5 * x).abs() < 1e-6);
+
+        let albedo = hit.material.scatter(&ray, &hit, &mut sampler).unwrap().attenuation;
+        assert!((albedo.f64_r() - (1.0 - x)).abs() < 1e-6, "{} at {}", albedo.f64_r(), x);
+        assert!((albedo.f64_b() - x).abs() < 1e-6, "{} at {}", albedo.f64_b(), x);
+    }
+}
+
+#[test]
+fn ply_errors_report_line_or_byte_offset() {
+    let text = ascii_ply();
+    let text = String::from_utf8(text).unwrap().replace("4 0 1 2 3", "4 0 1 2 7");
+    match parse_ply(text.as_bytes(), Path::new("bad.ply"), gray()) {
+        Err(ImportError::Parse { line, message, .. }) => {
+            assert_eq!(line, 18);
+            assert!(message.contains("vertex index 7 out of range"), "{}", message);
+        }
+        _ => panic!("expected a parse error"),
+    }
+
+    let mut data = binary_ply(false);
+    data.truncate(data.len() - 2);
+    let error = parse_ply(&data, Path::new("short.ply"), gray()).err().unwrap();
+    assert!(matches!(error, ImportError::Data { .. }));
+    assert!(error.to_string().starts_with("short.ply: at byte"), "{}", error);
+
+    match parse_ply(b"ply\nformat ascii 1.0\nelement vertex 1\nproperty foo x\nend_header\n", Path::new("t.ply"), gray()) {
+        Err(ImportError::Parse { line, .. }) => assert_eq!(line, 4),
+        _ => panic!("expected a parse error"),
+    }
+
+    //没有属性的元素不能声称有数据，否则会空转计数那么多次
+    let header = b"ply\nformat binary_little_endian 1.0\nelement foo 4000000000\nend_header\n";
+    match parse_ply(header, Path::new("t.ply"), gray()) {
+        Err(ImportError::Parse { line, message, .. }) => {
+            assert_eq!(line, 3);
+            assert!(message.contains("no properties"), "{}", message);
+        }
+        _ => panic!("expected a parse error"),
+    }
+}
+
+#[test]
+fn stl_ascii_and_binary_load_the_same_triangles() {
+    let ascii = b"solid test
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 0 1 0
+    endloop
+  endfacet
+endsolid test
+";
+
+    //二进制STL的文件头也以solid开头，要按文件长度识别
+    let mut binary = b"solid but actually binary".to_vec();
+    binary.resize(80, 0);
+    binary.extend(1u32.to_le_bytes());
+    for x in [0.0f32, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0] {
+        binary.extend(x.to_le_bytes());
+    }
+    binary.extend([0, 0]);
+
+    for data in [&ascii[..], &binary[..]] {
+        let mesh = parse_stl(data, Path::new("t.stl"), gray()).unwrap();
+        assert_eq!(mesh.data().faces.len(), 1);
+
+        let ray = Ray::new(Point3::new(0.2, 0.2, 1.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+        let hit = mesh.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+        assert!(hit.front_face);
+    }
+
+    let bad = b"solid t\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0\n";
+    match parse_stl(bad, Path::new("bad.stl"), gray()) {
+        Err(ImportError::Parse { line, .. }) => assert_eq!(line, 5),
+        _ => panic!("expected a parse error"),
+    }
+}
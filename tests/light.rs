@@ -0,0 +1,243 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use render::aarect::{XyRect, XzRect};
+use render::background::SolidBackground;
+use render::camera::{Camera, MisHeuristic, RenderSettings};
+use render::color::Color;
+use render::diffuse_light::DiffuseLight;
+use render::directional_light::DirectionalLight;
+use render::hittable::{HitInfo, Hittable};
+use render::isotropic::Isotropic;
+use render::lambertian::Lambertian;
+use render::light::Light;
+use render::material::Material;
+use render::point_light::PointLight;
+use render::ray::Ray;
+use render::sphere::Sphere;
+use render::spot_light::SpotLight;
+use render::triangle::Triangle;
+use render::utils::Sampler;
+use render::vector::{Point3, Vector3};
+use render::world::World;
+
+fn light_material() -> Arc<DiffuseLight> {
+    Arc::new(DiffuseLight::new(&Color::new(64.0, 64.0, 64.0)))
+}
+
+//均匀采样所有方向估计pdf_value在整个球面上的积分，应该为1；random采样到的方向都能看到光源
+fn check_light_sampling(light: &dyn Hittable, origin: &Point3) {
+    let mut sampler = Sampler::new(11);
+    let count = 200000;
+    let integral: f64 = (0..count)
+        .map(|_| light.pdf_value(origin, &Vector3::random_unit_vector(&mut sampler), &mut sampler))
+        .sum::<f64>() * 4.0 * PI / count as f64;
+    assert!((integral - 1.0).abs() < 0.05, "pdf integrates to {}", integral);
+
+    for _ in 0..1000 {
+        let direction = light.random(origin, &mut sampler);
+        assert!(light.pdf_value(origin, &direction, &mut sampler) > 0.0);
+    }
+}
+
+#[test]
+fn light_pdfs_integrate_to_one() {
+    let origin = Point3::new(0.3, 0.0, 0.2);
+    check_light_sampling(&Sphere::new(Point3::new(0.0, 2.0, 0.0), 1.0, light_material()), &origin);
+    check_light_sampling(&XzRect::new(-1.0, 1.0, -0.5, 1.5, 1.0, light_material()), &origin);
+    check_light_sampling(&XyRect::new(-1.0, 2.0, -1.0, 1.0, -1.5, light_material()), &origin);
+    check_light_sampling(&Triangle::new(Point3::new(-1.0, 1.0, -1.0), Point3::new(2.0, 1.5, 0.0), Point3::new(0.0, 1.0, 2.0), light_material()), &origin);
+
+    //在球内向所有方向均匀采样
+    check_light_sampling(&Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, light_material()), &origin);
+}
+
+#[test]
+fn world_light_pdf_averages_over_lights() {
+    let mut world = World::new();
+    world.add_light(Arc::new(XzRect::new(-1.0, 1.0, -1.0, 1.0, 2.0, light_material())));
+    world.add_light(Arc::new(Sphere::new(Point3::new(0.0, -3.0, 0.0), 1.0, light_material())));
+    assert_eq!(world.lights().len(), 2);
+
+    let origin = Point3::new(0.0, 0.0, 0.0);
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    let rect_pdf = world.lights()[0].pdf_value(&origin, &up, &mut Sampler::new(0));
+    assert!((rect_pdf - 1.0).abs() < 1e-12);
+    assert!((world.light_pdf(&origin, &up, &mut Sampler::new(0)) - rect_pdf / 2.0).abs() < 1e-12);
+
+    //击中光源时HitInfo带有标记
+    let hit = world.hit(&Ray::new(origin, up, 0.0), 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!(hit.is_light);
+}
+
+//小球形光源正下方的漫反射地面，辐亮度为 albedo · emit · (r / h)^2
+fn small_light_scene(sample_lights: bool) -> (World, Camera) {
+    let mut world = World::new();
+    let floor = Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)));
+    world.add(Box::new(XzRect::new(-50.0, 50.0, -50.0, 50.0, 0.0, floor)));
+
+    let light = Sphere::new(Point3::new(0.0, 4.0, 0.0), 0.5, light_material());
+    if sample_lights {
+        world.add_light(Arc::new(light));
+    } else {
+        world.add(Box::new(light));
+    }
+
+    let mut camera = Camera::new(Point3::new(3.0, 1.0, 0.0), Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0),
+        1.0, 1.0, 0.0, 1.0, 0.0, 1.0);
+    camera.set_background(Arc::new(SolidBackground::new(&Color::black())));
+    (world, camera)
+}
+
+#[test]
+fn direct_light_sampling_converges_for_small_lights() {
+    let settings = RenderSettings { image_width: 8, image_height: 8, samples_per_pixel: 16, max_depth: 2, threads: 1, seed: 3, mis: MisHeuristic::Power };
+    let expected = 0.5 * 64.0 * (0.5f64 / 4.0).powi(2);
+    let error = |image: &[Color]| image.iter().map(|c| (c.f64_g() - expected).abs()).sum::<f64>() / image.len() as f64;
+
+    let (world, camera) = small_light_scene(true);
+    let sampled = camera.take_photo(&world, &settings);
+    let (world, camera) = small_light_scene(false);
+    let random_walk = camera.take_photo(&world, &settings);
+
+    assert!(error(&sampled) < 0.02 * expected, "error {}", error(&sampled));
+    assert!(error(&random_walk) > 10.0 * error(&sampled), "{} vs {}", error(&random_walk), error(&sampled));
+}
+
+#[test]
+fn mis_weights_sum_to_one() {
+    for heuristic in [MisHeuristic::Balance, MisHeuristic::Power] {
+        for &(a, b) in &[(1.0, 3.0), (0.2, 0.2), (5.0, 0.0)] {
+            assert!((heuristic.weight(a, b) + heuristic.weight(b, a) - 1.0).abs() < 1e-12);
+        }
+        assert_eq!(heuristic.weight(0.0, 0.0), 0.0);
+    }
+
+    //power更偏向概率密度大的策略
+    assert!((MisHeuristic::Balance.weight(3.0, 1.0) - 0.75).abs() < 1e-12);
+    assert!((MisHeuristic::Power.weight(3.0, 1.0) - 0.9).abs() < 1e-12);
+}
+
+//scatter给出的概率密度与pdf一致，attenuation等于eval / pdf，pdf在整个球面上的积分为1
+fn check_material_sampling(material: &dyn Material, hit_info: &HitInfo, r_in: &Ray) {
+    let mut sampler = Sampler::new(5);
+    for _ in 0..1000 {
+        let scatter = material.scatter(r_in, hit_info, &mut sampler).unwrap();
+        let direction = scatter.scattered.dir;
+        let pdf = scatter.pdf.unwrap();
+        assert!((pdf - material.pdf(r_in, hit_info, &direction)).abs() < 1e-9);
+
+        let eval = material.eval(r_in, hit_info, &direction);
+        assert!((scatter.attenuation.f64_g() * pdf - eval.f64_g()).abs() < 1e-9);
+    }
+
+    let count = 200000;
+    let integral: f64 = (0..count)
+        .map(|_| material.pdf(r_in, hit_info, &Vector3::random_unit_vector(&mut sampler)))
+        .sum::<f64>() * 4.0 * PI / count as f64;
+    assert!((integral - 1.0).abs() < 0.02, "pdf integrates to {}", integral);
+}
+
+#[test]
+fn materials_report_consistent_pdfs() {
+    let r_in = Ray::new(Point3::new(0.0, 1.0, 1.0), Vector3::new(0.0, -1.0, -1.0), 0.0);
+    let lambertian = Lambertian::new(&Color::new(0.5, 0.6, 0.7));
+    let isotropic = Isotropic::new(&Color::new(0.9, 0.8, 0.7));
+    let hit_info = HitInfo::new(&r_in, Point3::new(0.0, 0.0, 0.0), &Vector3::new(0.0, 1.0, 0.0), 1.0, 0.0, 0.0, &lambertian);
+
+    check_material_sampling(&lambertian, &hit_info, &r_in);
+    check_material_sampling(&isotropic, &hit_info, &r_in);
+
+    //按cosθ加权采样时cosθ的均值为2/3，并且不会采样到表面以下
+    let mut sampler = Sampler::new(9);
+    let count = 20000;
+    let mean_cosine = (0..count)
+        .map(|_| lambertian.scatter(&r_in, &hit_info, &mut sampler).unwrap().scattered.dir.unit().y)
+        .inspect(|cosine| assert!(*cosine >= 0.0))
+        .sum::<f64>() / count as f64;
+    assert!((mean_cosine - 2.0 / 3.0).abs() < 0.01, "{}", mean_cosine);
+}
+
+#[test]
+fn mis_is_unbiased_for_large_lights() {
+    //大的球形光源，地面的辐亮度同样为albedo · emit · (r / h)^2
+    let mut world = World::new();
+    let floor = Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)));
+    world.add(Box::new(XzRect::new(-50.0, 50.0, -50.0, 50.0, 0.0, floor)));
+    world.add_light(Arc::new(Sphere::new(Point3::new(0.0, 4.0, 0.0), 3.0, Arc::new(DiffuseLight::new(&Color::new(2.0, 2.0, 2.0))))));
+
+    let mut camera = Camera::new(Point3::new(5.0, 0.5, 0.0), Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0),
+        1.0, 1.0, 0.0, 1.0, 0.0, 1.0);
+    camera.set_background(Arc::new(SolidBackground::new(&Color::black())));
+    let expected = 0.5 * 2.0 * (3.0f64 / 4.0).powi(2);
+
+    for mis in [MisHeuristic::Balance, MisHeuristic::Power] {
+        let settings = RenderSettings { image_width: 8, image_height: 8, samples_per_pixel: 64, max_depth: 2, threads: 1, seed: 1, mis };
+        let image = camera.take_photo(&world, &settings);
+        let mean = image.iter().map(|c| c.f64_g()).sum::<f64>() / image.len() as f64;
+        assert!((mean - expected).abs() < 0.01 * expected, "{:?}: {} vs {}", mis, mean, expected);
+    }
+}
+
+#[test]
+fn delta_lights_fall_off_with_distance_and_angle() {
+    let p = Point3::new(0.0, 0.0, 0.0);
+    let white = Color::new(1.0, 1.0, 1.0);
+
+    let point = PointLight::new(Point3::new(0.0, 4.0, 0.0), &white, 16.0).sample(&p).unwrap();
+    assert!((point.distance - 4.0).abs() < 1e-12);
+    assert!((point.direction.y - 1.0).abs() < 1e-12);
+    assert!((point.irradiance.f64_r() - 1.0).abs() < 1e-12);
+
+    //内圆锥之内全部强度，内外圆锥之间平滑衰减，外圆锥之外为0
+    let spot = SpotLight::new(Point3::new(0.0, 1.0, 0.0), &Vector3::new(0.0, -1.0, 0.0), &white, 1.0, 20.0, 40.0);
+    assert_eq!(spot.sample(&p).unwrap().irradiance.f64_r(), 1.0);
+    let edge = spot.sample(&Point3::new(30f64.to_radians().tan(), 0.0, 0.0)).unwrap();
+    assert!(edge.irradiance.f64_r() > 0.0 && edge.irradiance.f64_r() < 1.0 / (1.0 + 30f64.to_radians().tan().powi(2)));
+    assert!(spot.sample(&Point3::new(1.0, 0.0, 0.0)).is_none());
+    assert!(spot.falloff(20f64.to_radians().cos() + 1e-9) == 1.0 && spot.falloff(40f64.to_radians().cos()) == 0.0);
+
+    let sun = DirectionalLight::new(&Vector3::new(0.0, -2.0, 0.0), &Color::new(1.0, 0.5, 0.25), 2.0).sample(&p).unwrap();
+    assert!(sun.distance.is_infinite());
+    assert!((sun.direction.y - 1.0).abs() < 1e-12);
+    assert_eq!((sun.irradiance.f64_r(), sun.irradiance.f64_b()), (2.0, 0.5));
+}
+
+//只有delta光源照亮的漫反射地面，相机看向原点附近
+fn lit_floor(light: Box<dyn Light>, blocker: bool) -> f64 {
+    let mut world = World::new();
+    let floor = Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)));
+    world.add(Box::new(XzRect::new(-50.0, 50.0, -50.0, 50.0, 0.0, floor.clone())));
+    if blocker {
+        world.add(Box::new(Sphere::new(Point3::new(0.0, 2.0, 0.0), 0.5, floor)));
+    }
+    world.add_delta_light(light);
+
+    let mut camera = Camera::new(Point3::new(3.0, 1.0, 0.0), Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0),
+        0.1, 1.0, 0.0, 1.0, 0.0, 1.0);
+    camera.set_background(Arc::new(SolidBackground::new(&Color::black())));
+    let settings = RenderSettings { image_width: 4, image_height: 4, samples_per_pixel: 4, max_depth: 2, threads: 1, seed: 1, mis: MisHeuristic::Power };
+
+    let image = camera.take_photo(&world, &settings);
+    image.iter().map(|c| c.f64_r()).sum::<f64>() / image.len() as f64
+}
+
+#[test]
+fn delta_lights_illuminate_through_shadow_rays() {
+    let white = Color::new(1.0, 1.0, 1.0);
+    let lambert = 0.5 / PI;
+
+    //点光源在正上方4处，照度为16 / 4^2
+    let point = || Box::new(PointLight::new(Point3::new(0.0, 4.0, 0.0), &white, 16.0));
+    assert!((lit_floor(point(), false) - lambert).abs() < 1e-3 * lambert);
+    assert_eq!(lit_floor(point(), true), 0.0);
+
+    let spot = SpotLight::new(Point3::new(0.0, 4.0, 0.0), &Vector3::new(0.0, -1.0, 0.0), &white, 16.0, 10.0, 15.0);
+    assert!((lit_floor(Box::new(spot), false) - lambert).abs() < 1e-3 * lambert);
+    let aside = SpotLight::new(Point3::new(0.0, 4.0, 0.0), &Vector3::new(1.0, -1.0, 0.0), &white, 16.0, 10.0, 15.0);
+    assert_eq!(lit_floor(Box::new(aside), false), 0.0);
+
+    //45度斜射的平行光，照度乘以cos45°
+    let sun = DirectionalLight::new(&Vector3::new(-1.0, -1.0, 0.0), &white, 2.0);
+    assert!((lit_floor(Box::new(sun), false) - lambert * 2.0 * 0.5f64.sqrt()).abs() < 1e-3 * lambert);
+}
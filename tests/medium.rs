@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use render::color::Color;
+use render::constant_medium::ConstantMedium;
+use render::cuboid::Cuboid;
+use render::hittable::Hittable;
+use render::isotropic::Isotropic;
+use render::lambertian::Lambertian;
+use render::material::Material;
+use render::ray::Ray;
+use render::sphere::Sphere;
+use render::utils::Sampler;
+use render::vector::{Point3, Vector3};
+
+fn unit_sphere() -> Arc<dyn Hittable> {
+    Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)))))
+}
+
+#[test]
+fn free_flight_follows_exponential_distribution() {
+    //穿过球心的光线在介质中走过的距离为2，不发生散射的概率为exp(-density·2)
+    let density = 0.5;
+    let medium = ConstantMedium::with_color(unit_sphere(), density, &Color::new(1.0, 1.0, 1.0));
+    let ray = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -2.0), 0.0);
+
+    let mut sampler = Sampler::new(7);
+    let count = 20000;
+    let mut passed = 0;
+    let mut total_depth = 0.0;
+    for _ in 0..count {
+        match medium.hit(&ray, 0.001, f64::INFINITY, &mut sampler) {
+            Some(hit) => {
+                //散射点都在球内，t按没有归一化的方向计算
+                assert!(hit.t >= 2.0 - 1e-9 && hit.t <= 3.0 + 1e-9);
+                assert!(hit.pos.length() <= 1.0 + 1e-9);
+                total_depth += 1.0 - hit.pos.z;
+            }
+            None => passed += 1,
+        }
+    }
+
+    let expected = (-density * 2.0f64).exp();
+    let fraction = passed as f64 / count as f64;
+    assert!((fraction - expected).abs() < 0.015, "{} vs {}", fraction, expected);
+
+    //截断在[0, 2]上的指数分布的期望
+    let scattered = (count - passed) as f64;
+    let expected_depth = 1.0 / density - 2.0 * expected / (1.0 - expected);
+    assert!((total_depth / scattered - expected_depth).abs() < 0.03);
+}
+
+#[test]
+fn rays_starting_inside_the_medium_scatter_ahead() {
+    let medium = ConstantMedium::with_color(unit_sphere(), 1000.0, &Color::new(1.0, 1.0, 1.0));
+    let ray = Ray::new(Point3::new(0.2, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 0.0);
+
+    let hit = medium.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(1)).unwrap();
+    assert!(hit.t >= 0.001 && hit.t < 0.05);
+    //法线与光线方向相反，光线总是来自正面
+    assert!(hit.front_face);
+    assert!(hit.normal.dot(&ray.dir) < 0.0);
+
+    //t_max之后的介质不会被击中
+    assert!(medium.hit(&ray, 0.001, 0.0005, &mut Sampler::new(1)).is_none());
+
+    //离开介质的光线没有交点
+    let ray = Ray::new(Point3::new(2.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 0.0);
+    assert!(medium.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(1)).is_none());
+}
+
+#[test]
+fn medium_fills_box_boundary() {
+    let material: Arc<dyn Material> = Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)));
+    let boundary = Arc::new(Cuboid::new(&Point3::new(0.0, 0.0, 0.0), &Point3::new(1.0, 1.0, 1.0), material));
+    let medium = ConstantMedium::with_color(boundary.clone(), 100.0, &Color::new(0.2, 0.4, 0.6));
+
+    let bbox = medium.bounding_box(0.0, 1.0).unwrap();
+    let expected = boundary.bounding_box(0.0, 1.0).unwrap();
+    assert_eq!((bbox.minimum.x, bbox.maximum.y), (expected.minimum.x, expected.maximum.y));
+
+    let ray = Ray::new(Point3::new(0.5, 0.5, -1.0), Vector3::new(0.0, 0.0, 1.0), 0.0);
+    let mut sampler = Sampler::new(3);
+    let hit = medium.hit(&ray, 0.001, f64::INFINITY, &mut sampler).unwrap();
+    assert!(hit.pos.z >= 0.0 && hit.pos.z <= 1.0);
+
+    //相函数的颜色来自介质
+    let scatter = hit.material.scatter(&ray, &hit, &mut sampler).unwrap();
+    let a = scatter.attenuation;
+    assert_eq!((a.f64_r(), a.f64_g(), a.f64_b()), (0.2, 0.4, 0.6));
+}
+
+#[test]
+fn isotropic_scatters_uniformly() {
+    let material = Isotropic::new(&Color::new(1.0, 1.0, 1.0));
+    let medium = ConstantMedium::new(unit_sphere(), 1000.0, Arc::new(Isotropic::new(&Color::new(1.0, 1.0, 1.0))));
+    let ray = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0), 0.5);
+    let mut sampler = Sampler::new(5);
+    let hit = medium.hit(&ray, 0.001, f64::INFINITY, &mut sampler).unwrap();
+
+    //散射方向是单位向量，平均值接近0（与入射方向无关）
+    let count = 20000;
+    let mut sum = Vector3::new(0.0, 0.0, 0.0);
+    for _ in 0..count {
+        let scatter = material.scatter(&ray, &hit, &mut sampler).unwrap();
+        assert!((scatter.scattered.dir.length() - 1.0).abs() < 1e-9);
+        assert_eq!(scatter.scattered.time, 0.5);
+        sum = sum + scatter.scattered.dir;
+    }
+    assert!((sum / count as f64).length() < 0.02);
+}
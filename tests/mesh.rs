@@ -0,0 +1,127 @@
+mod common;
+
+use render::hittable::Hittable;
+use render::ray::Ray;
+use render::triangle::Triangle;
+use render::triangle_mesh::{MeshData, MeshFace, TriangleMesh};
+use render::utils::Sampler;
+use render::vector::{Point3, Vector3};
+
+use common::{gray, near};
+
+//两个三角形组成的[0,1]x[0,1]正方形，位于z = 0平面上
+fn quad() -> MeshData {
+    let mut data = MeshData::new();
+    data.positions = vec![
+        Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0),
+        Point3::new(1.0, 1.0, 0.0), Point3::new(0.0, 1.0, 0.0),
+    ];
+    data.uvs = vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+    data.faces = vec![
+        MeshFace { positions: [0, 1, 2], normals: None, uvs: Some([0, 1, 2]) },
+        MeshFace { positions: [0, 2, 3], normals: None, uvs: Some([0, 2, 3]) },
+    ];
+    data
+}
+
+#[test]
+fn triangle_hit_uses_barycentric_coordinates() {
+    let triangle = Triangle::new(Point3::new(0.0, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0), Point3::new(0.0, 2.0, 0.0), gray());
+
+    let ray = Ray::new(Point3::new(0.5, 1.0, 3.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+    let hit = triangle.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!((hit.t - 3.0).abs() < 1e-9);
+    assert!((hit.u - 0.25).abs() < 1e-9 && (hit.v - 0.5).abs() < 1e-9);
+    assert!(hit.front_face);
+    assert!(near(&hit.normal, &Vector3::new(0.0, 0.0, 1.0)));
+
+    //重心坐标之和超过1的点在三角形之外
+    let ray = Ray::new(Point3::new(1.5, 1.0, 3.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+    assert!(triangle.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).is_none());
+
+    //与三角形平行的光线
+    let ray = Ray::new(Point3::new(-1.0, 0.5, 0.0), Vector3::new(1.0, 0.0, 0.0), 0.0);
+    assert!(triangle.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).is_none());
+
+    //平行于坐标平面的三角形的包围盒也有厚度
+    let bbox = triangle.bounding_box(0.0, 1.0).unwrap();
+    assert!(bbox.maximum.z > bbox.minimum.z);
+}
+
+#[test]
+fn mesh_interpolates_uvs_from_shared_buffers() {
+    let mesh = TriangleMesh::new(quad(), gray(), false).unwrap();
+
+    for (x, y) in [(0.25, 0.75), (0.8, 0.1), (0.5, 0.5)] {
+        let ray = Ray::new(Point3::new(x, y, 1.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+        let hit = mesh.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+        assert!((hit.u - x).abs() < 1e-9 && (hit.v - y).abs() < 1e-9);
+        assert!(near(&hit.normal, &Vector3::new(0.0, 0.0, 1.0)));
+    }
+
+    let ray = Ray::new(Point3::new(1.5, 0.5, 1.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+    assert!(mesh.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).is_none());
+}
+
+#[test]
+fn smooth_shading_interpolates_vertex_normals() {
+    //屋脊形状：两个斜面在x = 0处相交
+    let mut data = MeshData::new();
+    data.positions = vec![
+        Point3::new(-1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0), Point3::new(0.0, 1.0, -1.0), Point3::new(-1.0, 0.0, -1.0),
+        Point3::new(1.0, 0.0, 0.0), Point3::new(1.0, 0.0, -1.0),
+    ];
+    data.faces = vec![
+        MeshFace::new([0, 1, 2]), MeshFace::new([0, 2, 3]),
+        MeshFace::new([1, 4, 2]), MeshFace::new([4, 5, 2]),
+    ];
+    data.compute_vertex_normals();
+
+    //屋脊上的顶点法线是两个斜面法线的平均，指向正上方
+    assert!(near(&data.normals[1], &Vector3::new(0.0, 1.0, 0.0)));
+
+    let flat = TriangleMesh::new(data.clone(), gray(), false).unwrap();
+    let smooth = TriangleMesh::new(data, gray(), true).unwrap();
+
+    let ray = Ray::new(Point3::new(-0.01, 5.0, -0.5), Vector3::new(0.0, -1.0, 0.0), 0.0);
+    let flat_normal = flat.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap().normal;
+    let smooth_normal = smooth.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap().normal;
+
+    let slope = Vector3::new(-1.0, 1.0, 0.0).unit();
+    assert!(near(&flat_normal, &slope));
+    //靠近屋脊的地方平滑法线几乎指向正上方
+    assert!(smooth_normal.y > 0.99);
+}
+
+#[test]
+fn front_face_follows_the_geometric_normal() {
+    //顶点法线严重倾斜，掠射的光线从几何上的正面射入，但与插值法线同向
+    let mut data = quad();
+    data.normals = vec![Vector3::new(1.0, 0.0, 0.1).unit()];
+    for face in &mut data.faces {
+        face.normals = Some([0, 0, 0]);
+    }
+    let mesh = TriangleMesh::new(data, gray(), true).unwrap();
+
+    let ray = Ray::new(Point3::new(-1.0, 0.5, 0.05), Vector3::new(1.0, 0.0, -0.05), 0.0);
+    let hit = mesh.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!(hit.front_face);
+    assert!(near(&hit.normal, &Vector3::new(1.0, 0.0, 0.1).unit()));
+
+    //从背面射入时正反面和法线一起翻转到光线所在的一侧
+    let ray = Ray::new(Point3::new(-1.0, 0.5, -0.05), Vector3::new(1.0, 0.0, 0.05), 0.0);
+    let hit = mesh.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!(!hit.front_face);
+    assert!(hit.normal.z < 0.0);
+}
+
+#[test]
+fn mesh_rejects_out_of_range_indices() {
+    let mut data = quad();
+    data.faces.push(MeshFace::new([0, 1, 4]));
+    assert!(TriangleMesh::new(data, gray(), false).is_err());
+
+    //没有三角形的网格不与任何光线相交，也没有包围盒
+    let empty = TriangleMesh::new(MeshData::new(), gray(), false).unwrap();
+    assert!(empty.bounding_box(0.0, 1.0).is_none());
+}
@@ -0,0 +1,165 @@
+use std::io::Cursor;
+
+use render::color::Color;
+use render::output::{read_exr, read_hdr, read_png, read_ppm, writer_for_format, ImageWriter};
+use render::postprocess::{PostProcess, ToneMap, TransferFunction};
+
+fn test_image(width: usize, height: usize) -> Vec<Color> {
+    (0..width * height)
+        .map(|i| {
+            let x = i as f64;
+            Color::new(x * 0.37, 1.0 / (1.0 + x), if i % 5 == 0 { 0.0 } else { 20.0 - x * 0.1 })
+        })
+        .collect()
+}
+
+fn encode(writer: &dyn ImageWriter, image: &[Color], width: usize, height: usize) -> Vec<u8> {
+    let mut data = Vec::new();
+    writer.write_image(&mut data, image, width, height).unwrap();
+    data
+}
+
+//相对于像素最大分量的误差
+fn max_error(a: &[Color], b: &[Color]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| {
+        let scale = x.f64_r().max(x.f64_g()).max(x.f64_b()).max(1e-6);
+        [(x.f64_r() - y.f64_r()), (x.f64_g() - y.f64_g()), (x.f64_b() - y.f64_b())]
+            .iter()
+            .map(|d| d.abs() / scale)
+            .fold(0.0, f64::max)
+    }).fold(0.0, f64::max)
+}
+
+#[test]
+fn hdr_round_trip_preserves_values_above_one() {
+    //宽度40时使用RLE压缩，宽度5时不压缩
+    for (width, height) in [(40, 3), (5, 2)] {
+        let image = test_image(width, height);
+        let writer = writer_for_format("hdr").unwrap();
+        assert!(writer.is_hdr());
+
+        let data = encode(writer.as_ref(), &image, width, height);
+        let loaded = read_hdr(&mut Cursor::new(data)).unwrap();
+
+        assert_eq!((loaded.width, loaded.height), (width, height));
+        assert!(max_error(&image, &loaded.pixels) < 0.01);
+    }
+}
+
+#[test]
+fn exr_round_trip_in_half_and_float() {
+    let (width, height) = (7, 4);
+    let image = test_image(width, height);
+
+    for (format, tolerance) in [("exr", 1e-3), ("exr32", 1e-6)] {
+        let data = encode(writer_for_format(format).unwrap().as_ref(), &image, width, height);
+        let loaded = read_exr(&mut Cursor::new(data)).unwrap();
+
+        assert_eq!((loaded.width, loaded.height), (width, height));
+        assert!(max_error(&image, &loaded.pixels) < tolerance, "{}", format);
+    }
+}
+
+#[test]
+fn hdr_and_exr_readers_reject_sizes_the_data_does_not_have() {
+    let data = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 100000 +X 100000000\n\x01\x02\x03\x80".to_vec();
+    assert!(read_hdr(&mut Cursor::new(data)).is_err());
+
+    //把EXR文件头中的数据窗口改成很大的尺寸
+    let mut data = encode(writer_for_format("exr").unwrap().as_ref(), &test_image(2, 2), 2, 2);
+    let name = b"dataWindow\0box2i\0";
+    let start = data.windows(name.len()).position(|w| w == name).unwrap() + name.len() + 4;
+    data[start + 8..start + 16].copy_from_slice(&[0xff, 0xff, 0xff, 0x7f, 0xff, 0xff, 0xff, 0x7f]);
+    data[start..start + 8].copy_from_slice(&[0, 0, 0, 0x80, 0, 0, 0, 0x80]);
+    let error = match read_exr(&mut Cursor::new(data)) {
+        Err(e) => e,
+        Ok(_) => panic!("image should not load"),
+    };
+    assert!(error.to_string().contains("does not fit"), "{}", error);
+}
+
+#[test]
+fn binary_ppm_has_header_and_three_bytes_per_pixel() {
+    let image = vec![Color::new(1.0, 0.0, 0.25); 6];
+    let data = encode(writer_for_format("ppm").unwrap().as_ref(), &image, 3, 2);
+
+    let header = b"P6\n3 2\n255\n";
+    assert_eq!(&data[..header.len()], header);
+    assert_eq!(&data[header.len()..header.len() + 3], &[255, 0, 64]);
+    assert_eq!(data.len(), header.len() + 6 * 3);
+}
+
+#[test]
+fn ppm_and_png_are_read_back_as_linear_colors() {
+    //显示值0.5按sRGB解码后约为0.214
+    let image = vec![Color::new(1.0, 0.0, 0.5), Color::new(0.0, 1.0, 0.0)];
+    let expected = [Color::new(1.0, 0.0, 0.2158), Color::new(0.0, 1.0, 0.0)];
+
+    for format in ["ppm", "ppm-ascii", "png", "png16"] {
+        let data = encode(writer_for_format(format).unwrap().as_ref(), &image, 2, 1);
+        let mut input = Cursor::new(data);
+        let loaded = if format.starts_with("ppm") { read_ppm(&mut input) } else { read_png(&mut input) }.unwrap();
+
+        assert_eq!((loaded.width, loaded.height), (2, 1));
+        for (a, b) in loaded.pixels.iter().zip(&expected) {
+            assert!((a.f64_r() - b.f64_r()).abs() < 2e-3, "{}", format);
+            assert!((a.f64_g() - b.f64_g()).abs() < 2e-3, "{}", format);
+            assert!((a.f64_b() - b.f64_b()).abs() < 2e-3, "{}", format);
+        }
+    }
+}
+
+#[test]
+fn ppm_reader_skips_header_comments() {
+    let data = b"P3\n# comment\n2 1 # size\n15\n15 0 0  0 0 15\n".to_vec();
+    let loaded = read_ppm(&mut Cursor::new(data)).unwrap();
+
+    assert_eq!((loaded.width, loaded.height), (2, 1));
+    assert_eq!(loaded.pixels[0].f64_r(), 1.0);
+    assert_eq!(loaded.pixels[1].f64_b(), 1.0);
+}
+
+#[test]
+fn ppm_reader_rejects_sizes_the_data_does_not_have() {
+    let error = |data: Vec<u8>| match read_ppm(&mut Cursor::new(data)) {
+        Err(e) => e,
+        Ok(_) => panic!("image should not load"),
+    };
+
+    //文件头声明的尺寸很大但数据很少，应当报错而不是按声明的尺寸分配内存
+    let e = error(b"P6\n100000 100000\n255\n\x01\x02\x03".to_vec());
+    assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    let e = error(format!("P6\n{} {}\n65535\n", usize::MAX / 2, 3).into_bytes());
+    assert!(e.to_string().contains("too large"), "{}", e);
+}
+
+#[test]
+fn default_post_process_matches_gamma_two_and_clamp() {
+    let mut image = vec![Color::new(0.25, 4.0, -1.0)];
+    PostProcess::default().apply(&mut image);
+
+    assert!((image[0].f64_r() - 0.5).abs() < 1e-12);
+    assert_eq!(image[0].f64_g(), 1.0);
+    assert_eq!(image[0].f64_b(), 0.0);
+}
+
+#[test]
+fn tone_map_operators_stay_in_display_range() {
+    let operators = ["clamp", "reinhard", "reinhard-extended:2", "aces", "hable"];
+
+    for name in operators {
+        let tone_map: ToneMap = name.parse().unwrap();
+        let mut previous = 0.0;
+        for i in 0..100 {
+            let y = tone_map.map(i as f64 * 0.5);
+            assert!((0.0..=1.0).contains(&y), "{}", name);
+            assert!(y >= previous, "{} is not monotonic", name);
+            previous = y;
+        }
+    }
+
+    assert_eq!("gamma:2.2".parse::<TransferFunction>(), Ok(TransferFunction::Gamma(2.2)));
+    assert!("gamma".parse::<TransferFunction>().is_err());
+    assert!((TransferFunction::Srgb.encode(1.0) - 1.0).abs() < 1e-9);
+}
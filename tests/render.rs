@@ -0,0 +1,102 @@
+use std::sync::Mutex;
+
+use render::builtin_scenes::create_random_spheres_scene;
+use render::bvh::BvhSplit;
+use render::camera::RenderSettings;
+use render::color::Color;
+use render::scene::{parse_scene, Scene};
+
+const SCENE: &str = "
+render width=24 aspect=3/2 spp=4 max_depth=8
+camera lookfrom=13,2,3 lookat=0,0,0 vfov=20 aperture=0.1 focus_dist=10
+material ground lambertian albedo=0.5,0.5,0.5
+material glass dielectric ir=1.5
+material bronze metal albedo=0.7,0.6,0.5 fuzz=0.3
+sphere center=0,-1000,0 radius=1000 material=ground
+sphere center=0,1,0 radius=1 material=glass
+sphere center=4,1,0 radius=1 material=bronze move_to=4,1.5,0
+";
+
+fn render(scene: &Scene, threads: usize, seed: u64) -> Vec<Color> {
+    let settings = RenderSettings { threads, seed, ..scene.settings };
+    scene.camera.take_photo(&scene.world, &settings)
+}
+
+fn same_image(a: &[Color], b: &[Color]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| {
+        x.f64_r().to_bits() == y.f64_r().to_bits()
+            && x.f64_g().to_bits() == y.f64_g().to_bits()
+            && x.f64_b().to_bits() == y.f64_b().to_bits()
+    })
+}
+
+#[test]
+fn fixed_seed_gives_identical_image_on_any_thread_count() {
+    let scene = parse_scene(SCENE).unwrap();
+
+    let single = render(&scene, 1, 7);
+    assert_eq!(single.len(), 24 * 16);
+    assert!(same_image(&single, &render(&scene, 1, 7)));
+    assert!(same_image(&single, &render(&scene, 3, 7)));
+    assert!(same_image(&single, &render(&scene, 8, 7)));
+}
+
+#[test]
+fn different_seeds_give_different_images() {
+    let scene = parse_scene(SCENE).unwrap();
+
+    assert!(!same_image(&render(&scene, 2, 1), &render(&scene, 2, 2)));
+}
+
+#[test]
+fn progress_reports_every_row_once() {
+    let scene = parse_scene(SCENE).unwrap();
+    let settings = RenderSettings { threads: 3, seed: 7, ..scene.settings };
+
+    let reported = Mutex::new(Vec::new());
+    let image = scene.camera.take_photo_with_progress(&scene.world, &settings, &|remaining| reported.lock().unwrap().push(remaining));
+    assert!(same_image(&image, &render(&scene, 1, 7)));
+
+    let mut reported = reported.into_inner().unwrap();
+    reported.sort();
+    assert_eq!(reported, (0..16).collect::<Vec<_>>());
+}
+
+#[test]
+fn bvh_does_not_change_the_image() {
+    let mut scene = create_random_spheres_scene(3);
+    scene.settings.image_width = 16;
+    scene.settings.image_height = 9;
+    scene.settings.samples_per_pixel = 2;
+
+    let linear = render(&scene, 2, 5);
+    let (time0, time1) = scene.camera.shutter();
+    scene.world.build_bvh(time0, time1, BvhSplit::Sah);
+
+    assert!(same_image(&linear, &render(&scene, 2, 5)));
+}
+
+#[test]
+fn emissive_material_is_seen_directly_and_black_background_adds_no_light() {
+    let scene = parse_scene("
+        render width=8 aspect=1 spp=2
+        camera lookfrom=0,0,0 lookat=0,0,-1 vfov=30
+        background solid color=0,0,0
+        material light diffuse_light emit=3,2,1
+        sphere center=0,0,-10 radius=5 material=light
+    ").unwrap();
+
+    for c in render(&scene, 1, 1) {
+        assert_eq!((c.f64_r(), c.f64_g(), c.f64_b()), (3.0, 2.0, 1.0));
+    }
+
+    let dark = parse_scene("
+        render width=8 aspect=1 spp=2
+        camera lookfrom=0,0,0 lookat=0,0,-1
+        background solid color=0,0,0
+        material m lambertian albedo=0.9,0.9,0.9
+        sphere center=0,0,-3 radius=1 material=m
+    ").unwrap();
+
+    assert!(render(&dark, 1, 1).iter().all(|c| c.f64_r() == 0.0 && c.f64_g() == 0.0 && c.f64_b() == 0.0));
+}
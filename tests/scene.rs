@@ -0,0 +1,315 @@
+use render::camera::MisHeuristic;
+use render::color::Color;
+use render::output::writer_for_format;
+use render::ray::Ray;
+use render::scene::{parse_scene, SceneError};
+use render::vector::{Point3, Vector3};
+
+fn parse_error(text: &str) -> (usize, String) {
+    match parse_scene(text) {
+        Err(SceneError::Parse { line, message }) => (line, message),
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("scene should not parse"),
+    }
+}
+
+#[test]
+fn parses_render_settings_and_camera() {
+    let scene = parse_scene("
+        # comment line
+        render width=200 aspect=2 spp=8 max_depth=5 mis=balance output=\"my image.png\"
+        camera lookfrom=0,0,5 lookat=0,0,0
+        material m lambertian albedo=0.5,0.5,0.5
+        sphere center=0,0,0 radius=1 material=m
+    ").unwrap();
+
+    assert_eq!(scene.settings.image_width, 200);
+    assert_eq!(scene.settings.image_height, 100);
+    assert_eq!(scene.settings.samples_per_pixel, 8);
+    assert_eq!(scene.settings.max_depth, 5);
+    assert_eq!(scene.settings.mis, MisHeuristic::Balance);
+    assert_eq!(scene.output.to_str(), Some("my image.png"));
+    assert!((scene.camera.aspect_ratio() - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn reports_line_and_field_of_errors() {
+    let (line, message) = parse_error("camera lookfrom=0,0,5 lookat=0,0,0\n\nsphere center=0,0 radius=1 material=m\n");
+    assert_eq!(line, 3);
+    assert!(message.contains("field `center`"), "{}", message);
+
+    let (line, message) = parse_error("camera lookfrom=0,0,5 lookat=0,0,0\nsphere center=0,0,0 radius=1 material=m\n");
+    assert_eq!(line, 2);
+    assert!(message.contains("undefined material `m`"), "{}", message);
+
+    let (line, message) = parse_error("material m metal albedo=1,1,1 fuz=0.1\n");
+    assert_eq!(line, 1);
+    assert!(message.contains("unknown field `fuz`"), "{}", message);
+
+    let (_, message) = parse_error("render width=x\n");
+    assert!(message.contains("field `width`"), "{}", message);
+
+    //超出i32范围的采样数和深度不能回绕成负数
+    let (_, message) = parse_error("render spp=3000000000\n");
+    assert!(message.contains("field `spp`"), "{}", message);
+    let (_, message) = parse_error("render max_depth=2147483648\n");
+    assert!(message.contains("field `max_depth`"), "{}", message);
+    let (_, message) = parse_error("render max_depth=0\n");
+    assert!(message.contains("field `max_depth`"), "{}", message);
+
+    //快门区间为空或相机上方与视线平行时相机无法生成光线
+    let (_, message) = parse_error("camera lookfrom=0,0,5 lookat=0,0,0 shutter=1,0\n");
+    assert!(message.contains("field `shutter`"), "{}", message);
+    let (_, message) = parse_error("camera lookfrom=0,5,0 lookat=0,0,0\n");
+    assert!(message.contains("field `vup`"), "{}", message);
+
+    let (_, message) = parse_error("render mis=uniform\n");
+    assert!(message.contains("unknown MIS heuristic"), "{}", message);
+}
+
+#[test]
+fn requires_a_camera() {
+    assert!(matches!(parse_scene("render width=10\n"), Err(SceneError::Missing("camera"))));
+}
+
+#[test]
+fn material_colors_can_reference_textures() {
+    parse_scene("
+        camera lookfrom=0,0,5 lookat=0,0,0
+        texture dark solid color=0.1,0.1,0.1
+        texture floor checker even=dark odd=0.9,0.9,0.9 scale=0.5
+        material ground lambertian albedo=floor
+        material lamp diffuse_light emit=floor
+        sphere center=0,0,0 radius=1 material=ground
+    ").unwrap();
+
+    let (line, message) = parse_error("material m lambertian albedo=wood\n");
+    assert_eq!(line, 1);
+    assert!(message.contains("field `albedo`: undefined texture `wood`"), "{}", message);
+
+    let (_, message) = parse_error("texture t image file=does-not-exist.png\n");
+    assert!(message.contains("field `file`: cannot load image"), "{}", message);
+}
+
+#[test]
+fn parses_procedural_textures() {
+    parse_scene("
+        camera lookfrom=0,0,5 lookat=0,0,0
+        texture n noise scale=2 seed=3
+        texture t turbulence octaves=4
+        texture m marble vein=0.1,0.1,0.1
+        texture w wood
+        material a lambertian albedo=m
+        material b metal albedo=w fuzz=t
+        material c metal albedo=1,1,1 fuzz=0.3
+    ").unwrap();
+
+    let (_, message) = parse_error("material b metal albedo=1,1,1 fuzz=rough\n");
+    assert!(message.contains("field `fuzz`"), "{}", message);
+
+    let (_, message) = parse_error("texture t turbulence octaves=0\n");
+    assert!(message.contains("field `octaves`"), "{}", message);
+}
+
+#[test]
+fn parses_rects_and_boxes() {
+    parse_scene("
+        camera lookfrom=278,278,-800 lookat=278,278,0
+        material white lambertian albedo=0.73,0.73,0.73
+        xy_rect x=0,555 y=0,555 z=555 material=white
+        xz_rect x=0,555 z=0,555 y=0 material=white
+        yz_rect y=0,555 z=0,555 x=0 material=white
+        box min=130,0,65 max=295,165,230 material=white
+    ").unwrap();
+
+    let (_, message) = parse_error("material w lambertian albedo=1,1,1\nxz_rect x=5,1 z=0,1 y=0 material=w\n");
+    assert!(message.contains("field `x`"), "{}", message);
+
+    let (_, message) = parse_error("material w lambertian albedo=1,1,1\nbox min=0,0,0 max=1,0,1 material=w\n");
+    assert!(message.contains("field `max`"), "{}", message);
+}
+
+#[test]
+fn mesh_directive_reports_import_errors() {
+    let (_, message) = parse_error("mesh file=model.fbx\n");
+    assert!(message.contains("unsupported model format"), "{}", message);
+
+    let (_, message) = parse_error("mesh file=does-not-exist.obj\n");
+    assert!(message.contains("field `file`: does-not-exist.obj"), "{}", message);
+}
+
+#[test]
+fn parses_transforms_and_instances() {
+    parse_scene("
+        camera lookfrom=278,278,-800 lookat=278,278,0
+        material white lambertian albedo=0.73,0.73,0.73
+        box min=0,0,0 max=165,330,165 material=white rotate=0,15,0 translate=265,0,295
+        box min=0,0,0 max=1,1,1 material=white scale=2
+        box min=0,0,0 max=1,1,1 material=white scale=1,2,3
+    ").unwrap();
+
+    let (_, message) = parse_error("material w lambertian albedo=1,1,1\nbox min=0,0,0 max=1,1,1 material=w scale=0\n");
+    assert!(message.contains("field `scale`"), "{}", message);
+
+    let (_, message) = parse_error("material w lambertian albedo=1,1,1\nbox min=0,0,0 max=1,1,1 material=w rotate=1,2\n");
+    assert!(message.contains("field `rotate`"), "{}", message);
+
+    let (line, message) = parse_error("\ninstance model=bunny translate=0,1,0\n");
+    assert_eq!(line, 2);
+    assert!(message.contains("undefined model `bunny`"), "{}", message);
+}
+
+#[test]
+fn parses_keyframe_animations() {
+    parse_scene("
+        camera lookfrom=0,0,10 lookat=0,0,0 shutter=0,1
+        material white lambertian albedo=0.73,0.73,0.73
+        keyframe spin time=0
+        keyframe spin time=1 rotate=0,90,0 translate=1,0,0 scale=2
+        box min=0,0,0 max=1,1,1 material=white scale=0.5 animation=spin
+        sphere center=0,0,0 radius=1 material=white animation=spin
+    ").unwrap();
+
+    let (line, message) = parse_error("keyframe a time=0\nkeyframe a time=0 translate=1,0,0\n");
+    assert_eq!(line, 2);
+    assert!(message.contains("field `time`"), "{}", message);
+
+    let (_, message) = parse_error("keyframe a time=0 scale=1,-1,1\n");
+    assert!(message.contains("field `scale`"), "{}", message);
+
+    let (_, message) = parse_error("material w lambertian albedo=1,1,1\nsphere center=0,0,0 radius=1 material=w animation=walk\n");
+    assert!(message.contains("undefined animation `walk`"), "{}", message);
+}
+
+#[test]
+fn parses_participating_media() {
+    parse_scene("
+        camera lookfrom=278,278,-800 lookat=278,278,0
+        material smoke isotropic albedo=0,0,0
+        material fog isotropic albedo=1,1,1
+        box min=0,0,0 max=165,330,165 material=smoke density=0.01 rotate=0,15,0 translate=265,0,295
+        sphere center=0,0,0 radius=1 material=fog density=0.5
+    ").unwrap();
+
+    let (_, message) = parse_error("material fog isotropic albedo=1,1,1\nsphere center=0,0,0 radius=1 material=fog density=0\n");
+    assert!(message.contains("field `density`"), "{}", message);
+
+    //矩形不能围成一个区域
+    let (_, message) = parse_error("material fog isotropic albedo=1,1,1\nxy_rect x=0,1 y=0,1 z=0 material=fog density=1\n");
+    assert!(message.contains("unknown field `density`"), "{}", message);
+}
+
+#[test]
+fn parses_grid_volumes() {
+    let path = std::env::temp_dir().join(format!("render-scene-volume-{}.raw", std::process::id()));
+    let data: Vec<u8> = [0.0f32, 1.0, 0.5, 0.25].iter().flat_map(|v| v.to_le_bytes()).collect();
+    std::fs::write(&path, data).unwrap();
+    let file = path.display().to_string();
+
+    parse_scene(&format!("
+        camera lookfrom=0,0,10 lookat=0,0,0
+        volume file={} resolution=2,2,1 min=0,0,0 max=1,1,1 absorption=1 scattering=2 albedo=0.9,0.9,0.9 emission=1,0.5,0
+        volume file={} resolution=2,2,1 min=0,0,0 max=1,1,1 rotate=0,45,0 translate=2,0,0
+    ", file, file)).unwrap();
+
+    let (_, message) = parse_error(&format!("volume file={} min=0,0,0 max=1,1,1\n", file));
+    assert!(message.contains("missing field `resolution`"), "{}", message);
+
+    let (_, message) = parse_error(&format!("volume file={} resolution=2,2,2 min=0,0,0 max=1,1,1\n", file));
+    assert!(message.contains("needs 32 bytes"), "{}", message);
+
+    let (_, message) = parse_error(&format!("volume file={} resolution=2,2,1 min=0,0,0 max=1,1,1 emission=1,1,1\n", file));
+    assert!(message.contains("field `emission`"), "{}", message);
+
+    let (_, message) = parse_error("volume file=smoke.vdb min=0,0,0 max=1,1,1\n");
+    assert!(message.contains("unsupported voxel format"), "{}", message);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn emissive_shapes_become_lights() {
+    let scene = parse_scene("
+        camera lookfrom=278,278,-800 lookat=278,278,0
+        material light diffuse_light emit=15,15,15
+        material white lambertian albedo=0.73,0.73,0.73
+        xz_rect x=213,343 z=227,332 y=554 material=light
+        sphere center=0,10,0 radius=1 material=light
+        triangle v0=0,0,0 v1=1,0,0 v2=0,1,0 material=light
+        xz_rect x=0,555 z=0,555 y=0 material=white
+        sphere center=5,10,0 radius=1 material=light move_to=6,10,0
+        xy_rect x=0,1 y=0,1 z=0 material=light translate=0,5,0
+        box min=0,0,0 max=1,1,1 material=light
+    ").unwrap();
+
+    //只有静止、没有变换的球体、矩形和三角形登记为光源
+    assert_eq!(scene.world.lights().len(), 3);
+}
+
+#[test]
+fn parses_delta_lights() {
+    let scene = parse_scene("
+        camera lookfrom=0,2,5 lookat=0,0,0
+        point_light position=0,5,0 color=1,0.9,0.8 intensity=50
+        spot_light position=0,5,0 direction=0,-1,0 inner=15 outer=25 intensity=80
+        directional_light direction=-1,-1,0
+    ").unwrap();
+    assert_eq!(scene.world.delta_lights().len(), 3);
+    assert!(scene.world.lights().is_empty());
+
+    let (_, message) = parse_error("spot_light position=0,5,0 direction=0,-1,0 inner=30 outer=20\n");
+    assert!(message.contains("field `inner`"), "{}", message);
+
+    let (_, message) = parse_error("directional_light direction=0,0,0\n");
+    assert!(message.contains("zero vector"), "{}", message);
+
+    let (_, message) = parse_error("point_light position=0,5,0 intensity=-1\n");
+    assert!(message.contains("field `intensity`"), "{}", message);
+
+    let (_, message) = parse_error("point_light position=0,5,0 translate=0,1,0\n");
+    assert!(message.contains("unknown field `translate`"), "{}", message);
+}
+
+#[test]
+fn parses_environment_backgrounds() {
+    let path = std::env::temp_dir().join(format!("render-scene-env-{}.hdr", std::process::id()));
+    let pixels = vec![Color::new(0.5, 1.0, 2.0); 8 * 4];
+    let mut data = Vec::new();
+    writer_for_format("hdr").unwrap().write_image(&mut data, &pixels, 8, 4).unwrap();
+    std::fs::write(&path, data).unwrap();
+    let file = path.display().to_string();
+
+    let scene = parse_scene(&format!("
+        camera lookfrom=0,0,5 lookat=0,0,0
+        background image file={} intensity=2 rotation=90
+    ", file)).unwrap();
+    let color = scene.camera.background().color(&Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), 0.0));
+    assert!((color.f64_b() - 4.0).abs() < 0.05, "{:?}", color);
+
+    let (_, message) = parse_error(&format!("background image file={} intensity=-1\n", file));
+    assert!(message.contains("field `intensity`"), "{}", message);
+
+    let (_, message) = parse_error("background image file=missing.hdr\n");
+    assert!(message.contains("cannot load image `missing.hdr`"), "{}", message);
+
+    let (_, message) = parse_error("background cloudy\n");
+    assert!(message.contains("expected gradient, solid, image or sky"), "{}", message);
+
+    //天空同时加入配套的太阳光
+    let scene = parse_scene("
+        camera lookfrom=0,0,5 lookat=0,0,0
+        background sky sun_direction=1,2,0 turbidity=4 ground=0.2,0.2,0.2
+    ").unwrap();
+    assert_eq!(scene.world.delta_lights().len(), 1);
+    let zenith = scene.camera.background().color(&Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), 0.0));
+    assert!(zenith.f64_b() > zenith.f64_r(), "{:?}", zenith);
+
+    let (_, message) = parse_error("background sky sun_direction=0,-1,1\n");
+    assert!(message.contains("field `sun_direction`"), "{}", message);
+    let (_, message) = parse_error("background sky sun_direction=0,1,1 turbidity=1\n");
+    assert!(message.contains("field `turbidity`"), "{}", message);
+    let (_, message) = parse_error("background sky sun_direction=0,1,1 ground=2,0,0\n");
+    assert!(message.contains("field `ground`"), "{}", message);
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use render::aarect::{XyRect, XzRect, YzRect};
+use render::cuboid::Cuboid;
+use render::hittable::Hittable;
+use render::ray::Ray;
+use render::utils::Sampler;
+use render::vector::{Point3, Vector3};
+
+use common::{gray, near};
+
+#[test]
+fn rects_report_normal_front_face_and_uv() {
+    let rect = XyRect::new(0.0, 2.0, 0.0, 4.0, 1.0, gray());
+
+    //从+z一侧射入是正面，法线指向+z
+    let ray = Ray::new(Point3::new(0.5, 3.0, 5.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+    let hit = rect.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!((hit.t - 4.0).abs() < 1e-9);
+    assert!(hit.front_face);
+    assert!(near(&hit.normal, &Vector3::new(0.0, 0.0, 1.0)));
+    assert!((hit.u - 0.25).abs() < 1e-9 && (hit.v - 0.75).abs() < 1e-9);
+
+    //从背面射入，法线仍然与光线方向相反
+    let ray = Ray::new(Point3::new(0.5, 3.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0.0);
+    let hit = rect.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!(!hit.front_face);
+    assert!(near(&hit.normal, &Vector3::new(0.0, 0.0, -1.0)));
+
+    //矩形范围之外和与平面平行的光线都不相交
+    let ray = Ray::new(Point3::new(2.5, 3.0, 5.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+    assert!(rect.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).is_none());
+    let ray = Ray::new(Point3::new(0.5, 3.0, 1.0), Vector3::new(1.0, 0.0, 0.0), 0.0);
+    assert!(rect.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).is_none());
+
+    let xz = XzRect::new(0.0, 1.0, 0.0, 1.0, 2.0, gray());
+    let ray = Ray::new(Point3::new(0.5, 0.0, 0.5), Vector3::new(0.0, 1.0, 0.0), 0.0);
+    assert!(!xz.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap().front_face);
+
+    let yz = YzRect::new(0.0, 1.0, 0.0, 1.0, -2.0, gray());
+    let ray = Ray::new(Point3::new(0.0, 0.5, 0.5), Vector3::new(-1.0, 0.0, 0.0), 0.0);
+    assert!(yz.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap().front_face);
+
+    //包围盒在法线方向上有一点厚度
+    let bbox = rect.bounding_box(0.0, 1.0).unwrap();
+    assert!(bbox.maximum.z > bbox.minimum.z);
+}
+
+#[test]
+fn every_face_of_a_box_faces_outwards() {
+    let cuboid = Cuboid::new(&Point3::new(1.0, 1.0, 1.0), &Point3::new(-1.0, -1.0, -1.0), gray());
+    let axes = [Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0)];
+
+    for axis in axes {
+        for sign in [1.0, -1.0] {
+            let outward = axis * sign;
+
+            //从外面射向盒子中心：正面，法线就是该面的外法线
+            let ray = Ray::new(outward * 5.0, -outward, 0.0);
+            let hit = cuboid.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+            assert!((hit.t - 4.0).abs() < 1e-9);
+            assert!(hit.front_face);
+            assert!(near(&hit.normal, &outward));
+
+            //从盒子中心射出：背面
+            let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), outward, 0.0);
+            let hit = cuboid.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+            assert!(!hit.front_face);
+            assert!(near(&hit.normal, &-outward));
+        }
+    }
+
+    let bbox = cuboid.bounding_box(0.0, 1.0).unwrap();
+    assert!(near(&bbox.minimum, &Point3::new(-1.0, -1.0, -1.0)));
+    assert!(near(&bbox.maximum, &Point3::new(1.0, 1.0, 1.0)));
+}
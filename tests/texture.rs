@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use render::checker_texture::CheckerTexture;
+use render::color::Color;
+use render::hittable::Hittable;
+use render::image_texture::ImageTexture;
+use render::lambertian::Lambertian;
+use render::metal::Metal;
+use render::noise_texture::{MarbleTexture, NoiseTexture, WoodTexture};
+use render::output::Image;
+use render::perlin::Perlin;
+use render::ray::Ray;
+use render::sphere::Sphere;
+use render::texture::{SolidColor, Texture};
+use render::utils::Sampler;
+use render::vector::{Point3, Vector3};
+
+fn same(a: Color, b: Color) -> bool {
+    (a.f64_r() - b.f64_r()).abs() < 1e-9 && (a.f64_g() - b.f64_g()).abs() < 1e-9 && (a.f64_b() - b.f64_b()).abs() < 1e-9
+}
+
+#[test]
+fn checker_alternates_between_neighbouring_cells() {
+    let (white, black) = (Color::new(1.0, 1.0, 1.0), Color::black());
+    let checker = CheckerTexture::from_colors(0.5, &white, &black);
+
+    assert!(same(checker.value(0.0, 0.0, &Point3::new(0.25, 0.25, 0.25)), white));
+    assert!(same(checker.value(0.0, 0.0, &Point3::new(0.75, 0.25, 0.25)), black));
+    assert!(same(checker.value(0.0, 0.0, &Point3::new(0.75, 0.75, 0.25)), white));
+    //负坐标的格子同样交替
+    assert!(same(checker.value(0.0, 0.0, &Point3::new(-0.25, 0.25, 0.25)), black));
+}
+
+#[test]
+fn sphere_hit_reports_spherical_uv() {
+    let material = Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5)));
+    let sphere = Sphere::new(Point3::new(0.0, 0.0, 0.0), 2.0, material);
+
+    //从+x方向射向球心，击中点(2, 0, 0)：u = 0.5，v = 0.5
+    let ray = Ray::new(Point3::new(5.0, 0.0, 0.0), Vector3::new(-1.0, 0.0, 0.0), 0.0);
+    let hit = sphere.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!((hit.u - 0.5).abs() < 1e-9 && (hit.v - 0.5).abs() < 1e-9);
+
+    //从上方射下，击中北极点：v = 1
+    let ray = Ray::new(Point3::new(0.0, 5.0, 0.0), Vector3::new(0.0, -1.0, 0.0), 0.0);
+    let hit = sphere.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!((hit.v - 1.0).abs() < 1e-9);
+
+    //击中(0, 0, 2)：u = 0.25
+    let ray = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+    let hit = sphere.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!((hit.u - 0.25).abs() < 1e-9);
+}
+
+#[test]
+fn image_texture_maps_v_upwards() {
+    //2x2的图片，第一行在最上面
+    let (red, green, blue, white) = (Color::new(1.0, 0.0, 0.0), Color::new(0.0, 1.0, 0.0), Color::new(0.0, 0.0, 1.0), Color::new(1.0, 1.0, 1.0));
+    let texture = ImageTexture::new(Image { width: 2, height: 2, pixels: vec![red, green, blue, white] });
+    let p = Point3::new(0.0, 0.0, 0.0);
+
+    assert!(same(texture.value(0.25, 0.75, &p), red));
+    assert!(same(texture.value(0.75, 0.75, &p), green));
+    assert!(same(texture.value(0.25, 0.25, &p), blue));
+    assert!(same(texture.value(1.0, 0.0, &p), white));
+}
+
+#[test]
+fn lambertian_albedo_comes_from_texture() {
+    let (white, black) = (Color::new(1.0, 1.0, 1.0), Color::black());
+    let checker = Arc::new(CheckerTexture::new(1.0, Arc::new(SolidColor::new(&white)), Arc::new(SolidColor::new(&black))));
+    let sphere = Sphere::new(Point3::new(0.0, 0.0, 0.0), 0.5, Arc::new(Lambertian::with_texture(checker)));
+    let mut sampler = Sampler::new(0);
+
+    //击中点(0.5, 0, 0)在白色格子，(-0.5, 0, 0)在黑色格子
+    for (origin, dir, expected) in [(5.0, -1.0, white), (-5.0, 1.0, black)] {
+        let ray = Ray::new(Point3::new(origin, 0.0, 0.0), Vector3::new(dir, 0.0, 0.0), 0.0);
+        let hit = sphere.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+        let scatter = hit.material.scatter(&ray, &hit, &mut sampler).unwrap();
+        assert!(same(scatter.attenuation, expected));
+    }
+}
+
+#[test]
+fn perlin_noise_is_seedable() {
+    let points: Vec<Point3> = (0..50).map(|i| Point3::new(i as f64 * 0.37, i as f64 * 0.11 - 2.0, 1.5 - i as f64 * 0.23)).collect();
+    let (a, b, c) = (Perlin::with_seed(7), Perlin::with_seed(7), Perlin::with_seed(8));
+
+    assert!(points.iter().all(|p| a.noise(p) == b.noise(p)));
+    assert!(points.iter().any(|p| a.noise(p) != c.noise(p)));
+
+    for p in &points {
+        assert!(a.noise(p).abs() <= 1.0);
+        assert!(a.turbulence(p, 7) >= 0.0);
+    }
+
+    //整数格点上噪声为0
+    assert_eq!(a.noise(&Point3::new(3.0, -2.0, 5.0)), 0.0);
+}
+
+#[test]
+fn noise_textures_stay_between_their_colors() {
+    let noise = Arc::new(Perlin::with_seed(0));
+    let (dark, light) = (Color::new(0.1, 0.2, 0.3), Color::new(0.9, 0.8, 0.7));
+    let textures: Vec<Box<dyn Texture>> = vec![
+        Box::new(NoiseTexture::new(noise.clone(), 4.0)),
+        Box::new(MarbleTexture::new(noise.clone(), 4.0, 10.0, &dark, &light)),
+        Box::new(WoodTexture::new(noise, 8.0, 1.0, &light, &dark)),
+    ];
+
+    for i in 0..100 {
+        let p = Point3::new(i as f64 * 0.13, 0.5, i as f64 * -0.07);
+        for texture in &textures {
+            let c = texture.value(0.0, 0.0, &p);
+            assert!((0.0..=1.0).contains(&c.f64_r()) && (0.0..=1.0).contains(&c.f64_b()));
+        }
+    }
+}
+
+#[test]
+fn metal_fuzz_comes_from_texture() {
+    //上半球光滑、下半球完全模糊
+    let checker = Arc::new(CheckerTexture::from_colors(1.0, &Color::black(), &Color::new(1.0, 1.0, 1.0)));
+    let albedo = Arc::new(SolidColor::new(&Color::new(1.0, 1.0, 1.0)));
+    let sphere = Sphere::new(Point3::new(0.5, 0.0, 0.5), 0.25, Arc::new(Metal::with_textures(albedo, checker)));
+    let mut sampler = Sampler::new(0);
+
+    let ray = Ray::new(Point3::new(0.5, 5.0, 0.5), Vector3::new(0.0, -1.0, 0.0), 0.0);
+    let hit = sphere.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    let scatter = hit.material.scatter(&ray, &hit, &mut sampler).unwrap();
+    assert!((scatter.scattered.dir.unit().y - 1.0).abs() < 1e-9);
+
+    let ray = Ray::new(Point3::new(0.5, -5.0, 0.5), Vector3::new(0.0, 1.0, 0.0), 0.0);
+    let hit = sphere.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    let directions: Vec<Vector3> = (0..20).filter_map(|_| hit.material.scatter(&ray, &hit, &mut sampler)).map(|s| s.scattered.dir.unit()).collect();
+    assert!(directions.iter().any(|d| (d.y + 1.0).abs() > 1e-3));
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use std::sync::Arc;
+
+use render::cuboid::Cuboid;
+use render::hittable::Hittable;
+use render::instance::Instance;
+use render::matrix::{Matrix4, Transform};
+use render::ray::Ray;
+use render::sphere::Sphere;
+use render::utils::Sampler;
+use render::vector::{Point3, Vector3};
+
+use common::{gray, near};
+
+#[test]
+fn matrix_inverse_round_trips() {
+    let m = Matrix4::new([
+        [2.0, 0.0, 1.0, 3.0],
+        [0.0, 1.0, 4.0, -1.0],
+        [1.0, 0.0, 1.0, 2.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+    let product = m * m.inverse().unwrap();
+    for i in 0..4 {
+        for j in 0..4 {
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!((product.m[i][j] - expected).abs() < 1e-12);
+        }
+    }
+
+    //奇异矩阵没有逆
+    let singular = Matrix4::new([
+        [1.0, 2.0, 3.0, 0.0],
+        [2.0, 4.0, 6.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+    assert!(singular.inverse().is_none());
+    assert!(Transform::new(singular).is_none());
+}
+
+#[test]
+fn transforms_compose_in_order() {
+    //先绕z轴转90度再平移，与先平移再旋转结果不同
+    let rotate_then_move = Transform::rotate_z(90.0).then(&Transform::translate(&Vector3::new(1.0, 0.0, 0.0)));
+    let p = rotate_then_move.point(&Point3::new(1.0, 0.0, 0.0));
+    assert!(near(&p, &Point3::new(1.0, 1.0, 0.0)));
+
+    let move_then_rotate = Transform::translate(&Vector3::new(1.0, 0.0, 0.0)).then(&Transform::rotate_z(90.0));
+    let p = move_then_rotate.point(&Point3::new(1.0, 0.0, 0.0));
+    assert!(near(&p, &Point3::new(0.0, 2.0, 0.0)));
+
+    //方向不受平移影响
+    let v = rotate_then_move.vector(&Vector3::new(1.0, 0.0, 0.0));
+    assert!(near(&v, &Vector3::new(0.0, 1.0, 0.0)));
+
+    //逆变换把点变回原处
+    let p = Point3::new(0.3, -2.0, 5.0);
+    let back = rotate_then_move.inverse().point(&rotate_then_move.point(&p));
+    assert!(near(&back, &p));
+}
+
+#[test]
+fn normals_stay_perpendicular_under_non_uniform_scale() {
+    let transform = Transform::scale(&Vector3::new(4.0, 1.0, 1.0));
+
+    //平面x+y=0上的切向量和法线
+    let tangent = Vector3::new(1.0, -1.0, 0.0);
+    let normal = Vector3::new(1.0, 1.0, 0.0);
+
+    let tangent = transform.vector(&tangent);
+    let normal = transform.normal(&normal);
+    assert!(tangent.dot(&normal).abs() < 1e-12);
+    //直接用矩阵变换法线会得到错误的结果
+    assert!(tangent.dot(&transform.vector(&Vector3::new(1.0, 1.0, 0.0))).abs() > 1.0);
+}
+
+#[test]
+fn instance_hits_transformed_object() {
+    let material = gray();
+    let sphere: Arc<dyn Hittable> = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, material));
+
+    //沿x轴拉长为半轴2的椭球，再平移到(0,0,-5)
+    let transform = Transform::scale(&Vector3::new(2.0, 1.0, 1.0)).then(&Transform::translate(&Vector3::new(0.0, 0.0, -5.0)));
+    let instance = Instance::new(sphere.clone(), transform);
+
+    //从+x方向射向椭球的端点
+    let ray = Ray::new(Point3::new(10.0, 0.0, -5.0), Vector3::new(-1.0, 0.0, 0.0), 0.0);
+    let hit = instance.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!((hit.t - 8.0).abs() < 1e-9);
+    assert!(near(&hit.pos, &Point3::new(2.0, 0.0, -5.0)));
+    assert!(near(&hit.normal, &Vector3::new(1.0, 0.0, 0.0)));
+    assert!(hit.front_face);
+
+    //原来的位置已经没有物体
+    let ray = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0), 0.0);
+    let hit = instance.hit(&ray, 0.001, 8.0, &mut Sampler::new(0));
+    assert!(hit.is_none());
+
+    //椭球斜面上的法线经过逆转置变换后垂直于表面
+    let ray = Ray::new(Point3::new(2.0f64.sqrt(), 5.0, -5.0), Vector3::new(0.0, -1.0, 0.0), 0.0);
+    let hit = instance.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    let expected = Vector3::new(1.0, 2.0, 0.0).unit();
+    assert!(near(&hit.normal, &expected));
+
+    let bbox = instance.bounding_box(0.0, 1.0).unwrap();
+    assert!(near(&bbox.minimum, &Point3::new(-2.0, -1.0, -6.0)));
+    assert!(near(&bbox.maximum, &Point3::new(2.0, 1.0, -4.0)));
+}
+
+#[test]
+fn rotated_box_bounding_box_covers_corners() {
+    let material = gray();
+    let cuboid = Arc::new(Cuboid::new(&Point3::new(0.0, 0.0, 0.0), &Point3::new(1.0, 1.0, 1.0), material));
+    let instance = Instance::new(cuboid, Transform::rotate_y(45.0));
+
+    let half_diagonal = 2.0f64.sqrt() / 2.0;
+    let bbox = instance.bounding_box(0.0, 1.0).unwrap();
+    assert!(bbox.minimum.x.abs() < 1e-3 && (bbox.maximum.x - 2.0 * half_diagonal).abs() < 1e-3);
+    assert!((bbox.minimum.z + half_diagonal).abs() < 1e-3 && (bbox.maximum.z - half_diagonal).abs() < 1e-3);
+
+    //从上方射向旋转后的顶面中心
+    let ray = Ray::new(Point3::new(half_diagonal, 5.0, 0.0), Vector3::new(0.0, -1.0, 0.0), 0.0);
+    let hit = instance.hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).unwrap();
+    assert!((hit.t - 4.0).abs() < 1e-9);
+    assert!(near(&hit.normal, &Vector3::new(0.0, 1.0, 0.0)));
+}
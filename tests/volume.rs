@@ -0,0 +1,234 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use render::aarect::XzRect;
+use render::background::SolidBackground;
+use render::camera::{Camera, MisHeuristic, RenderSettings};
+use render::color::Color;
+use render::constant_medium::ConstantMedium;
+use render::cuboid::Cuboid;
+use render::directional_light::DirectionalLight;
+use render::grid_volume::GridVolume;
+use render::hittable::Hittable;
+use render::lambertian::Lambertian;
+use render::import::{parse_raw_voxels, parse_vgrid, ImportError};
+use render::ray::Ray;
+use render::utils::Sampler;
+use render::vector::{Point3, Vector3};
+use render::voxel_grid::VoxelGrid;
+use render::world::World;
+
+fn vgrid(format: &str, resolution: &str, body: &[u8]) -> Vec<u8> {
+    let mut data = format!("vgrid\ncomment test grid\nresolution {}\nformat {}\nend_header\n", resolution, format).into_bytes();
+    data.extend_from_slice(body);
+    data
+}
+
+fn constant_volume(density: f32, absorption: f64, scattering: f64) -> GridVolume {
+    let grid = Arc::new(VoxelGrid::new([1, 1, 1], vec![density]).unwrap());
+    GridVolume::new(grid, &Point3::new(0.0, 0.0, 0.0), &Point3::new(1.0, 1.0, 2.0), absorption, scattering)
+}
+
+#[test]
+fn grid_interpolates_between_voxel_centers() {
+    let grid = VoxelGrid::new([2, 1, 1], vec![1.0, 3.0]).unwrap();
+    assert_eq!(grid.max(), 3.0);
+
+    //体素中心取体素值，两个中心之间线性插值，边界上的半个体素取最近的体素值
+    assert!((grid.sample(&Point3::new(0.25, 0.5, 0.5)) - 1.0).abs() < 1e-12);
+    assert!((grid.sample(&Point3::new(0.5, 0.5, 0.5)) - 2.0).abs() < 1e-12);
+    assert!((grid.sample(&Point3::new(0.75, 0.1, 0.9)) - 3.0).abs() < 1e-12);
+    assert!((grid.sample(&Point3::new(0.0, 0.5, 0.5)) - 1.0).abs() < 1e-12);
+    assert!((grid.sample(&Point3::new(1.0, 0.5, 0.5)) - 3.0).abs() < 1e-12);
+    assert_eq!(grid.sample(&Point3::new(1.01, 0.5, 0.5)), 0.0);
+
+    //三个方向同时插值
+    let values: Vec<f32> = (0..8).map(|i| i as f32).collect();
+    let grid = VoxelGrid::new([2, 2, 2], values).unwrap();
+    assert_eq!(grid.voxel(1, 0, 1), 5.0);
+    assert!((grid.sample(&Point3::new(0.5, 0.5, 0.5)) - 3.5).abs() < 1e-12);
+    assert!((grid.sample(&Point3::new(0.5, 0.25, 0.75)) - (0.5 + 0.0 + 4.0)).abs() < 1e-12);
+
+    assert!(VoxelGrid::new([2, 2, 2], vec![1.0; 7]).unwrap_err().contains("needs 8 values"));
+    assert!(VoxelGrid::new([0, 2, 2], vec![]).is_err());
+    assert!(VoxelGrid::new([1, 1, 2], vec![1.0, -1.0]).unwrap_err().contains("voxel 1"));
+}
+
+#[test]
+fn reads_vgrid_and_raw_files() {
+    let values = [0.0f32, 0.5, 1.0, 2.0, 0.25, 4.0];
+    let le: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    let be: Vec<u8> = values.iter().flat_map(|v| v.to_be_bytes()).collect();
+    let ascii = b"0 0.5 1\n2 0.25\n4\n";
+
+    let grids = [
+        parse_vgrid(&vgrid("binary_little_endian", "3 2 1", &le), Path::new("le.vgrid")).unwrap(),
+        parse_vgrid(&vgrid("binary_big_endian", "3 2 1", &be), Path::new("be.vgrid")).unwrap(),
+        parse_vgrid(&vgrid("ascii", "3 2 1", ascii), Path::new("ascii.vgrid")).unwrap(),
+        parse_raw_voxels(&le, Path::new("grid.raw"), [3, 2, 1]).unwrap(),
+    ];
+    for grid in &grids {
+        assert_eq!(grid.resolution(), [3, 2, 1]);
+        assert_eq!(grid.voxel(2, 0, 0), 1.0);
+        assert_eq!(grid.voxel(0, 1, 0), 2.0);
+        assert_eq!(grid.max(), 4.0);
+    }
+}
+
+#[test]
+fn reports_voxel_file_errors() {
+    let parse_error = |data: &[u8]| match parse_vgrid(data, Path::new("smoke.vgrid")) {
+        Err(ImportError::Parse { line, message, .. }) => (line, message),
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("grid should not load"),
+    };
+    let data_error = |result: Result<VoxelGrid, ImportError>| match result {
+        Err(ImportError::Data { offset, message, .. }) => (offset, message),
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("grid should not load"),
+    };
+
+    assert_eq!(parse_error(b"ply\n").0, 1);
+    let (line, message) = parse_error(b"vgrid\nresolution 2 0 2\nformat ascii\nend_header\n");
+    assert_eq!(line, 2);
+    assert!(message.contains("bad grid resolution"), "{}", message);
+    let (line, message) = parse_error(b"vgrid\nformat ascii\nend_header\n");
+    assert_eq!(line, 3);
+    assert!(message.contains("missing resolution"), "{}", message);
+
+    //体素个数溢出的分辨率在文件头中报告，不会溢出或按它分配内存
+    let (line, message) = parse_error(b"vgrid\nresolution 100000000 100000000 100000000\nformat ascii\nend_header\n");
+    assert_eq!(line, 2);
+    assert!(message.contains("too large"), "{}", message);
+    let (line, message) = parse_error(&vgrid("ascii", "100000 100000 100000", b"1 2\n"));
+    assert_eq!(line, 6);
+    assert!(message.contains("expected 1000000000000000 voxel values, got 2"), "{}", message);
+
+    //ascii数据报告出错的行
+    let (line, message) = parse_error(&vgrid("ascii", "2 1 1", b"1\n-2\n"));
+    assert_eq!(line, 7);
+    assert!(message.contains("non-negative"), "{}", message);
+    let (line, message) = parse_error(&vgrid("ascii", "2 2 1", b"1 2\n3\n"));
+    assert_eq!(line, 7);
+    assert!(message.contains("expected 4 voxel values, got 3"), "{}", message);
+
+    //二进制数据报告字节偏移
+    let header_len = vgrid("binary_little_endian", "2 1 1", b"").len() as u64;
+    let (offset, message) = data_error(parse_vgrid(&vgrid("binary_little_endian", "2 1 1", &[0; 7]), Path::new("smoke.vgrid")));
+    assert_eq!(offset, header_len);
+    assert!(message.contains("needs 8 bytes"), "{}", message);
+
+    let (_, message) = data_error(parse_raw_voxels(&[0; 8], Path::new("smoke.raw"), [usize::MAX, 2, 1]));
+    assert!(message.contains("too large"), "{}", message);
+    assert!(VoxelGrid::new([usize::MAX, 2, 1], vec![]).unwrap_err().contains("too large"));
+
+    let nan: Vec<u8> = [1.0f32, f32::NAN].iter().flat_map(|v| v.to_le_bytes()).collect();
+    let (offset, _) = data_error(parse_raw_voxels(&nan, Path::new("smoke.raw"), [2, 1, 1]));
+    assert_eq!(offset, 4);
+}
+
+#[test]
+fn delta_and_ratio_tracking_match_beer_lambert() {
+    //密度为0.5，消光系数1.2·0.5，沿z轴穿过长度为2的介质
+    let volume = constant_volume(0.5, 0.4, 0.8);
+    let ray = Ray::new(Point3::new(0.5, 0.5, -3.0), Vector3::new(0.0, 0.0, 0.5), 0.0);
+    let expected = (-1.2 * 0.5 * 2.0f64).exp();
+
+    let mut sampler = Sampler::new(21);
+    let count = 20000;
+    let mut passed = 0;
+    let mut scattered = 0;
+    let mut transmittance = 0.0;
+    for _ in 0..count {
+        match volume.hit(&ray, 0.001, f64::INFINITY, &mut sampler) {
+            Some(hit) => {
+                assert!(hit.pos.z >= 0.0 && hit.pos.z <= 2.0);
+                if hit.material.scatter(&ray, &hit, &mut sampler).is_some() {
+                    scattered += 1;
+                }
+            }
+            None => passed += 1,
+        }
+        transmittance += volume.transmittance(&ray, 0.001, f64::INFINITY, &mut sampler);
+    }
+
+    assert!((passed as f64 / count as f64 - expected).abs() < 0.015);
+    assert!((transmittance / count as f64 - expected).abs() < 0.015);
+    //真实碰撞中散射与吸收的比例为0.8 : 0.4
+    let collided = (count - passed) as f64;
+    assert!((scattered as f64 / collided - 2.0 / 3.0).abs() < 0.02);
+}
+
+#[test]
+fn tracking_is_unbiased_in_heterogeneous_grids() {
+    let values: Vec<f32> = (0..64).map(|i| ((i * 37 % 11) as f32) / 5.0).collect();
+    let grid = Arc::new(VoxelGrid::new([4, 4, 4], values).unwrap());
+    let volume = GridVolume::new(grid, &Point3::new(-1.0, -1.0, -1.0), &Point3::new(1.0, 1.0, 1.0), 0.3, 0.7);
+
+    let origin = Point3::new(-2.0, -0.3, 0.2);
+    let target = Point3::new(2.0, 0.5, -0.4);
+    let ray = Ray::new(origin, target - origin, 0.0);
+
+    //数值积分得到光学厚度
+    let steps = 100000;
+    let optical_depth: f64 = (0..steps)
+        .map(|i| volume.density(&ray.at((i as f64 + 0.5) / steps as f64)))
+        .sum::<f64>() * (target - origin).length() / steps as f64;
+    let expected = (-optical_depth).exp();
+
+    let mut sampler = Sampler::new(4);
+    let count = 20000;
+    let passed = (0..count).filter(|_| volume.hit(&ray, 0.001, f64::INFINITY, &mut sampler).is_none()).count();
+    let ratio: f64 = (0..count).map(|_| volume.transmittance(&ray, 0.001, f64::INFINITY, &mut sampler)).sum();
+
+    assert!((passed as f64 / count as f64 - expected).abs() < 0.015, "{} vs {}", passed as f64 / count as f64, expected);
+    assert!((ratio / count as f64 - expected).abs() < 0.01, "{} vs {}", ratio / count as f64, expected);
+}
+
+#[test]
+fn emission_integrates_along_the_ray() {
+    //只有吸收：沿光线积分emission·density·T得到emission / absorption · (1 - exp(-absorption·density·L))
+    let mut volume = constant_volume(1.0, 0.5, 0.0);
+    volume.set_emission(&Color::new(2.0, 1.0, 0.0));
+    let ray = Ray::new(Point3::new(0.5, 0.5, -3.0), Vector3::new(0.0, 0.0, 1.0), 0.0);
+
+    let mut sampler = Sampler::new(8);
+    let count = 20000;
+    let mut total = 0.0;
+    for _ in 0..count {
+        if let Some(hit) = volume.hit(&ray, 0.001, f64::INFINITY, &mut sampler) {
+            assert!(hit.material.scatter(&ray, &hit, &mut sampler).is_none());
+            total += hit.material.emitted(&ray, &hit).f64_r();
+        }
+    }
+
+    let expected = 2.0 / 0.5 * (1.0 - (-0.5f64 * 2.0).exp());
+    assert!((total / count as f64 - expected).abs() < 0.05, "{} vs {}", total / count as f64, expected);
+}
+
+#[test]
+fn shadow_rays_take_the_transmittance_of_media() {
+    //不散射的介质板挡在平行光和地面之间，阴影射线不在介质中碰撞，而是乘上透射率exp(-density·厚度)
+    let black = Color::black();
+    let boundary = Arc::new(Cuboid::new(&Point3::new(-50.0, 2.0, -50.0), &Point3::new(50.0, 2.5, 50.0), Arc::new(Lambertian::new(&black))));
+    let medium = ConstantMedium::with_color(boundary, 1.2, &black);
+    let ray = Ray::new(Point3::new(0.0, 5.0, 0.0), Vector3::new(0.0, -2.0, 0.0), 0.0);
+    assert!(medium.surface_hit(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)).is_none());
+    assert!((medium.transmittance(&ray, 0.001, f64::INFINITY, &mut Sampler::new(0)) - (-0.6f64).exp()).abs() < 1e-9);
+
+    let mut world = World::new();
+    world.add(Box::new(XzRect::new(-50.0, 50.0, -50.0, 50.0, 0.0, Arc::new(Lambertian::new(&Color::new(0.5, 0.5, 0.5))))));
+    world.add(Box::new(medium));
+    world.add_delta_light(Box::new(DirectionalLight::new(&Vector3::new(0.0, -1.0, 0.0), &Color::new(1.0, 1.0, 1.0), 2.0)));
+
+    let mut camera = Camera::new(Point3::new(1.0, 1.0, 0.0), Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0),
+        30.0, 1.0, 0.0, 1.0, 0.0, 1.0);
+    camera.set_background(Arc::new(SolidBackground::new(&black)));
+    let settings = RenderSettings { image_width: 8, image_height: 8, samples_per_pixel: 4, max_depth: 2, threads: 1, seed: 3, mis: MisHeuristic::Power };
+
+    //透射率是解析计算的，每个像素都没有噪点
+    let expected = 0.5 / std::f64::consts::PI * 2.0 * (-0.6f64).exp();
+    for c in camera.take_photo(&world, &settings) {
+        assert!((c.f64_g() - expected).abs() < 1e-9, "{} vs {}", c.f64_g(), expected);
+    }
+}